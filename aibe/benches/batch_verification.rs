@@ -0,0 +1,90 @@
+#[macro_use]
+extern crate criterion;
+use criterion::{BenchmarkId, Criterion};
+
+use aibe::traits::IdentityBasedEncryption;
+use aibe::bf_ibe::{BFIbe, pairing};
+use aibe::utils::{u64_to_scalar, hash_to_g2, pedersen_commitment};
+use aibe::zk::nonce;
+use aibe::zk::transfer::{TransferStatement, TransferWitness, TransferProver, TransferVerifier, FullTransferProof};
+
+/// A proven transfer of 40 out of a balance of 60.
+fn transfer_instance() -> (TransferStatement, FullTransferProof) {
+    let mut rng = rand::thread_rng();
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let (msk1, mpk1) = ibe.generate_key();
+    let (_, mpk2) = ibe.generate_key();
+    let sk1 = ibe.extract("zico1", &msk1);
+
+    let b_star = u64_to_scalar(40);
+    let b_prime = u64_to_scalar(20);
+    let c_balance = ibe.encrypt(&u64_to_scalar(60), "zico1", &mpk1);
+    let ((c_transfer, c_transfer_bar), _, r) =
+        ibe.encrypt_correlated_internal(&b_star, ("zico1", "zico2"), (&mpk1, &mpk2));
+
+    let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng);
+    let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+    let statement = TransferStatement {
+        y: mpk1,
+        y_bar: mpk2,
+        pk_id_bar: pairing(mpk2, hash_to_g2("zico2".as_bytes())),
+        c1: c_transfer.0,
+        c2: c_transfer.1,
+        c2_bar: c_transfer_bar.1,
+        c1_tilde: c_balance.0 - c_transfer.0,
+        c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
+        c_b_star,
+        c_b_prime,
+        epoch: 0,
+        u: nonce(&sk1, 0),
+    };
+    let witness = TransferWitness {
+        r,
+        s: msk1,
+        r_star,
+        r_prime,
+        b_star,
+        b_prime,
+        h_id: hash_to_g2("zico1".as_bytes()),
+        h_id_bar: hash_to_g2("zico2".as_bytes()),
+        sk_id: sk1,
+    };
+
+    let proof = TransferProver::new(rng).generate_full_proof(statement.clone(), witness).unwrap();
+    (statement, proof)
+}
+
+/// Verify `n` transfers one by one and as one batch. Divide by `n` for the cost per proof.
+fn verify_transfers(c: &mut Criterion) {
+    let batch = (0..16).map(|_| transfer_instance()).collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("verify transfers one by one");
+    for n in [1, 4, 16] {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &batch[..n], |b, batch| {
+            b.iter(|| {
+                for (statement, proof) in batch.iter().cloned() {
+                    TransferVerifier::verify_full_proof(statement, proof).unwrap();
+                }
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("verify transfers as a batch");
+    for n in [1, 4, 16] {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &batch[..n], |b, batch| {
+            b.iter(|| TransferVerifier::verify_batch(batch).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = batch_verification;
+    config = Criterion::default().sample_size(10);
+    targets = verify_transfers,
+}
+
+criterion_main!(batch_verification);
@@ -2,9 +2,11 @@ use crate::errors::IbeError;
 use rand::Rng;
 use bn::arith::U256;
 use borsh::maybestd::collections::HashMap;
+use borsh::maybestd::vec::Vec;
 use crate::traits::IdentityBasedEncryption;
 use crate::traits::ToBytes;
-use crate::utils::{u64_to_scalar, hash_to_g2, baby_step_giant_step};
+use crate::dlog::DlogTable;
+use crate::utils::{i128_to_scalar, hash_to_g2};
 
 pub use bn::{G1, G2, Gt, Fr, Group, pairing};
 
@@ -15,6 +17,9 @@ pub type MasterSecretKey = Scalar;
 pub type MasterPublicKey = G1;
 pub type IdSecretKey = G2;
 pub type CipherTextPair = (G1, Gt, Gt);
+/// The ciphertexts of the sender and of every recipient of a multi-recipient transfer, the hashes of
+/// their identities and the shared randomness, see [`BFIbe::encrypt_multi_internal`].
+pub type MultiCipherText = ((CipherText, Vec<CipherText>), (G2, Vec<G2>), Scalar);
 
 
 /// Dan Boneh and Matthew K. Franklin. Identity-based encryption from the weil pairing. SIAM J. Comput., 32(3):586{615, 2003.
@@ -72,6 +77,53 @@ where R: Rng {
 		((cipher_1, cipher_2), (hash_id1, hash_id2), r)
     }
 
+    /// Encrypt `msgs[i]` for `recipients[i]` with one shared randomness, together with the encryption
+    /// of the sum of `msgs` for the `sender`, which is the amount deducted from the sender's balance.
+    pub fn encrypt_multi_internal(&mut self, msgs: &[PlainData], sender: (&str, &MasterPublicKey), recipients: &[(&str, &MasterPublicKey)]) -> MultiCipherText {
+        let r = Scalar::random(&mut self.rng);
+        let c1 = G1::one() * r;
+        let g = pairing(G1::one(), G2::one());
+
+        let sum = msgs.iter().fold(Scalar::zero(), |acc, msg| acc + *msg);
+        let hash_id = hash_to_g2(sender.0.as_bytes());
+        let cipher = (c1, g.pow(sum) * pairing(*sender.1, hash_id * r));
+
+        let (ciphers, hash_ids) = msgs.iter().zip(recipients)
+            .map(|(msg, (id, mpk))| {
+                let hash_id = hash_to_g2(id.as_bytes());
+                ((c1, g.pow(*msg) * pairing(**mpk, hash_id * r)), hash_id)
+            })
+            .unzip();
+
+        ((cipher, ciphers), (hash_id, hash_ids), r)
+    }
+
+    /// Encrypt a transfer of `msg` from `pk_ids[sender]` to `pk_ids[receiver]` for every member of a
+    /// ring with one shared randomness: the sender gets `-msg`, the receiver `msg` and everybody else
+    /// zero. Returns the shared `c1`, the `c2` of every member and the randomness.
+    pub fn encrypt_ring_internal(&mut self, msg: &PlainData, pk_ids: &[Gt], sender: usize, receiver: usize) -> ((G1, Vec<Gt>), Scalar) {
+        let r = Scalar::random(&mut self.rng);
+        let c1 = G1::one() * r;
+        let g = pairing(G1::one(), G2::one());
+
+        let c2s = pk_ids.iter().enumerate()
+            .map(|(i, pk_id)| {
+                let c2 = pk_id.pow(r);
+                if i == sender {
+                    c2 * g.pow(-*msg)
+                }
+                else if i == receiver {
+                    c2 * g.pow(*msg)
+                }
+                else {
+                    c2
+                }
+            })
+            .collect();
+
+        ((c1, c2s), r)
+    }
+
     /// Convert a master secret key to its master public key.
     pub fn msk_to_mpk(msk: &MasterSecretKey) -> MasterPublicKey {
 		let mpk = G1::one() * *msk; 
@@ -93,6 +145,20 @@ where R: Rng {
         Scalar::random(&mut self.rng)
     }
 
+    /// Decryption of a plaintext in `[-table.bound(), table.bound()]` with a prebuilt table of base
+    /// `pairing(G1::one(), G2::one())`.
+    pub fn decrypt_with_table(cipher: &CipherText, sk: &IdSecretKey, table: &DlogTable) -> Result<PlainData, IbeError> {
+        Self::decrypt_signed_with_table(cipher, sk, table).map(i128_to_scalar)
+    }
+
+    /// Signed decryption with a prebuilt table, see `decrypt_with_table`.
+    pub fn decrypt_signed_with_table(cipher: &CipherText, sk: &IdSecretKey, table: &DlogTable) -> Result<i128, IbeError> {
+        let (c1, c2) = cipher;
+        let result = pairing(*c1, *sk).inverse().ok_or(IbeError::GtInverseError)?;
+
+        table.solve(*c2 * result)
+    }
+
 }
 
 impl<R> IdentityBasedEncryption for BFIbe<R>
@@ -183,7 +249,7 @@ where R: Rng {
 		hash_id * *msk
     }
 
-    /// Decryption.
+    /// Decryption of a plaintext in `[-bound, bound]`.
     ///
     /// # Examples
     ///
@@ -193,11 +259,22 @@ where R: Rng {
     /// ```
 
     fn decrypt(&mut self, cipher: &Self::CipherText, id: &str, sk: &Self::IdSecretKey, bound: u64) -> Result<Self::PlainData, IbeError> {
-		let (c1, c2) = cipher;
-		let result = pairing(*c1, *sk).inverse().ok_or(IbeError::GtInverseError)?;
-		let result = *c2 * result;
+        self.decrypt_signed(cipher, id, sk, bound).map(i128_to_scalar)
+    }
+
+    /// Decryption of a plaintext in `[-bound, bound]` to a signed integer, e.g. to detect an overdrawn balance.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Following the examples of `encrypt` and `extract`
+    /// let balance = ibe.decrypt_signed("alice", &cipher, &sk, 100)?;
+    /// if balance < 0 { /* overdrawn */ }
+    /// ```
+    fn decrypt_signed(&mut self, cipher: &Self::CipherText, _id: &str, sk: &Self::IdSecretKey, bound: u64) -> Result<i128, IbeError> {
+        let table = DlogTable::new(pairing(G1::one(), G2::one()), bound);
 
-        baby_step_giant_step(result, pairing(G1::one(), G2::one()), bound)
+        Self::decrypt_signed_with_table(cipher, sk, &table)
     }
 
 }
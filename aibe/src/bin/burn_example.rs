@@ -3,6 +3,9 @@ use aibe::traits::{IdentityBasedEncryption};
 use aibe::bf_ibe::{BFIbe};
 use aibe::utils::{u64_to_scalar, hash_to_g2};
 use aibe::zk::burn::{BurnStatement, BurnWitness, BurnProver, BurnVerifier};
+use aibe::zk::nonce;
+use bn::Fr as Scalar;
+use bulletproofs::PedersenGens;
 use rand::Rng;
 use borsh::ser::BorshSerialize;
 
@@ -44,25 +47,34 @@ fn main() {
     assert_eq!(result.unwrap(), plain);
 
 
+    let r_b = Scalar::random(&mut rng);
     let statement = BurnStatement {
         y: mpk,
         c1_id: cipher.0,
         c2_id: cipher.1,
+        c_b: PedersenGens::default().commit(plain, r_b),
+        epoch: 0,
+        u: nonce(&sk, 0),
     };
     let witness = BurnWitness {
         b: plain,
         s: msk,
+        r_b,
         h_id: hash_to_g2("zico".as_bytes()),
         sk_id: sk,
     };
 
+    // The proof is bound to the account the funds are paid to, by its SCALE encoding, which for
+    // an `AccountId32` is its public key. This is the dev account Alice.
+    let destination = hex::decode("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d").unwrap();
+
     let mut prover = BurnProver::new(rng.clone());
-    let proof = prover.generate_proof(statement.clone(), witness);
+    let proof = prover.generate_full_proof(statement.clone(), witness, &destination).unwrap();
 
     println!("Burn proof:\n{}", base64::encode(proof.try_to_vec().unwrap()));
     println!("Burn statement:\n{}", base64::encode(statement.try_to_vec().unwrap()));
 
-    //let result = BurnVerifier::verify_proof(statement, proof);
+    //let result = BurnVerifier::verify_full_proof(statement, proof, &destination);
     //assert!(result.is_ok());
 }
 
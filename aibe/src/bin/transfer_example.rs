@@ -1,10 +1,10 @@
 
 use aibe::traits::{IdentityBasedEncryption};
-use aibe::bf_ibe::{BFIbe};
+use aibe::bf_ibe::{BFIbe, pairing};
 use aibe::utils::{u64_to_scalar, hash_to_g2, pedersen_commitment};
 use aibe::zk::transfer::{TransferStatement, TransferWitness, TransferProver, TransferVerifier};
+use aibe::zk::nonce;
 use rand::Rng;
-use bn::{G1, Group};
 use borsh::ser::BorshSerialize;
 
 extern crate base64;
@@ -42,18 +42,16 @@ fn main() {
     let elapsed = now.elapsed();
     println!("[IBE encrypt]: {:.2?}", elapsed);
 
-    let h1 = G1::random(&mut rng);
-
     let now = Instant::now();
-    let (r_star, c_b_star) = pedersen_commitment(b_star, h1, &mut rng); 
-    let (r_prime, c_b_prime) = pedersen_commitment(b_prime, h1, &mut rng); 
+    let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng); 
+    let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng); 
     let elapsed = now.elapsed();
     println!("[Pedersen commitment]: {:.2?}", elapsed);
 
     let statement = TransferStatement {
-        h1,
         y: mpk1,
         y_bar: mpk2,
+        pk_id_bar: pairing(mpk2, h_id_bar),
         c1: c_transfer.0,
         c2: c_transfer.1,
         c2_bar: c_transfer_bar.1,
@@ -61,6 +59,8 @@ fn main() {
         c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
         c_b_star,
         c_b_prime,
+        epoch: 0,
+        u: nonce(&sk1, 0),
     };
     let witness = TransferWitness {
         r,
@@ -75,12 +75,12 @@ fn main() {
     };
 
     let mut prover = TransferProver::new(rng.clone());
-    let proof = prover.generate_proof(statement.clone(), witness);
+    let proof = prover.generate_full_proof(statement.clone(), witness).unwrap();
 
     println!("Transfer proof:\n{}", base64::encode(proof.try_to_vec().unwrap()));
     println!("Transfer statement:\n{}", base64::encode(statement.try_to_vec().unwrap()));
 
-    //let result = TransferVerifier::verify_proof(statement, proof);
+    //let result = TransferVerifier::verify_full_proof(statement, proof);
     //assert!(result.is_ok());
 }
 
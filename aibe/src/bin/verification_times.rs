@@ -0,0 +1,166 @@
+//! Prints the native verification time of every proof the faceless pallet verifies, for the
+//! sizes its weights are fitted to, the time of encrypting a deposit, which needs no proof, and the
+//! time of reading the spendable balance of an account with a pending balance.
+//! See `pallets/faceless/src/weights.rs`.
+
+use aibe::traits::IdentityBasedEncryption;
+use aibe::bf_ibe::{BFIbe, G1, G2, Gt, Fr, Group, pairing};
+use aibe::utils::{u64_to_scalar, hash_to_g2, pedersen_commitment};
+use aibe::zk::nonce;
+use aibe::zk::burn::{BurnStatement, BurnWitness, BurnProver, BurnVerifier};
+use aibe::zk::register::{RegisterStatement, RegisterWitness, RegisterProver, RegisterVerifier};
+use aibe::zk::transfer::{TransferStatement, TransferWitness, TransferProver, TransferVerifier, FullTransferProof};
+use aibe::zk::multi_transfer::{MultiTransferStatement, MultiTransferWitness, MultiTransferProver, MultiTransferVerifier};
+use aibe::zk::ring_transfer::{RingTransferStatement, RingTransferWitness, RingTransferProver, RingTransferVerifier};
+use bulletproofs::PedersenGens;
+use rand::rngs::ThreadRng;
+use std::time::Instant;
+
+const REPEAT: u32 = 5;
+
+/// The mean time of `REPEAT` runs of `f`, in milliseconds.
+fn time(mut f: impl FnMut()) -> f64 {
+    let now = Instant::now();
+    for _ in 0..REPEAT {
+        f();
+    }
+    now.elapsed().as_secs_f64() * 1000.0 / REPEAT as f64
+}
+
+/// A statement and proof for transferring 40 out of a balance of 60 between two fresh identities.
+fn transfer(ibe: &mut BFIbe<ThreadRng>, rng: &mut ThreadRng, i: usize) -> (TransferStatement, FullTransferProof) {
+    let (id1, id2) = (format!("sender{}", i), format!("receiver{}", i));
+    let (msk1, mpk1) = ibe.generate_key();
+    let (_, mpk2) = ibe.generate_key();
+    let sk1 = ibe.extract(&id1, &msk1);
+
+    let c_balance = ibe.encrypt(&u64_to_scalar(60), &id1, &mpk1);
+    let b_star = u64_to_scalar(40);
+    let b_prime = u64_to_scalar(20);
+    let ((c_transfer, c_transfer_bar), (h_id, h_id_bar), r) =
+        ibe.encrypt_correlated_internal(&b_star, (&id1, &id2), (&mpk1, &mpk2));
+    let (r_star, c_b_star) = pedersen_commitment(b_star, rng);
+    let (r_prime, c_b_prime) = pedersen_commitment(b_prime, rng);
+
+    let statement = TransferStatement {
+        y: mpk1,
+        y_bar: mpk2,
+        pk_id_bar: pairing(mpk2, h_id_bar),
+        c1: c_transfer.0,
+        c2: c_transfer.1,
+        c2_bar: c_transfer_bar.1,
+        c1_tilde: c_balance.0 - c_transfer.0,
+        c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
+        c_b_star,
+        c_b_prime,
+        epoch: 0,
+        u: nonce(&sk1, 0),
+    };
+    let witness = TransferWitness { r, s: msk1, r_star, r_prime, b_star, b_prime, h_id, h_id_bar, sk_id: sk1 };
+    let proof = TransferProver::new(rng.clone()).generate_full_proof(statement.clone(), witness).unwrap();
+    (statement, proof)
+}
+
+fn main() {
+    let mut rng = rand::thread_rng();
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+
+    let statement = RegisterStatement { y: mpk, pk_id: BFIbe::<ThreadRng>::pk_id(&mpk, "zico") };
+    let witness = RegisterWitness { h_id: hash_to_g2("zico".as_bytes()), sk_id: sk };
+    let proof = RegisterProver::new(rng.clone()).generate_proof(statement.clone(), witness, b"context");
+    println!("register: {:.1} ms", time(|| RegisterVerifier::verify_proof(statement.clone(), proof.clone(), b"context").unwrap()));
+
+    println!("deposit: {:.1} ms", time(|| {
+        pairing(G1::one(), G2::one()).pow(u64_to_scalar(100));
+    }));
+
+    let balance = ibe.encrypt(&u64_to_scalar(60), "zico", &mpk);
+    let pending = ibe.encrypt(&u64_to_scalar(10), "zico", &mpk);
+    let stored = [balance, pending].map(|(c1, c2)| (c1.to_compressed(), c2.to_compressed()));
+    println!("balance: {:.1} ms", time(|| {
+        let [balance, pending] = stored.map(|(c1, c2)| (G1::from_compressed(&c1).unwrap(), Gt::from_compressed(&c2).unwrap()));
+        let (c1, c2) = BFIbe::<ThreadRng>::add_ciphers(&balance, &pending);
+        c1.to_compressed();
+        c2.to_compressed();
+    }));
+
+    let r_b = Fr::random(&mut rng);
+    let statement = BurnStatement {
+        y: mpk,
+        c1_id: balance.0,
+        c2_id: balance.1 * pairing(G1::one(), G2::one()).pow(-u64_to_scalar(25)),
+        c_b: PedersenGens::default().commit(u64_to_scalar(35), r_b),
+        epoch: 0,
+        u: nonce(&sk, 0),
+    };
+    let witness = BurnWitness { b: u64_to_scalar(35), s: msk, r_b, h_id: hash_to_g2("zico".as_bytes()), sk_id: sk };
+    let proof = BurnProver::new(rng.clone()).generate_full_proof(statement.clone(), witness, b"context").unwrap();
+    println!("burn: {:.1} ms", time(|| BurnVerifier::verify_full_proof(statement.clone(), proof.clone(), b"context").unwrap()));
+
+    let (statement, proof) = transfer(&mut ibe, &mut rng, 0);
+    println!("transfer: {:.1} ms", time(|| TransferVerifier::verify_full_proof(statement.clone(), proof.clone()).unwrap()));
+
+    for n in [1, 2, 4, 8, 16] {
+        let instances = (0..n).map(|i| transfer(&mut ibe, &mut rng, i)).collect::<Vec<_>>();
+        println!("transfer batch of {}: {:.1} ms", n, time(|| TransferVerifier::verify_batch(&instances).unwrap()));
+    }
+
+    for n in [1, 2, 4, 8, 16] {
+        let (msk, mpk) = ibe.generate_key();
+        let sk = ibe.extract("sender", &msk);
+        let c_balance = ibe.encrypt(&u64_to_scalar(60), "sender", &mpk);
+        let receivers = (0..n).map(|i| (format!("receiver{}", i), ibe.generate_key().1)).collect::<Vec<_>>();
+        let recipients = receivers.iter().map(|(id, mpk)| (id.as_str(), mpk)).collect::<Vec<_>>();
+
+        let b_stars = vec![u64_to_scalar(1); n];
+        let b_prime = u64_to_scalar(60 - n as u64);
+        let ((c_transfer, c_outputs), (h_id, h_id_bars), r) = ibe.encrypt_multi_internal(&b_stars, ("sender", &mpk), &recipients);
+        let (r_stars, c_b_stars) = b_stars.iter().map(|b_star| pedersen_commitment(*b_star, &mut rng)).unzip();
+        let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+        let statement = MultiTransferStatement {
+            y: mpk,
+            y_bars: receivers.iter().map(|(_, mpk)| *mpk).collect(),
+            pk_id_bars: receivers.iter().map(|(id, mpk)| BFIbe::<ThreadRng>::pk_id(mpk, id)).collect(),
+            c1: c_transfer.0,
+            c2: c_transfer.1,
+            c2_bars: c_outputs.iter().map(|c| c.1).collect(),
+            c1_tilde: c_balance.0 - c_transfer.0,
+            c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
+            c_b_stars,
+            c_b_prime,
+            epoch: 0,
+            u: nonce(&sk, 0),
+        };
+        let witness = MultiTransferWitness { r, s: msk, r_stars, r_prime, b_stars, b_prime, h_id, h_id_bars, sk_id: sk };
+        let proof = MultiTransferProver::new(rng.clone()).generate_full_proof(statement.clone(), witness).unwrap();
+        println!("multi-transfer to {}: {:.1} ms", n, time(|| MultiTransferVerifier::verify_full_proof(statement.clone(), proof.clone()).unwrap()));
+    }
+
+    for n in 2..=8 {
+        let mut pk_ids = Vec::new();
+        let mut sks = Vec::new();
+        let mut balances = Vec::new();
+        for i in 0..n {
+            let id = format!("member{}", i);
+            let (msk, mpk) = ibe.generate_key();
+            pk_ids.push(BFIbe::<ThreadRng>::pk_id(&mpk, &id));
+            sks.push(ibe.extract(&id, &msk));
+            balances.push(ibe.encrypt(&u64_to_scalar(60), &id, &mpk));
+        }
+
+        let b_star = u64_to_scalar(40);
+        let b_prime = u64_to_scalar(20);
+        let ((c1, c2s), r) = ibe.encrypt_ring_internal(&b_star, &pk_ids, 0, n - 1);
+        let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng);
+        let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+        let statement = RingTransferStatement { pk_ids, balances, c1, c2s, c_b_star, c_b_prime, epoch: 0, u: nonce(&sks[0], 0) };
+        let witness = RingTransferWitness { sender: 0, receiver: (n - 1) as u32, r, r_star, r_prime, b_star, b_prime, sk_id: sks[0] };
+        let proof = RingTransferProver::new(rng.clone()).generate_full_proof(statement.clone(), witness).unwrap();
+        println!("ring transfer in a ring of {}: {:.1} ms", n, time(|| RingTransferVerifier::verify_full_proof(statement.clone(), proof.clone()).unwrap()));
+    }
+}
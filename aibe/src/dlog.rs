@@ -0,0 +1,146 @@
+use crate::errors::IbeError;
+use crate::traits::ToBytes;
+use crate::utils::u64_to_scalar;
+use bn::Gt;
+use borsh::maybestd::collections::HashMap;
+use borsh::maybestd::vec::Vec;
+use borsh::BorshDeserialize;
+use libm::sqrt;
+use sha2::Digest;
+
+const MAGIC: &[u8; 8] = b"FLDLOG01";
+
+/// A baby-step giant-step table solving `h = g^x` for `x` in `[-bound, bound]`.
+///
+/// The table is built once per base and bound and can be saved with [`DlogTable::to_bytes`].
+/// Baby steps are keyed by a 64-bit truncated hash of their encoding, so the table of 32-bit
+/// balances takes about 512 KiB instead of 24 MiB. A hash match is always confirmed against
+/// the base before it is returned.
+///
+/// The serialized format is `MAGIC || bound || m || g || hash(g^0) || ... || hash(g^m)`, with
+/// integers and hashes in little endian and `g` Borsh encoded.
+#[derive(Clone, PartialEq)]
+pub struct DlogTable {
+    g: Gt,
+    bound: u64,
+    /// Number of baby steps minus one, which is also the giant step size.
+    m: u64,
+    table: HashMap<u64, u64>,
+}
+
+fn truncated_hash(x: &Gt) -> u64 {
+    let hash = sha2::Sha256::digest(x.to_bytes());
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn read_u64(bytes: &mut &[u8]) -> Result<u64, IbeError> {
+    if bytes.len() < 8 {
+        return Err(IbeError::InvalidDlogTable);
+    }
+    let (head, tail) = bytes.split_at(8);
+    *bytes = tail;
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(head);
+    Ok(u64::from_le_bytes(buf))
+}
+
+impl DlogTable {
+    /// Build the table for base `g` and plaintexts in `[-bound, bound]`.
+    pub fn new(g: Gt, bound: u64) -> Self {
+        let m = (sqrt(bound as f64) as u64) + 1;
+
+        let mut table = HashMap::with_capacity(m as usize + 1);
+        let mut x = Gt::one();
+        for i in 0..=m {
+            table.insert(truncated_hash(&x), i);
+            x = x * g;
+        }
+
+        Self { g, bound, m, table }
+    }
+
+    pub fn bound(&self) -> u64 {
+        self.bound
+    }
+
+    /// Solve `h = g^x` for `x` in `[-bound, bound]`.
+    pub fn solve(&self, h: Gt) -> Result<i128, IbeError> {
+        // search for solution, h * z^i for a positive and h^-1 * z^i for a negative one. The
+        // steps cover up to m * m + m, which may be past the bound, so larger solutions are
+        // skipped rather than returned.
+        let z = self.g.pow(-u64_to_scalar(self.m));
+        let mut x = h;
+        let mut x_neg = h.inverse().ok_or(IbeError::GtInverseError)?;
+        for i in 0..=self.m {
+            if let Some(j) = self.table.get(&truncated_hash(&x)) {
+                let candidate = i * self.m + j;
+                if candidate <= self.bound && self.g.pow(u64_to_scalar(candidate)) == h {
+                    return Ok(candidate as i128);
+                }
+            }
+            if let Some(j) = self.table.get(&truncated_hash(&x_neg)) {
+                let candidate = i * self.m + j;
+                if candidate <= self.bound && self.g.pow(-u64_to_scalar(candidate)) == h {
+                    return Ok(-(candidate as i128));
+                }
+            }
+            x = x * z;
+            x_neg = x_neg * z;
+        }
+        Err(IbeError::OutOfBoundError)
+    }
+
+    /// Serialize the table to its compact format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut hashes = self.table.iter().collect::<Vec<_>>();
+        hashes.sort_by_key(|(_, i)| **i);
+
+        let g = self.g.to_bytes();
+        let mut bytes = Vec::with_capacity(MAGIC.len() + 16 + g.len() + 8 * hashes.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&self.bound.to_le_bytes());
+        bytes.extend_from_slice(&self.m.to_le_bytes());
+        bytes.extend_from_slice(&g);
+        for (hash, _) in hashes {
+            bytes.extend_from_slice(&hash.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserialize a table produced by [`DlogTable::to_bytes`].
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, IbeError> {
+        if !bytes.starts_with(MAGIC) {
+            return Err(IbeError::InvalidDlogTable);
+        }
+        bytes = &bytes[MAGIC.len()..];
+
+        let bound = read_u64(&mut bytes)?;
+        let m = read_u64(&mut bytes)?;
+        let g = Gt::deserialize(&mut bytes).map_err(|_| IbeError::InvalidDlogTable)?;
+        if m != (sqrt(bound as f64) as u64) + 1 || (bytes.len() as u64) != 8 * (m + 1) {
+            return Err(IbeError::InvalidDlogTable);
+        }
+
+        let mut table = HashMap::with_capacity(m as usize + 1);
+        for i in 0..=m {
+            table.insert(read_u64(&mut bytes)?, i);
+        }
+
+        Ok(Self { g, bound, m, table })
+    }
+
+    /// Save the table to `path`.
+    #[cfg(feature = "std")]
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+
+    /// Load a table saved with [`DlogTable::save`].
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes).map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid dlog table"))
+    }
+}
@@ -0,0 +1,136 @@
+//! Compact Borsh encodings for group elements.
+//!
+//! Borsh serializes `G1` and `G2` as Jacobian coordinates and `Gt` as a full Fq12. The types
+//! here use the compressed encodings of `bn` instead (33 bytes for G1, 65 for G2 and 192 for
+//! Gt), which is what ciphertexts, statements and proofs are sent and stored as.
+
+use bn::{Fr, G1, G2, Gt};
+use borsh::maybestd::io::{Error, ErrorKind, Result, Write};
+use borsh::maybestd::vec::Vec;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Types with a compact encoding.
+pub trait Compress: Sized {
+    fn compress<W: Write>(&self, writer: &mut W) -> Result<()>;
+    fn decompress(buf: &mut &[u8]) -> Result<Self>;
+}
+
+fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if buf.len() < len {
+        return Err(Error::new(ErrorKind::InvalidData, "unexpected length of input"));
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+macro_rules! impl_compress_point {
+    ($t:ty, $len:expr) => {
+        impl Compress for $t {
+            fn compress<W: Write>(&self, writer: &mut W) -> Result<()> {
+                writer.write_all(&self.to_compressed())
+            }
+
+            fn decompress(buf: &mut &[u8]) -> Result<Self> {
+                <$t>::from_compressed(take(buf, $len)?)
+                    .map_err(|_| Error::new(ErrorKind::InvalidData, "invalid compressed encoding"))
+            }
+        }
+    };
+}
+
+impl_compress_point!(G1, 33);
+impl_compress_point!(G2, 65);
+impl_compress_point!(Gt, 192);
+
+impl Compress for Fr {
+    fn compress<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.serialize(writer)
+    }
+
+    fn decompress(buf: &mut &[u8]) -> Result<Self> {
+        Fr::deserialize(buf)
+    }
+}
+
+impl Compress for u64 {
+    fn compress<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.serialize(writer)
+    }
+
+    fn decompress(buf: &mut &[u8]) -> Result<Self> {
+        u64::deserialize(buf)
+    }
+}
+
+impl<A: Compress, B: Compress> Compress for (A, B) {
+    fn compress<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.compress(writer)?;
+        self.1.compress(writer)
+    }
+
+    fn decompress(buf: &mut &[u8]) -> Result<Self> {
+        Ok((A::decompress(buf)?, B::decompress(buf)?))
+    }
+}
+
+/// Vectors are prefixed with their length as a little endian `u32`, like in Borsh.
+impl<T: Compress> Compress for Vec<T> {
+    fn compress<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let len = u32::try_from(self.len()).map_err(|_| Error::new(ErrorKind::InvalidInput, "vector too long"))?;
+        len.serialize(writer)?;
+        for x in self {
+            x.compress(writer)?;
+        }
+        Ok(())
+    }
+
+    fn decompress(buf: &mut &[u8]) -> Result<Self> {
+        let len = u32::deserialize(buf)?;
+        // do not preallocate from an untrusted length
+        let mut v = Vec::new();
+        for _ in 0..len {
+            v.push(T::decompress(buf)?);
+        }
+        Ok(v)
+    }
+}
+
+/// A Borsh wrapper that serializes its content with the compact encoding, e.g.
+/// `Compressed(cipher).try_to_vec()` for a `CipherText`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Compressed<T>(pub T);
+
+impl<T: Compress> BorshSerialize for Compressed<T> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        self.0.compress(writer)
+    }
+}
+
+impl<T: Compress> BorshDeserialize for Compressed<T> {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self> {
+        T::decompress(buf).map(Compressed)
+    }
+}
+
+/// Implement Borsh for a struct by writing its fields, in order, with their compact encoding.
+macro_rules! impl_compressed_borsh {
+    ($name:ident { $($field:ident),* $(,)? }) => {
+        impl borsh::BorshSerialize for $name {
+            fn serialize<W: borsh::maybestd::io::Write>(&self, writer: &mut W) -> borsh::maybestd::io::Result<()> {
+                $( $crate::encoding::Compress::compress(&self.$field, writer)?; )*
+                Ok(())
+            }
+        }
+
+        impl borsh::BorshDeserialize for $name {
+            fn deserialize(buf: &mut &[u8]) -> borsh::maybestd::io::Result<Self> {
+                Ok(Self {
+                    $( $field: $crate::encoding::Compress::decompress(buf)?, )*
+                })
+            }
+        }
+    };
+}
+
+pub(crate) use impl_compressed_borsh;
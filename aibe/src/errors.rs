@@ -2,8 +2,10 @@
 pub enum IbeError {
     GtInverseError,
     OutOfBoundError,
+    InvalidDlogTable,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ZkError {
     ProofError,
     VerificationError,
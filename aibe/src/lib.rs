@@ -1,6 +1,11 @@
 #![no_std]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 pub mod bf_ibe;
+pub mod dlog;
+pub mod encoding;
 pub mod errors;
 pub mod traits;
 pub mod utils;
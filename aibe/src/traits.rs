@@ -17,6 +17,8 @@ pub trait IdentityBasedEncryption {
     fn encrypt_correlated(&mut self, msg: &Self::PlainData, ids: (&str, &str), mpks: (&Self::MasterPublicKey, &Self::MasterPublicKey)) -> (Self::CipherText, Self::CipherText);
     fn extract(&mut self, id: &str, msk: &Self::MasterSecretKey) -> Self::IdSecretKey;
     fn decrypt(&mut self, cipher: &Self::CipherText, id: &str, sk: &Self::IdSecretKey, bound: u64) -> Result<Self::PlainData, IbeError>;
+    /// Decrypt a plaintext in `[-bound, bound]` to a signed integer.
+    fn decrypt_signed(&mut self, cipher: &Self::CipherText, id: &str, sk: &Self::IdSecretKey, bound: u64) -> Result<i128, IbeError>;
 }
 
 pub trait ToBytes {
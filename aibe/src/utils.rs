@@ -1,14 +1,13 @@
 use crate::errors::IbeError;
+use crate::dlog::DlogTable;
 use rand::Rng;
-use bn::{G1, G2, Gt, Fr as Scalar, Group, pairing};
+use bn::{G1, G2, Gt, Fr as Scalar, pairing};
 use bn::arith::U256;
 use sha2::Digest;
-use borsh::maybestd::collections::HashMap;
 use crate::traits::IdentityBasedEncryption;
-use crate::traits::ToBytes;
-use libm::sqrt;
 use borsh::BorshSerialize;
 use borsh::BorshDeserialize;
+use bulletproofs::PedersenGens;
 
 
 pub fn hash_to_scalar(msg: &[u8]) -> Scalar {
@@ -16,8 +15,15 @@ pub fn hash_to_scalar(msg: &[u8]) -> Scalar {
     Scalar::new_mul_factor(U256::from_slice(&hash).unwrap())
 }
 
+/// Domain separation tag of the identity hash. The version must be bumped whenever the mapping
+/// changes: identities hashed under different versions map to unrelated points, so keys extracted
+/// under one version cannot decrypt balances encrypted under another.
+pub const IDENTITY_HASH_DST: &[u8] = b"FACELESS-IBE-V01-CS01-with-BN254G2_XMD:SHA-256_SVDW_RO_";
+
+/// Hash an identity to G2. Nobody, including the key generation center, learns the discrete log
+/// of the result.
 pub fn hash_to_g2(msg: &[u8]) -> G2 {
-    G2::one() * hash_to_scalar(msg) 
+    G2::hash_to_curve(msg, IDENTITY_HASH_DST)
 }
 
 pub fn u64_to_scalar(x: u64) -> Scalar {
@@ -40,56 +46,27 @@ pub fn i32_to_scalar(x: i32) -> Scalar {
     y
 }
 
-pub fn baby_step_giant_step(h: Gt, g: Gt, bound: u64) -> Result<Scalar, IbeError> {
-    let mut table = HashMap::new();
-
-    let m = (sqrt(bound as f64) as u64) + 1;
-
-    // precompute the table
-    let mut x = Gt::one();
-    let mut i = 0;
-    while i <= m {
-        table.insert(x.to_bytes(), i);
-        x = x * g;
-		i = i + 1;
+pub fn i128_to_scalar(x: i128) -> Scalar {
+    let magnitude = x.unsigned_abs();
+    let y = Scalar::new_mul_factor(U256::from([magnitude as u64, (magnitude >> 64) as u64, 0, 0]));
+    if x < 0 {
+        -y
+    } else {
+        y
     }
+}
 
-    // search for solution
-	let z = g.pow(-u64_to_scalar(m));
-    //z.inverse();
-    x = h;
-	i = 0;
-    while i <= m {
-        // positive solution
-        match table.get(&x.to_bytes()) {
-            Some(value) => {
-                let temp = i * m + value;
-                return Ok(u64_to_scalar(temp));
-            }
-            None => {
-                x = x * z;
-            }
-        }
-        // negative solution
-        //match table.get(&x_neg.tostring()) {
-            //Some(value) => {
-                //let mut temp = BigNum::modmul(&i, &m, &CURVE_ORDER);
-                //temp = BigNum::modadd(&value, &temp, &CURVE_ORDER);
-                //temp = BigNum::modneg(&temp, &CURVE_ORDER);
-                //let temp = BigInt::from_str_radix(&temp.tostring(), 16).unwrap() - (&*MODULUS);
-                //return Some(temp);
-            //}
-            //None => {
-                //x_neg.mul(&z);
-                //x_neg.reduce();
-            //}
-        //}
-        i = i + 1
-    }
-	Err(IbeError::OutOfBoundError)
+/// Solve `h = g^x` for `x` in `[-bound, bound]`.
+///
+/// Both signs are searched, so an encrypted balance that went negative (e.g. overdrawn) is
+/// reported as such instead of being unreadable. This builds a fresh [`DlogTable`]; build one
+/// once and reuse it when solving many instances.
+pub fn baby_step_giant_step(h: Gt, g: Gt, bound: u64) -> Result<i128, IbeError> {
+    DlogTable::new(g, bound).solve(h)
 }
 
-pub fn pedersen_commitment<R: Rng>(m: Scalar, h1: G1, rng: &mut R) -> (Scalar, G1) {
+/// Commit to `m` with the Pedersen generators shared with the range proofs, returning the blinding and the commitment.
+pub fn pedersen_commitment<R: Rng>(m: Scalar, rng: &mut R) -> (Scalar, G1) {
     let r = Scalar::random(rng);
-    (r, G1::one() * m + h1 * r)
+    (r, PedersenGens::default().commit(m, r))
 }
@@ -0,0 +1,67 @@
+//! Batch verification of the equations of sigma proofs.
+//!
+//! Every equation is raised to a random weight and all of them are multiplied together, so that
+//! many equations, possibly of many proofs, are checked with one multiexp in G1 and one
+//! multi-pairing with a single final exponentiation. A false equation makes the combined check
+//! fail except with negligible probability over the weights.
+
+use borsh::maybestd::vec::Vec;
+use bn::{Fr as Scalar, G1, G2, Gt, Group, pairing_batch};
+use rand_chacha::ChaCha20Rng;
+use crate::errors::ZkError;
+use crate::zk::verifier_rng;
+
+pub(crate) struct BatchCheck {
+    rng: ChaCha20Rng,
+    /// Weighted terms of the G1 equations, which sum up to zero.
+    g1_terms: Vec<(G1, Scalar)>,
+    /// Weighted pairings of the right-hand side of the Gt equations.
+    pairs: Vec<(G1, G2)>,
+    /// Weighted exponent of `e(G1::one(), G2::one())` on the right-hand side of the Gt equations.
+    gt_exp: Scalar,
+    /// Weighted product of the left-hand side of the Gt equations.
+    gt: Gt,
+}
+
+impl BatchCheck {
+    /// Create an empty check whose weights are derived from `seed`, which must cover every
+    /// statement and proof that is checked.
+    pub(crate) fn new(seed: &[u8]) -> Self {
+        Self {
+            rng: verifier_rng(seed),
+            g1_terms: Vec::new(),
+            pairs: Vec::new(),
+            gt_exp: Scalar::zero(),
+            gt: Gt::one(),
+        }
+    }
+
+    /// Add the equation `sum(p * s) = 0` over the `terms` `(p, s)`.
+    pub(crate) fn add_g1(&mut self, terms: &[(G1, Scalar)]) {
+        let w = Scalar::random(&mut self.rng);
+        self.g1_terms.extend(terms.iter().map(|(p, s)| (*p, *s * w)));
+    }
+
+    /// Add the equation `prod(a^s) = e(G1::one(), G2::one())^gt_exp * prod(e(p, q))` over the
+    /// terms `(a, s)` of `lhs` and the `pairs` `(p, q)`.
+    pub(crate) fn add_gt(&mut self, lhs: &[(Gt, Scalar)], gt_exp: Scalar, pairs: &[(G1, G2)]) {
+        let w = Scalar::random(&mut self.rng);
+        for (a, s) in lhs {
+            self.gt = self.gt * a.pow(*s * w);
+        }
+        self.gt_exp = self.gt_exp + gt_exp * w;
+        self.pairs.extend(pairs.iter().map(|(p, q)| (*p * w, *q)));
+    }
+
+    /// Check all equations at once.
+    pub(crate) fn verify(mut self) -> Result<(), ZkError> {
+        self.pairs.push((G1::one() * self.gt_exp, G2::one()));
+
+        if G1::multiexp(&self.g1_terms).is_zero() && pairing_batch(&self.pairs) == self.gt {
+            Ok(())
+        }
+        else {
+            Err(ZkError::VerificationError)
+        }
+    }
+}
@@ -1,34 +1,93 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use bn::{Fr as Scalar, G1, G2, Gt, pairing, Group};
+use borsh::maybestd::vec::Vec;
+use bn::{Fr as Scalar, G1, G2, Gt, Group};
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use bulletproofs::transcript::TranscriptProtocol;
+use merlin::Transcript;
+use crate::encoding::impl_compressed_borsh;
 use crate::errors::ZkError;
-use crate::traits::ToBytes;
-use crate::utils::{hash_to_scalar};
-use rand::Rng;
+use crate::utils::scalar_to_u64;
+use crate::zk::{BALANCE_BITS, nonce_generator, verifier_rng};
+use crate::zk::batch::BatchCheck;
+use crate::zk::sigma::{GtTerm, Relation, SigmaProof};
+use crate::zk::transcript::SigmaTranscript;
+use rand::{CryptoRng, Rng};
 use core::ops::Neg;
-use borsh::maybestd::vec::Vec;
 
-#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone)]
+/// Statement of a burn (or partial withdraw) proof: the ciphertext `(c1_id, c2_id)` encrypts the value
+/// committed in `c_b` under the identity key of `y`, and `u` is the nonce of that key in `epoch`.
+#[derive(Eq, PartialEq, Clone)]
 pub struct BurnStatement {
     pub y: G1,
     pub c1_id: G1,
     pub c2_id: Gt,
+    /// Pedersen commitment (with `PedersenGens::default()`) to the encrypted value.
+    pub c_b: G1,
+    pub epoch: u64,
+    /// Nonce of the identity key in `epoch`, see [`nonce`](crate::zk::nonce).
+    pub u: Gt,
+}
+
+impl_compressed_borsh!(BurnStatement { y, c1_id, c2_id, c_b, epoch, u });
+
+impl BurnStatement {
+    /// Absorb the domain separator, every element of the statement and the context into the transcript.
+    fn append_to_transcript(&self, transcript: &mut Transcript, context: &[u8]) {
+        transcript.burn_domain_sep();
+        transcript.append_point(b"y", &self.y);
+        transcript.append_point(b"c1_id", &self.c1_id);
+        transcript.append_gt(b"c2_id", &self.c2_id);
+        transcript.append_point(b"c_b", &self.c_b);
+        transcript.append_u64(b"epoch", self.epoch);
+        transcript.append_gt(b"u", &self.u);
+        transcript.append_message(b"context", context);
+    }
+
+    /// The relation proven by a burn proof, over the secrets `b, s, r_b` and `h_id, sk_id` of a
+    /// [`BurnWitness`]: `y = g^s`, `c_b` commits to `b` with blinding `r_b`, `sk_id = s * h_id`,
+    /// `(c1_id, c2_id)` decrypts to `b` under `sk_id` and `u` is the nonce of `sk_id` in `epoch`.
+    pub fn relation(&self) -> Relation {
+        let pc_gens = PedersenGens::default();
+        let mut relation = Relation::new();
+        let b = relation.scalar();
+        let s = relation.scalar();
+        let r_b = relation.scalar();
+        let h_id = relation.g2();
+        let sk_id = relation.g2();
+
+        relation.g1_equation(&[(G1::one(), s)], self.y);
+        relation.g1_equation(&[(pc_gens.B, b), (pc_gens.B_blinding, r_b)], self.c_b);
+        relation.gt_equation(&[GtTerm::Pairing(self.y, h_id), GtTerm::Pairing(G1::one().neg(), sk_id)], Gt::one());
+        relation.gt_equation(&[GtTerm::Generator(b), GtTerm::Pairing(self.c1_id, sk_id)], self.c2_id);
+        relation.gt_equation(&[GtTerm::Pairing(nonce_generator(self.epoch), sk_id)], self.u);
+        relation
+    }
 }
 
 #[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone, Debug)]
 pub struct BurnWitness {
     pub b: Scalar,
     pub s: Scalar,
+    pub r_b: Scalar,
     pub h_id: G2,
     pub sk_id: G2, 
 }
 
-#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone, Debug)]
-pub struct BurnProof {
-    pub x: Scalar,
-    pub zb: Scalar,
-    pub zs: Scalar,
-    pub z_id: G2,
-    pub z_sk: G2,
+impl BurnWitness {
+    /// The values of the secrets of [`BurnStatement::relation`].
+    fn assignment(&self) -> (Vec<Scalar>, Vec<G2>) {
+        (Vec::from([self.b, self.s, self.r_b]), Vec::from([self.h_id, self.sk_id]))
+    }
+}
+
+/// A proof of [`BurnStatement::relation`].
+pub type BurnProof = SigmaProof;
+
+/// A burn proof together with a range proof showing that the committed value lies in `[0, 2^BALANCE_BITS)`.
+#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone)]
+pub struct FullBurnProof {
+    pub proof: BurnProof,
+    pub range_proof: RangeProof,
 }
 
 pub struct BurnProver<R> {
@@ -37,43 +96,58 @@ pub struct BurnProver<R> {
 
 
 impl<R> BurnProver<R> 
-where R: Rng {
+where R: Rng + CryptoRng {
     pub fn new(rng: R) -> Self {
         Self {
             rng
         }
     }
 
-    pub fn generate_proof(&mut self, statement: BurnStatement, witness: BurnWitness) -> BurnProof {
-        let mb = Scalar::random(&mut self.rng); 
-        let ms = Scalar::random(&mut self.rng);
-        let m_id = G2::random(&mut self.rng);
-        let m_sk = G2::random(&mut self.rng);
-
-        let d_y = G1::one() * ms;
-        let r = pairing(statement.y, m_id) * pairing(G1::one().neg(), m_sk);
-        let d_id = pairing(G1::one(), G2::one()).pow(mb) * pairing(statement.c1_id, m_sk);
-        
-        let script = d_y.to_bytes()
-            .iter()
-            .chain(r.to_bytes().iter())
-            .chain(d_id.to_bytes().iter())
-            .map(|x| *x)
-            .collect::<Vec<_>>();
-        let x = hash_to_scalar(&script);
-
-        let zb = x * witness.b + mb;
-        let zs = x * witness.s + ms;
-        let z_id = witness.h_id * x + m_id;
-        let z_sk = witness.sk_id * x + m_sk;
-
-        BurnProof {
-            x,
-            zb,
-            zs,
-            z_id,
-            z_sk,
+    /// Generate a burn proof bound to `context`, e.g. the account the burned funds are paid to,
+    /// so that the proof cannot be used in another context.
+    pub fn generate_proof(&mut self, statement: BurnStatement, witness: BurnWitness, context: &[u8]) -> BurnProof {
+        let mut transcript = Transcript::new(b"faceless-burn");
+        statement.append_to_transcript(&mut transcript, context);
+
+        self.prove_sigma(&mut transcript, statement, witness)
+    }
+
+    /// Generate a burn proof bound to `context` together with a range proof on the committed value.
+    /// Both proofs share one transcript, so the challenge of the burn proof also binds the range proof.
+    pub fn generate_full_proof(&mut self, statement: BurnStatement, witness: BurnWitness, context: &[u8]) -> Result<FullBurnProof, ZkError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(BALANCE_BITS, 1);
+
+        let mut transcript = Transcript::new(b"faceless-burn");
+        statement.append_to_transcript(&mut transcript, context);
+
+        let (range_proof, c_b) = RangeProof::prove_single_with_rng(
+            &bp_gens,
+            &pc_gens,
+            &mut transcript,
+            scalar_to_u64(witness.b),
+            &witness.r_b,
+            BALANCE_BITS,
+            &mut self.rng,
+        ).map_err(|_| ZkError::ProofError)?;
+
+        if c_b != statement.c_b {
+            return Err(ZkError::ProofError);
         }
+
+        let proof = self.prove_sigma(&mut transcript, statement, witness);
+
+        Ok(FullBurnProof {
+            proof,
+            range_proof,
+        })
+    }
+
+    fn prove_sigma(&mut self, transcript: &mut Transcript, statement: BurnStatement, witness: BurnWitness) -> BurnProof {
+        let (scalars, g2s) = witness.assignment();
+        statement.relation()
+            .prove(transcript, &scalars, &g2s, &mut self.rng)
+            .expect("the witness assigns every secret of the relation; qed")
     }
 }
 
@@ -81,26 +155,45 @@ where R: Rng {
 pub struct BurnVerifier;
 
 impl BurnVerifier {
-    pub fn verify_proof(statement: BurnStatement, proof: BurnProof) -> Result<(), ZkError> {
-        let d_y = G1::one() * proof.zs - statement.y * proof.x;
-        let r = pairing(statement.y, proof.z_id) * pairing(G1::one().neg(), proof.z_sk);
-        let d_id = pairing(G1::one(), G2::one()).pow(proof.zb) *
-            pairing(statement.c1_id, proof.z_sk) *
-            statement.c2_id.pow(proof.x).inverse().unwrap();
-
-        let script = d_y.to_bytes()
-            .iter()
-            .chain(r.to_bytes().iter())
-            .chain(d_id.to_bytes().iter())
-            .map(|x| *x)
-            .collect::<Vec<_>>();
-        let x = hash_to_scalar(&script);
-
-        if x == proof.x {
-            Ok(())
-        }
-        else {
-            Err(ZkError::VerificationError)
+    /// Verify a burn proof generated for `context`.
+    pub fn verify_proof(statement: BurnStatement, proof: BurnProof, context: &[u8]) -> Result<(), ZkError> {
+        let seed = (&statement, &proof, context).try_to_vec().map_err(|_| ZkError::VerificationError)?;
+        let mut check = BatchCheck::new(&seed);
+
+        let mut transcript = Transcript::new(b"faceless-burn");
+        statement.append_to_transcript(&mut transcript, context);
+
+        statement.relation().add_to_check(&mut check, &mut transcript, &proof)?;
+        check.verify()
+    }
+
+    /// Verify a burn proof generated for `context` together with the range proof on the committed value.
+    pub fn verify_full_proof(statement: BurnStatement, proof: FullBurnProof, context: &[u8]) -> Result<(), ZkError> {
+        Self::verify_batch(&[(statement, proof, context)])
+    }
+
+    /// Verify many burn proofs, each with the context it was generated for, at once. The range
+    /// proofs are verified one by one, while the equations of all burn proofs are checked with a
+    /// single multi-pairing.
+    pub fn verify_batch(instances: &[(BurnStatement, FullBurnProof, &[u8])]) -> Result<(), ZkError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(BALANCE_BITS, 1);
+
+        let seed = instances.try_to_vec().map_err(|_| ZkError::VerificationError)?;
+        let mut check = BatchCheck::new(&seed);
+
+        for (statement, proof, context) in instances {
+            let mut transcript = Transcript::new(b"faceless-burn");
+            statement.append_to_transcript(&mut transcript, context);
+
+            let mut rng = verifier_rng(&proof.try_to_vec().map_err(|_| ZkError::VerificationError)?);
+            proof.range_proof
+                .verify_single_with_rng(&bp_gens, &pc_gens, &mut transcript, &statement.c_b, BALANCE_BITS, &mut rng)
+                .map_err(|_| ZkError::VerificationError)?;
+
+            statement.relation().add_to_check(&mut check, &mut transcript, &proof.proof)?;
         }
+
+        check.verify()
     }
 }
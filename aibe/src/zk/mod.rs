@@ -1,2 +1,40 @@
+pub(crate) mod batch;
 pub mod burn;
+pub mod multi_transfer;
+pub mod register;
+pub mod ring_transfer;
+pub mod sigma;
 pub mod transfer;
+pub mod transcript;
+
+use bn::{G1, G2, Gt, pairing};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::Digest;
+
+/// Number of bits of the range proofs on encrypted balances, i.e. balances must lie in `[0, 2^32)`.
+pub const BALANCE_BITS: usize = 32;
+
+/// Domain separation tag of the nonce generators.
+pub const NONCE_DST: &[u8] = b"FACELESS-NONCE-V01-CS01-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+
+/// The generator `H(epoch)` in G1 that the nonces of `epoch` are computed from.
+pub fn nonce_generator(epoch: u64) -> G1 {
+    G1::hash_to_curve(&epoch.to_le_bytes(), NONCE_DST)
+}
+
+/// The nonce `u = e(H(epoch), sk_id)` of an identity key in `epoch`.
+///
+/// Every burn and transfer proof made with the key in the same epoch carries the same nonce, so a
+/// verifier that records the nonces it has seen accepts at most one proof per account and epoch.
+/// Nonces of different epochs cannot be linked to each other without the key.
+pub fn nonce(sk_id: &G2, epoch: u64) -> Gt {
+    pairing(nonce_generator(epoch), *sk_id)
+}
+
+/// Deterministic rng used by verifiers to sample the random weights of a range proof check.
+/// It is seeded from the data being verified, so that verification also works inside the runtime.
+pub(crate) fn verifier_rng(data: &[u8]) -> ChaCha20Rng {
+    let seed = sha2::Sha256::digest(data);
+    ChaCha20Rng::from_seed(seed.into())
+}
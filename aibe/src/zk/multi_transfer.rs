@@ -0,0 +1,391 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::maybestd::vec::Vec;
+use bn::{Fr as Scalar, G1, G2, Gt, pairing, Group};
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use bulletproofs::transcript::TranscriptProtocol;
+use merlin::Transcript;
+use crate::encoding::impl_compressed_borsh;
+use crate::errors::ZkError;
+use crate::utils::scalar_to_u64;
+use crate::zk::{BALANCE_BITS, nonce_generator, verifier_rng};
+use crate::zk::transcript::SigmaTranscript;
+use rand::{CryptoRng, Rng};
+use core::ops::Neg;
+
+/// Statement of a transfer to `n` recipients with one shared randomness `c1`.
+///
+/// `(c1, c2)` is deducted from the sender's balance and encrypts the sum of the outputs under the
+/// sender's identity, `(c1, c2_bars[i])` encrypts output `i` under the identity of recipient `i`
+/// with master public key `y_bars[i]` and public key `pk_id_bars[i]`, and `(c1_tilde, c2_tilde)`
+/// is the remaining balance.
+#[derive(Eq, PartialEq, Clone)]
+pub struct MultiTransferStatement {
+    pub y: G1,
+    pub y_bars: Vec<G1>,
+    /// Public keys `e(y_bars[i], H(id_bars[i]))` of the recipients, which identify their accounts.
+    pub pk_id_bars: Vec<Gt>,
+    pub c1: G1,
+    pub c2: Gt,
+    pub c2_bars: Vec<Gt>,
+    pub c1_tilde: G1,
+    pub c2_tilde: Gt,
+    /// Pedersen commitments (with `PedersenGens::default()`) to the output amounts.
+    pub c_b_stars: Vec<G1>,
+    /// Pedersen commitment (with `PedersenGens::default()`) to the remaining balance.
+    pub c_b_prime: G1,
+    pub epoch: u64,
+    /// Nonce of the sender's identity key in `epoch`, see [`nonce`](crate::zk::nonce).
+    pub u: Gt,
+}
+
+impl_compressed_borsh!(MultiTransferStatement { y, y_bars, pk_id_bars, c1, c2, c2_bars, c1_tilde, c2_tilde, c_b_stars, c_b_prime, epoch, u });
+
+impl MultiTransferStatement {
+    /// Number of outputs, or `None` if the outputs of the statement have different lengths.
+    pub fn outputs(&self) -> Option<usize> {
+        let n = self.y_bars.len();
+        if n == 0 || self.pk_id_bars.len() != n || self.c2_bars.len() != n || self.c_b_stars.len() != n {
+            return None;
+        }
+        Some(n)
+    }
+
+    /// Absorb the domain separator and every element of the statement into the transcript.
+    fn append_to_transcript(&self, transcript: &mut Transcript) {
+        transcript.multi_transfer_domain_sep(self.y_bars.len() as u64);
+        transcript.append_point(b"y", &self.y);
+        for y_bar in &self.y_bars {
+            transcript.append_point(b"y_bar", y_bar);
+        }
+        for pk_id_bar in &self.pk_id_bars {
+            transcript.append_gt(b"pk_id_bar", pk_id_bar);
+        }
+        transcript.append_point(b"c1", &self.c1);
+        transcript.append_gt(b"c2", &self.c2);
+        for c2_bar in &self.c2_bars {
+            transcript.append_gt(b"c2_bar", c2_bar);
+        }
+        transcript.append_point(b"c1_tilde", &self.c1_tilde);
+        transcript.append_gt(b"c2_tilde", &self.c2_tilde);
+        for c_b_star in &self.c_b_stars {
+            transcript.append_point(b"c_b_star", c_b_star);
+        }
+        transcript.append_point(b"c_b_prime", &self.c_b_prime);
+        transcript.append_u64(b"epoch", self.epoch);
+        transcript.append_gt(b"u", &self.u);
+    }
+
+    /// The commitments of the aggregated range proof: the outputs and the remaining balance, padded
+    /// to a power of two with commitments to zero with a zero blinding.
+    fn range_commitments(&self) -> Vec<G1> {
+        let mut commitments = self.c_b_stars.clone();
+        commitments.push(self.c_b_prime);
+        commitments.resize(commitments.len().next_power_of_two(), G1::zero());
+        commitments
+    }
+}
+
+#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct MultiTransferWitness {
+    pub r: Scalar,
+    pub s: Scalar,
+    pub r_stars: Vec<Scalar>,
+    pub r_prime: Scalar,
+    pub b_stars: Vec<Scalar>,
+    pub b_prime: Scalar,
+    pub h_id: G2,
+    pub h_id_bars: Vec<G2>,
+    pub sk_id: G2,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct MultiTransferProof {
+    pub x: Scalar,
+    pub zr: Scalar,
+    pub zs: Scalar,
+    pub zr_stars: Vec<Scalar>,
+    pub zr_prime: Scalar,
+    pub zb_stars: Vec<Scalar>,
+    pub zb_prime: Scalar,
+    pub z_id: G2,
+    pub z_id_prime: G2,
+    pub z_id_bars: Vec<G2>,
+    pub z_id_bar_primes: Vec<G2>,
+    pub z_sk: G2,
+}
+
+impl_compressed_borsh!(MultiTransferProof { x, zr, zs, zr_stars, zr_prime, zb_stars, zb_prime, z_id, z_id_prime, z_id_bars, z_id_bar_primes, z_sk });
+
+/// A multi-recipient transfer proof together with an aggregated range proof showing that every
+/// output and the remaining balance lie in `[0, 2^BALANCE_BITS)`.
+#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct FullMultiTransferProof {
+    pub proof: MultiTransferProof,
+    pub range_proof: RangeProof,
+}
+
+pub struct MultiTransferProver<R> {
+    rng: R
+}
+
+
+impl<R> MultiTransferProver<R>
+where R: Rng + CryptoRng {
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng
+        }
+    }
+
+    pub fn generate_proof(&mut self, statement: MultiTransferStatement, witness: MultiTransferWitness) -> Result<MultiTransferProof, ZkError> {
+        let n = statement.outputs().ok_or(ZkError::ProofError)?;
+        if witness.b_stars.len() != n || witness.r_stars.len() != n || witness.h_id_bars.len() != n {
+            return Err(ZkError::ProofError);
+        }
+
+        let mut transcript = Transcript::new(b"faceless-multi-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        Ok(self.prove_sigma(&mut transcript, statement, witness))
+    }
+
+    /// Generate a multi-recipient transfer proof together with an aggregated range proof on the
+    /// committed outputs and remaining balance. Both proofs share one transcript, so the challenge
+    /// of the transfer proof also binds the range proof.
+    pub fn generate_full_proof(&mut self, statement: MultiTransferStatement, witness: MultiTransferWitness) -> Result<FullMultiTransferProof, ZkError> {
+        let n = statement.outputs().ok_or(ZkError::ProofError)?;
+        if witness.b_stars.len() != n || witness.r_stars.len() != n || witness.h_id_bars.len() != n {
+            return Err(ZkError::ProofError);
+        }
+
+        let commitments = statement.range_commitments();
+        let m = commitments.len();
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(BALANCE_BITS, m);
+
+        let mut values = witness.b_stars.iter().map(|b| scalar_to_u64(*b)).collect::<Vec<_>>();
+        values.push(scalar_to_u64(witness.b_prime));
+        values.resize(m, 0);
+        let mut blindings = witness.r_stars.clone();
+        blindings.push(witness.r_prime);
+        blindings.resize(m, Scalar::zero());
+
+        let mut transcript = Transcript::new(b"faceless-multi-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        let (range_proof, range_commitments) = RangeProof::prove_multiple_with_rng(
+            &bp_gens,
+            &pc_gens,
+            &mut transcript,
+            &values,
+            &blindings,
+            BALANCE_BITS,
+            &mut self.rng,
+        ).map_err(|_| ZkError::ProofError)?;
+
+        if range_commitments != commitments {
+            return Err(ZkError::ProofError);
+        }
+
+        let proof = self.prove_sigma(&mut transcript, statement, witness);
+
+        Ok(FullMultiTransferProof {
+            proof,
+            range_proof,
+        })
+    }
+
+    fn prove_sigma(&mut self, transcript: &mut Transcript, statement: MultiTransferStatement, witness: MultiTransferWitness) -> MultiTransferProof {
+        let pc_gens = PedersenGens::default();
+        let n = statement.y_bars.len();
+
+        let mr = Scalar::random(&mut self.rng);
+        let ms = Scalar::random(&mut self.rng);
+        let mr_stars = (0..n).map(|_| Scalar::random(&mut self.rng)).collect::<Vec<_>>();
+        let mr_prime = Scalar::random(&mut self.rng);
+        let mb_stars = (0..n).map(|_| Scalar::random(&mut self.rng)).collect::<Vec<_>>();
+        let mb_prime = Scalar::random(&mut self.rng);
+
+        let m_id = G2::random(&mut self.rng);
+        let m_id_prime = G2::random(&mut self.rng);
+        let m_id_bars = (0..n).map(|_| G2::random(&mut self.rng)).collect::<Vec<_>>();
+        let m_id_bar_primes = (0..n).map(|_| G2::random(&mut self.rng)).collect::<Vec<_>>();
+        let m_sk = G2::random(&mut self.rng);
+
+        let d_y = G1::one() * ms;
+        let d_1 = G1::one() * mr;
+        let d_b_stars = (0..n).map(|i| pc_gens.commit(mb_stars[i], mr_stars[i])).collect::<Vec<_>>();
+        let d_b_prime = pc_gens.commit(mb_prime, mr_prime);
+
+        let r = pairing(statement.c1, m_id) * pairing(G1::one().neg(), m_id_prime);
+        let r_bars = (0..n)
+            .map(|i| pairing(statement.c1, m_id_bars[i]) * pairing(G1::one().neg(), m_id_bar_primes[i]))
+            .collect::<Vec<_>>();
+        let r_sk = pairing(statement.y, m_id) * pairing(G1::one().neg(), m_sk);
+        // the ciphertext of recipient `i` is under the identity of `pk_id_bars[i]`
+        let r_pks = (0..n).map(|i| pairing(statement.y_bars[i], m_id_bars[i])).collect::<Vec<_>>();
+
+        // the deducted amount is the sum of the outputs
+        let gt = pairing(G1::one(), G2::one());
+        let mb_sum = mb_stars.iter().fold(Scalar::zero(), |acc, mb| acc + *mb);
+        let d_2 = gt.pow(mb_sum) * pairing(statement.y, m_id_prime);
+        let d_2_bars = (0..n)
+            .map(|i| gt.pow(mb_stars[i]) * pairing(statement.y_bars[i], m_id_bar_primes[i]))
+            .collect::<Vec<_>>();
+        let d_2_tilde = gt.pow(mb_prime) * pairing(statement.c1_tilde, m_sk);
+        let d_u = pairing(nonce_generator(statement.epoch), m_sk);
+
+        transcript.append_point(b"d_y", &d_y);
+        transcript.append_point(b"d_1", &d_1);
+        for d_b_star in &d_b_stars {
+            transcript.append_point(b"d_b_star", d_b_star);
+        }
+        transcript.append_point(b"d_b_prime", &d_b_prime);
+        transcript.append_gt(b"r", &r);
+        for r_bar in &r_bars {
+            transcript.append_gt(b"r_bar", r_bar);
+        }
+        transcript.append_gt(b"r_sk", &r_sk);
+        for r_pk in &r_pks {
+            transcript.append_gt(b"r_pk", r_pk);
+        }
+        transcript.append_gt(b"d_2", &d_2);
+        for d_2_bar in &d_2_bars {
+            transcript.append_gt(b"d_2_bar", d_2_bar);
+        }
+        transcript.append_gt(b"d_2_tilde", &d_2_tilde);
+        transcript.append_gt(b"d_u", &d_u);
+        let x = transcript.challenge_scalar(b"x");
+
+        let zr = x * witness.r + mr;
+        let zs = x * witness.s + ms;
+        let zr_stars = (0..n).map(|i| x * witness.r_stars[i] + mr_stars[i]).collect();
+        let zr_prime = x * witness.r_prime + mr_prime;
+        let zb_stars = (0..n).map(|i| x * witness.b_stars[i] + mb_stars[i]).collect();
+        let zb_prime = x * witness.b_prime + mb_prime;
+
+        let z_id = witness.h_id * x + m_id;
+        let z_id_prime = witness.h_id * witness.r * x + m_id_prime;
+        let z_id_bars = (0..n).map(|i| witness.h_id_bars[i] * x + m_id_bars[i]).collect();
+        let z_id_bar_primes = (0..n).map(|i| witness.h_id_bars[i] * witness.r * x + m_id_bar_primes[i]).collect();
+        let z_sk = witness.sk_id * x + m_sk;
+
+        MultiTransferProof {
+            x,
+            zr,
+            zs,
+            zr_stars,
+            zr_prime,
+            zb_stars,
+            zb_prime,
+            z_id,
+            z_id_prime,
+            z_id_bars,
+            z_id_bar_primes,
+            z_sk,
+        }
+    }
+}
+
+
+pub struct MultiTransferVerifier;
+
+impl MultiTransferVerifier {
+    pub fn verify_proof(statement: MultiTransferStatement, proof: MultiTransferProof) -> Result<(), ZkError> {
+        let mut transcript = Transcript::new(b"faceless-multi-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        Self::verify_sigma(&mut transcript, statement, proof)
+    }
+
+    /// Verify a multi-recipient transfer proof together with the aggregated range proof on the
+    /// committed outputs and remaining balance.
+    pub fn verify_full_proof(statement: MultiTransferStatement, proof: FullMultiTransferProof) -> Result<(), ZkError> {
+        statement.outputs().ok_or(ZkError::VerificationError)?;
+
+        let commitments = statement.range_commitments();
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(BALANCE_BITS, commitments.len());
+
+        let mut transcript = Transcript::new(b"faceless-multi-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        let mut rng = verifier_rng(&proof.try_to_vec().map_err(|_| ZkError::VerificationError)?);
+        proof.range_proof
+            .verify_multiple_with_rng(&bp_gens, &pc_gens, &mut transcript, &commitments, BALANCE_BITS, &mut rng)
+            .map_err(|_| ZkError::VerificationError)?;
+
+        Self::verify_sigma(&mut transcript, statement, proof.proof)
+    }
+
+    fn verify_sigma(transcript: &mut Transcript, statement: MultiTransferStatement, proof: MultiTransferProof) -> Result<(), ZkError> {
+        let n = statement.outputs().ok_or(ZkError::VerificationError)?;
+        if proof.zr_stars.len() != n || proof.zb_stars.len() != n || proof.z_id_bars.len() != n || proof.z_id_bar_primes.len() != n {
+            return Err(ZkError::VerificationError);
+        }
+        let pc_gens = PedersenGens::default();
+
+        let d_y = G1::one() * proof.zs - statement.y * proof.x;
+        let d_1 = G1::one() * proof.zr - statement.c1 * proof.x;
+        let d_b_stars = (0..n)
+            .map(|i| pc_gens.commit(proof.zb_stars[i], proof.zr_stars[i]) - statement.c_b_stars[i] * proof.x)
+            .collect::<Vec<_>>();
+        let d_b_prime = pc_gens.commit(proof.zb_prime, proof.zr_prime) - statement.c_b_prime * proof.x;
+
+        let r = pairing(statement.c1, proof.z_id) * pairing(G1::one().neg(), proof.z_id_prime);
+        let r_bars = (0..n)
+            .map(|i| pairing(statement.c1, proof.z_id_bars[i]) * pairing(G1::one().neg(), proof.z_id_bar_primes[i]))
+            .collect::<Vec<_>>();
+        let r_sk = pairing(statement.y, proof.z_id) * pairing(G1::one().neg(), proof.z_sk);
+        let r_pks = (0..n)
+            .map(|i| Some(pairing(statement.y_bars[i], proof.z_id_bars[i]) * statement.pk_id_bars[i].pow(proof.x).inverse()?))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ZkError::VerificationError)?;
+
+        let gt = pairing(G1::one(), G2::one());
+        let zb_sum = proof.zb_stars.iter().fold(Scalar::zero(), |acc, zb| acc + *zb);
+        let d_2 = gt.pow(zb_sum) *
+            pairing(statement.y, proof.z_id_prime) *
+            statement.c2.pow(proof.x).inverse().ok_or(ZkError::VerificationError)?;
+        let d_2_bars = (0..n)
+            .map(|i| Some(gt.pow(proof.zb_stars[i]) *
+                pairing(statement.y_bars[i], proof.z_id_bar_primes[i]) *
+                statement.c2_bars[i].pow(proof.x).inverse()?))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ZkError::VerificationError)?;
+        let d_2_tilde = gt.pow(proof.zb_prime) *
+            pairing(statement.c1_tilde, proof.z_sk) *
+            statement.c2_tilde.pow(proof.x).inverse().ok_or(ZkError::VerificationError)?;
+        let d_u = pairing(nonce_generator(statement.epoch), proof.z_sk) *
+            statement.u.pow(proof.x).inverse().ok_or(ZkError::VerificationError)?;
+
+        transcript.append_point(b"d_y", &d_y);
+        transcript.append_point(b"d_1", &d_1);
+        for d_b_star in &d_b_stars {
+            transcript.append_point(b"d_b_star", d_b_star);
+        }
+        transcript.append_point(b"d_b_prime", &d_b_prime);
+        transcript.append_gt(b"r", &r);
+        for r_bar in &r_bars {
+            transcript.append_gt(b"r_bar", r_bar);
+        }
+        transcript.append_gt(b"r_sk", &r_sk);
+        for r_pk in &r_pks {
+            transcript.append_gt(b"r_pk", r_pk);
+        }
+        transcript.append_gt(b"d_2", &d_2);
+        for d_2_bar in &d_2_bars {
+            transcript.append_gt(b"d_2_bar", d_2_bar);
+        }
+        transcript.append_gt(b"d_2_tilde", &d_2_tilde);
+        transcript.append_gt(b"d_u", &d_u);
+        let x = transcript.challenge_scalar(b"x");
+
+        if x == proof.x {
+            Ok(())
+        }
+        else {
+            Err(ZkError::VerificationError)
+        }
+    }
+}
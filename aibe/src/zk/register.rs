@@ -0,0 +1,108 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use bn::{Fr as Scalar, G1, G2, Gt, pairing, Group};
+use bulletproofs::transcript::TranscriptProtocol;
+use merlin::Transcript;
+use crate::encoding::impl_compressed_borsh;
+use crate::errors::ZkError;
+use crate::zk::transcript::SigmaTranscript;
+use rand::{CryptoRng, Rng};
+
+/// Statement of a registration proof: the prover knows the identity key `sk_id` of `pk_id`, i.e.
+/// `e(G1, sk_id) = pk_id`, and `pk_id = e(y, h_id)` for the hashed identity `h_id`.
+#[derive(Eq, PartialEq, Clone)]
+pub struct RegisterStatement {
+    pub y: G1,
+    pub pk_id: Gt,
+}
+
+impl_compressed_borsh!(RegisterStatement { y, pk_id });
+
+impl RegisterStatement {
+    /// Absorb the domain separator, every element of the statement and the context into the transcript.
+    fn append_to_transcript(&self, transcript: &mut Transcript, context: &[u8]) {
+        transcript.register_domain_sep();
+        transcript.append_point(b"y", &self.y);
+        transcript.append_gt(b"pk_id", &self.pk_id);
+        transcript.append_message(b"context", context);
+    }
+}
+
+#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct RegisterWitness {
+    pub h_id: G2,
+    pub sk_id: G2,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct RegisterProof {
+    pub x: Scalar,
+    pub z_id: G2,
+    pub z_sk: G2,
+}
+
+impl_compressed_borsh!(RegisterProof { x, z_id, z_sk });
+
+pub struct RegisterProver<R> {
+    rng: R
+}
+
+impl<R> RegisterProver<R>
+where R: Rng + CryptoRng {
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng
+        }
+    }
+
+    /// Generate a registration proof bound to `context`, e.g. the account submitting it, so
+    /// that the proof cannot be replayed in another context.
+    pub fn generate_proof(&mut self, statement: RegisterStatement, witness: RegisterWitness, context: &[u8]) -> RegisterProof {
+        let mut transcript = Transcript::new(b"faceless-register");
+        statement.append_to_transcript(&mut transcript, context);
+
+        let m_id = G2::random(&mut self.rng);
+        let m_sk = G2::random(&mut self.rng);
+
+        let r_id = pairing(statement.y, m_id);
+        let r_sk = pairing(G1::one(), m_sk);
+
+        transcript.append_gt(b"r_id", &r_id);
+        transcript.append_gt(b"r_sk", &r_sk);
+        let x = transcript.challenge_scalar(b"x");
+
+        let z_id = witness.h_id * x + m_id;
+        let z_sk = witness.sk_id * x + m_sk;
+
+        RegisterProof {
+            x,
+            z_id,
+            z_sk,
+        }
+    }
+}
+
+
+pub struct RegisterVerifier;
+
+impl RegisterVerifier {
+    /// Verify a registration proof generated for `context`.
+    pub fn verify_proof(statement: RegisterStatement, proof: RegisterProof, context: &[u8]) -> Result<(), ZkError> {
+        let mut transcript = Transcript::new(b"faceless-register");
+        statement.append_to_transcript(&mut transcript, context);
+
+        let pk_x = statement.pk_id.pow(proof.x).inverse().ok_or(ZkError::VerificationError)?;
+        let r_id = pairing(statement.y, proof.z_id) * pk_x;
+        let r_sk = pairing(G1::one(), proof.z_sk) * pk_x;
+
+        transcript.append_gt(b"r_id", &r_id);
+        transcript.append_gt(b"r_sk", &r_sk);
+        let x = transcript.challenge_scalar(b"x");
+
+        if x == proof.x {
+            Ok(())
+        }
+        else {
+            Err(ZkError::VerificationError)
+        }
+    }
+}
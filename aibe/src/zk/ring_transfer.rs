@@ -0,0 +1,335 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::maybestd::vec::Vec;
+use bn::{Fr as Scalar, G1, G2, Gt, pairing, Group};
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use bulletproofs::transcript::TranscriptProtocol;
+use merlin::Transcript;
+use crate::bf_ibe::CipherText;
+use crate::encoding::impl_compressed_borsh;
+use crate::errors::ZkError;
+use crate::utils::scalar_to_u64;
+use crate::zk::{BALANCE_BITS, nonce_generator, verifier_rng};
+use crate::zk::transcript::SigmaTranscript;
+use rand::{CryptoRng, Rng};
+
+/// Statement of a transfer that hides the sender and the receiver among a ring of `n` accounts.
+///
+/// Every member `i` of the ring, with public key `pk_ids[i]` and current balance `balances[i]`, is
+/// credited `(c1, c2s[i])` with `c1 = g^r` and `c2s[i] = gt^{v_i} * pk_ids[i]^r`, where `v_i` is
+/// minus the transfer amount for the sender, the transfer amount for the receiver and zero for
+/// everybody else. The proof shows this for some pair of distinct members without telling which,
+/// and that the sender knows the identity key of its account and keeps a non-negative balance.
+///
+/// The proof is an OR composition over all `n * (n - 1)` ordered pairs of members, so its size is
+/// quadratic and its verification cubic in `n`; rings are meant to be small.
+#[derive(Eq, PartialEq, Clone)]
+pub struct RingTransferStatement {
+    pub pk_ids: Vec<Gt>,
+    pub balances: Vec<CipherText>,
+    pub c1: G1,
+    pub c2s: Vec<Gt>,
+    /// Pedersen commitment (with `PedersenGens::default()`) to the transfer amount.
+    pub c_b_star: G1,
+    /// Pedersen commitment (with `PedersenGens::default()`) to the remaining balance of the sender.
+    pub c_b_prime: G1,
+    pub epoch: u64,
+    /// Nonce of the sender's identity key in `epoch`, see [`nonce`](crate::zk::nonce).
+    pub u: Gt,
+}
+
+impl_compressed_borsh!(RingTransferStatement { pk_ids, balances, c1, c2s, c_b_star, c_b_prime, epoch, u });
+
+impl RingTransferStatement {
+    /// Number of members of the ring, or `None` if the ring has less than two members or the
+    /// members of the statement have different lengths.
+    pub fn ring_size(&self) -> Option<usize> {
+        let n = self.pk_ids.len();
+        if n < 2 || self.balances.len() != n || self.c2s.len() != n {
+            return None;
+        }
+        Some(n)
+    }
+
+    /// Absorb the domain separator and every element of the statement into the transcript.
+    fn append_to_transcript(&self, transcript: &mut Transcript) {
+        transcript.ring_transfer_domain_sep(self.pk_ids.len() as u64);
+        for pk_id in &self.pk_ids {
+            transcript.append_gt(b"pk_id", pk_id);
+        }
+        for (c1, c2) in &self.balances {
+            transcript.append_point(b"balance_c1", c1);
+            transcript.append_gt(b"balance_c2", c2);
+        }
+        transcript.append_point(b"c1", &self.c1);
+        for c2 in &self.c2s {
+            transcript.append_gt(b"c2", c2);
+        }
+        transcript.append_point(b"c_b_star", &self.c_b_star);
+        transcript.append_point(b"c_b_prime", &self.c_b_prime);
+        transcript.append_u64(b"epoch", self.epoch);
+        transcript.append_gt(b"u", &self.u);
+    }
+}
+
+/// The ordered pairs `(sender, receiver)` of distinct members of a ring of size `n`, in the order
+/// of the branches of a proof.
+fn branches(n: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..n).flat_map(move |l| (0..n).filter(move |l_bar| *l_bar != l).map(move |l_bar| (l, l_bar)))
+}
+
+#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct RingTransferWitness {
+    /// Index of the sender in the ring.
+    pub sender: u32,
+    /// Index of the receiver in the ring.
+    pub receiver: u32,
+    pub r: Scalar,
+    pub r_star: Scalar,
+    pub r_prime: Scalar,
+    pub b_star: Scalar,
+    pub b_prime: Scalar,
+    pub sk_id: G2,
+}
+
+/// One challenge and one set of responses per branch, see [`RingTransferStatement`].
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct RingTransferProof {
+    pub x: Vec<Scalar>,
+    pub zr: Vec<Scalar>,
+    pub zr_star: Vec<Scalar>,
+    pub zr_prime: Vec<Scalar>,
+    pub zb_star: Vec<Scalar>,
+    pub zb_prime: Vec<Scalar>,
+    pub z_sk: Vec<G2>,
+}
+
+impl_compressed_borsh!(RingTransferProof { x, zr, zr_star, zr_prime, zb_star, zb_prime, z_sk });
+
+/// A ring transfer proof together with an aggregated range proof showing that both the transfer
+/// amount and the remaining balance of the sender lie in `[0, 2^BALANCE_BITS)`.
+#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone, Debug)]
+pub struct FullRingTransferProof {
+    pub proof: RingTransferProof,
+    pub range_proof: RangeProof,
+}
+
+/// The challenge and responses of one branch.
+struct Responses {
+    x: Scalar,
+    zr: Scalar,
+    zr_star: Scalar,
+    zr_prime: Scalar,
+    zb_star: Scalar,
+    zb_prime: Scalar,
+    z_sk: G2,
+}
+
+/// Recompute the commitments of the branch `(l, l_bar)` from its challenge and responses and
+/// absorb them into the transcript. With a zero challenge and the masks as responses, these are
+/// the commitments of the prover.
+fn append_branch_commitments(transcript: &mut Transcript, statement: &RingTransferStatement, (l, l_bar): (usize, usize), z: &Responses) -> Result<(), ZkError> {
+    let pc_gens = PedersenGens::default();
+    let gt = pairing(G1::one(), G2::one());
+    let x = z.x;
+
+    let d_1 = G1::one() * z.zr - statement.c1 * x;
+    let d_b_star = pc_gens.commit(z.zb_star, z.zr_star) - statement.c_b_star * x;
+    let d_b_prime = pc_gens.commit(z.zb_prime, z.zr_prime) - statement.c_b_prime * x;
+    transcript.append_point(b"d_1", &d_1);
+    transcript.append_point(b"d_b_star", &d_b_star);
+    transcript.append_point(b"d_b_prime", &d_b_prime);
+
+    // every member is credited with the same randomness, the sender minus and the receiver plus the amount
+    for (i, (pk_id, c2)) in statement.pk_ids.iter().zip(statement.c2s.iter()).enumerate() {
+        let mut d_2 = pk_id.pow(z.zr) * c2.pow(x).inverse().ok_or(ZkError::VerificationError)?;
+        if i == l {
+            d_2 = d_2 * gt.pow(-z.zb_star);
+        }
+        else if i == l_bar {
+            d_2 = d_2 * gt.pow(z.zb_star);
+        }
+        transcript.append_gt(b"d_2", &d_2);
+    }
+
+    // the sender knows its identity key, which decrypts its new balance to the remaining balance
+    let (c1_tilde, c2_tilde) = (statement.balances[l].0 + statement.c1, statement.balances[l].1 * statement.c2s[l]);
+    let r_sk = pairing(G1::one(), z.z_sk) *
+        statement.pk_ids[l].pow(x).inverse().ok_or(ZkError::VerificationError)?;
+    let d_2_tilde = gt.pow(z.zb_prime) *
+        pairing(c1_tilde, z.z_sk) *
+        c2_tilde.pow(x).inverse().ok_or(ZkError::VerificationError)?;
+    let d_u = pairing(nonce_generator(statement.epoch), z.z_sk) *
+        statement.u.pow(x).inverse().ok_or(ZkError::VerificationError)?;
+    transcript.append_gt(b"r_sk", &r_sk);
+    transcript.append_gt(b"d_2_tilde", &d_2_tilde);
+    transcript.append_gt(b"d_u", &d_u);
+
+    Ok(())
+}
+
+pub struct RingTransferProver<R> {
+    rng: R
+}
+
+
+impl<R> RingTransferProver<R>
+where R: Rng + CryptoRng {
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng
+        }
+    }
+
+    pub fn generate_proof(&mut self, statement: RingTransferStatement, witness: RingTransferWitness) -> Result<RingTransferProof, ZkError> {
+        let mut transcript = Transcript::new(b"faceless-ring-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        self.prove_sigma(&mut transcript, statement, witness)
+    }
+
+    /// Generate a ring transfer proof together with an aggregated range proof on the committed
+    /// transfer amount and remaining balance. Both proofs share one transcript, so the challenge
+    /// of the transfer proof also binds the range proof.
+    pub fn generate_full_proof(&mut self, statement: RingTransferStatement, witness: RingTransferWitness) -> Result<FullRingTransferProof, ZkError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(BALANCE_BITS, 2);
+
+        let mut transcript = Transcript::new(b"faceless-ring-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        let (range_proof, commitments) = RangeProof::prove_multiple_with_rng(
+            &bp_gens,
+            &pc_gens,
+            &mut transcript,
+            &[scalar_to_u64(witness.b_star), scalar_to_u64(witness.b_prime)],
+            &[witness.r_star, witness.r_prime],
+            BALANCE_BITS,
+            &mut self.rng,
+        ).map_err(|_| ZkError::ProofError)?;
+
+        if commitments != [statement.c_b_star, statement.c_b_prime] {
+            return Err(ZkError::ProofError);
+        }
+
+        let proof = self.prove_sigma(&mut transcript, statement, witness)?;
+
+        Ok(FullRingTransferProof {
+            proof,
+            range_proof,
+        })
+    }
+
+    fn prove_sigma(&mut self, transcript: &mut Transcript, statement: RingTransferStatement, witness: RingTransferWitness) -> Result<RingTransferProof, ZkError> {
+        let n = statement.ring_size().ok_or(ZkError::ProofError)?;
+        let (l, l_bar) = (witness.sender as usize, witness.receiver as usize);
+        if l >= n || l_bar >= n || l == l_bar {
+            return Err(ZkError::ProofError);
+        }
+
+        // The branches of the other pairs are simulated with random challenges and responses,
+        // the branch of the actual pair commits to random masks.
+        let real = branches(n).position(|branch| branch == (l, l_bar)).ok_or(ZkError::ProofError)?;
+        let mut responses = Vec::new();
+        for (k, branch) in branches(n).enumerate() {
+            let z = Responses {
+                x: if k == real { Scalar::zero() } else { Scalar::random(&mut self.rng) },
+                zr: Scalar::random(&mut self.rng),
+                zr_star: Scalar::random(&mut self.rng),
+                zr_prime: Scalar::random(&mut self.rng),
+                zb_star: Scalar::random(&mut self.rng),
+                zb_prime: Scalar::random(&mut self.rng),
+                z_sk: G2::random(&mut self.rng),
+            };
+            append_branch_commitments(transcript, &statement, branch, &z).map_err(|_| ZkError::ProofError)?;
+            responses.push(z);
+        }
+        let x = transcript.challenge_scalar(b"x");
+
+        // the challenges of all branches sum up to the challenge of the transcript
+        let x_real = responses.iter().fold(x, |acc, z| acc - z.x);
+        let z = &mut responses[real];
+        z.x = x_real;
+        z.zr = x_real * witness.r + z.zr;
+        z.zr_star = x_real * witness.r_star + z.zr_star;
+        z.zr_prime = x_real * witness.r_prime + z.zr_prime;
+        z.zb_star = x_real * witness.b_star + z.zb_star;
+        z.zb_prime = x_real * witness.b_prime + z.zb_prime;
+        z.z_sk = witness.sk_id * x_real + z.z_sk;
+
+        Ok(RingTransferProof {
+            x: responses.iter().map(|z| z.x).collect(),
+            zr: responses.iter().map(|z| z.zr).collect(),
+            zr_star: responses.iter().map(|z| z.zr_star).collect(),
+            zr_prime: responses.iter().map(|z| z.zr_prime).collect(),
+            zb_star: responses.iter().map(|z| z.zb_star).collect(),
+            zb_prime: responses.iter().map(|z| z.zb_prime).collect(),
+            z_sk: responses.iter().map(|z| z.z_sk).collect(),
+        })
+    }
+}
+
+
+pub struct RingTransferVerifier;
+
+impl RingTransferVerifier {
+    pub fn verify_proof(statement: RingTransferStatement, proof: RingTransferProof) -> Result<(), ZkError> {
+        let mut transcript = Transcript::new(b"faceless-ring-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        Self::verify_sigma(&mut transcript, statement, proof)
+    }
+
+    /// Verify a ring transfer proof together with the aggregated range proof on the committed
+    /// transfer amount and remaining balance.
+    pub fn verify_full_proof(statement: RingTransferStatement, proof: FullRingTransferProof) -> Result<(), ZkError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(BALANCE_BITS, 2);
+
+        let mut transcript = Transcript::new(b"faceless-ring-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        let mut rng = verifier_rng(&proof.try_to_vec().map_err(|_| ZkError::VerificationError)?);
+        proof.range_proof
+            .verify_multiple_with_rng(
+                &bp_gens,
+                &pc_gens,
+                &mut transcript,
+                &[statement.c_b_star, statement.c_b_prime],
+                BALANCE_BITS,
+                &mut rng,
+            )
+            .map_err(|_| ZkError::VerificationError)?;
+
+        Self::verify_sigma(&mut transcript, statement, proof.proof)
+    }
+
+    fn verify_sigma(transcript: &mut Transcript, statement: RingTransferStatement, proof: RingTransferProof) -> Result<(), ZkError> {
+        let n = statement.ring_size().ok_or(ZkError::VerificationError)?;
+        let m = n * (n - 1);
+        if proof.x.len() != m || proof.zr.len() != m || proof.zr_star.len() != m || proof.zr_prime.len() != m ||
+            proof.zb_star.len() != m || proof.zb_prime.len() != m || proof.z_sk.len() != m {
+            return Err(ZkError::VerificationError);
+        }
+
+        for (k, branch) in branches(n).enumerate() {
+            let z = Responses {
+                x: proof.x[k],
+                zr: proof.zr[k],
+                zr_star: proof.zr_star[k],
+                zr_prime: proof.zr_prime[k],
+                zb_star: proof.zb_star[k],
+                zb_prime: proof.zb_prime[k],
+                z_sk: proof.z_sk[k],
+            };
+            append_branch_commitments(transcript, &statement, branch, &z)?;
+        }
+        let x = transcript.challenge_scalar(b"x");
+
+        if x == proof.x.iter().fold(Scalar::zero(), |acc, x| acc + *x) {
+            Ok(())
+        }
+        else {
+            Err(ZkError::VerificationError)
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Generic sigma protocols for linear relations over G1, G2 and Gt.
+//!
+//! A [`Relation`] declares secret scalars and secret G2 elements, and equations over them:
+//!
+//! * G1 equations `sum(p_i * x_i) = t` with public points `p_i` and secret scalars `x_i`,
+//! * Gt equations `gt^(sum(x_i)) * prod(a_j^(x_j)) * prod(e(p_k, w_k)) = t` with the generator
+//!   `gt = e(G1::one(), G2::one())`, public `a_j` and `p_k`, secret scalars `x_i`, `x_j` and
+//!   secret G2 elements `w_k`.
+//!
+//! The prover, the verifier and the proof are derived from the relation: the proof holds one
+//! commitment per equation and one response per secret. Verification checks the equations of
+//! one or many proofs with a [`BatchCheck`].
+//!
+//! ```
+//! use aibe::zk::sigma::{GtTerm, Relation};
+//! use bn::{G1, G2, Group, pairing};
+//! use merlin::Transcript;
+//!
+//! let (s, sk_id) = (bn::Fr::random(&mut rand::thread_rng()), G2::random(&mut rand::thread_rng()));
+//! let (y, pk_id) = (G1::one() * s, pairing(G1::one(), sk_id));
+//!
+//! // y = g * s and pk_id = e(g, sk_id)
+//! let mut relation = Relation::new();
+//! let x_s = relation.scalar();
+//! let x_sk = relation.g2();
+//! relation.g1_equation(&[(G1::one(), x_s)], y);
+//! relation.gt_equation(&[GtTerm::Pairing(G1::one(), x_sk)], pk_id);
+//!
+//! let proof = relation.prove(&mut Transcript::new(b"example"), &[s], &[sk_id], &mut rand::thread_rng()).unwrap();
+//! assert!(relation.verify(&mut Transcript::new(b"example"), &proof).is_ok());
+//! ```
+
+use borsh::maybestd::vec::Vec;
+use borsh::BorshSerialize;
+use bn::{Fr as Scalar, G1, G2, Gt, Group, pairing_batch};
+use bulletproofs::transcript::TranscriptProtocol;
+use merlin::Transcript;
+use rand::{CryptoRng, Rng};
+use crate::encoding::impl_compressed_borsh;
+use crate::errors::ZkError;
+use crate::zk::batch::BatchCheck;
+use crate::zk::transcript::SigmaTranscript;
+
+/// A secret scalar of a relation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ScalarVar(usize);
+
+/// A secret G2 element of a relation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct G2Var(usize);
+
+/// A factor of the left-hand side of a Gt equation.
+// Terms are `Copy`, which a boxed Gt would rule out.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Copy)]
+pub enum GtTerm {
+    /// `e(G1::one(), G2::one())^x`.
+    Generator(ScalarVar),
+    /// `a^x` for a public `a`.
+    Pow(Gt, ScalarVar),
+    /// `e(p, w)` for a public `p`.
+    Pairing(G1, G2Var),
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+enum Equation {
+    G1(Vec<(G1, ScalarVar)>, G1),
+    Gt(Vec<GtTerm>, Gt),
+}
+
+/// A conjunction of G1 and Gt equations over secret scalars and G2 elements.
+#[derive(Clone, Default)]
+pub struct Relation {
+    scalars: usize,
+    g2s: usize,
+    equations: Vec<Equation>,
+}
+
+/// A proof of a [`Relation`]: the commitments of its G1 and Gt equations, in the order of the
+/// equations, and the responses for its secret scalars and G2 elements.
+#[derive(Clone, Eq, PartialEq)]
+pub struct SigmaProof {
+    pub g1_commitments: Vec<G1>,
+    pub gt_commitments: Vec<Gt>,
+    pub scalar_responses: Vec<Scalar>,
+    pub g2_responses: Vec<G2>,
+}
+
+impl_compressed_borsh!(SigmaProof { g1_commitments, gt_commitments, scalar_responses, g2_responses });
+
+impl Relation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare a secret scalar. Witnesses list the values of the scalars in declaration order.
+    pub fn scalar(&mut self) -> ScalarVar {
+        self.scalars += 1;
+        ScalarVar(self.scalars - 1)
+    }
+
+    /// Declare a secret G2 element. Witnesses list the values of the G2 elements in declaration order.
+    pub fn g2(&mut self) -> G2Var {
+        self.g2s += 1;
+        G2Var(self.g2s - 1)
+    }
+
+    /// Add the equation `sum(p * x) = target` over the `terms` `(p, x)`.
+    pub fn g1_equation(&mut self, terms: &[(G1, ScalarVar)], target: G1) {
+        self.equations.push(Equation::G1(terms.to_vec(), target));
+    }
+
+    /// Add the equation `prod(terms) = target`.
+    pub fn gt_equation(&mut self, terms: &[GtTerm], target: Gt) {
+        self.equations.push(Equation::Gt(terms.to_vec(), target));
+    }
+
+    /// Evaluate the left-hand sides of all equations at the given values of the secrets.
+    fn evaluate(&self, scalars: &[Scalar], g2s: &[G2]) -> (Vec<G1>, Vec<Gt>) {
+        let mut g1_values = Vec::new();
+        let mut gt_values = Vec::new();
+
+        for equation in &self.equations {
+            match equation {
+                Equation::G1(terms, _) => {
+                    let items = terms.iter().map(|(p, x)| (*p, scalars[x.0])).collect::<Vec<_>>();
+                    g1_values.push(G1::multiexp(&items));
+                }
+                Equation::Gt(terms, _) => {
+                    let mut gt_exp = Scalar::zero();
+                    let mut value = Gt::one();
+                    let mut pairs = Vec::new();
+                    for term in terms {
+                        match term {
+                            GtTerm::Generator(x) => gt_exp = gt_exp + scalars[x.0],
+                            GtTerm::Pow(a, x) => value = value * a.pow(scalars[x.0]),
+                            GtTerm::Pairing(p, w) => pairs.push((*p, g2s[w.0])),
+                        }
+                    }
+                    pairs.push((G1::one() * gt_exp, G2::one()));
+                    gt_values.push(value * pairing_batch(&pairs));
+                }
+            }
+        }
+
+        (g1_values, gt_values)
+    }
+
+    /// Absorb the commitments of `proof` into the transcript and derive the challenge.
+    fn challenge(transcript: &mut Transcript, proof: &SigmaProof) -> Scalar {
+        for d in &proof.g1_commitments {
+            transcript.append_point(b"d_g1", d);
+        }
+        for d in &proof.gt_commitments {
+            transcript.append_gt(b"d_gt", d);
+        }
+        transcript.challenge_scalar(b"x")
+    }
+
+    /// Prove knowledge of `scalars` and `g2s`, given in declaration order, that satisfy the
+    /// relation. The statement must already be absorbed into the transcript.
+    pub fn prove<R: Rng + CryptoRng>(&self, transcript: &mut Transcript, scalars: &[Scalar], g2s: &[G2], rng: &mut R) -> Result<SigmaProof, ZkError> {
+        if scalars.len() != self.scalars || g2s.len() != self.g2s {
+            return Err(ZkError::ProofError);
+        }
+
+        let masks = (0..self.scalars).map(|_| Scalar::random(rng)).collect::<Vec<_>>();
+        let g2_masks = (0..self.g2s).map(|_| G2::random(rng)).collect::<Vec<_>>();
+        let (g1_commitments, gt_commitments) = self.evaluate(&masks, &g2_masks);
+
+        let mut proof = SigmaProof {
+            g1_commitments,
+            gt_commitments,
+            scalar_responses: Vec::new(),
+            g2_responses: Vec::new(),
+        };
+        let x = Self::challenge(transcript, &proof);
+
+        proof.scalar_responses = scalars.iter().zip(masks).map(|(w, m)| x * *w + m).collect();
+        proof.g2_responses = g2s.iter().zip(g2_masks).map(|(w, m)| *w * x + m).collect();
+        Ok(proof)
+    }
+
+    /// Verify a proof of the relation. The statement must already be absorbed into the transcript.
+    pub fn verify(&self, transcript: &mut Transcript, proof: &SigmaProof) -> Result<(), ZkError> {
+        // the weights of the check depend on the statement through the transcript
+        let mut seed = [0u8; 32];
+        let mut seed_transcript = transcript.clone();
+        Self::challenge(&mut seed_transcript, proof);
+        seed_transcript.challenge_bytes(b"weights", &mut seed);
+        let seed = [&seed[..], &proof.try_to_vec().map_err(|_| ZkError::VerificationError)?].concat();
+
+        let mut check = BatchCheck::new(&seed);
+        self.add_to_check(&mut check, transcript, proof)?;
+        check.verify()
+    }
+
+    /// Absorb the commitments of `proof` into the transcript and add the equations of the relation,
+    /// under the resulting challenge, to `check`.
+    pub(crate) fn add_to_check(&self, check: &mut BatchCheck, transcript: &mut Transcript, proof: &SigmaProof) -> Result<(), ZkError> {
+        let g1_equations = self.equations.iter().filter(|e| matches!(e, Equation::G1(..))).count();
+        if proof.g1_commitments.len() != g1_equations ||
+            proof.gt_commitments.len() != self.equations.len() - g1_equations ||
+            proof.scalar_responses.len() != self.scalars ||
+            proof.g2_responses.len() != self.g2s {
+            return Err(ZkError::VerificationError);
+        }
+
+        let x = Self::challenge(transcript, proof);
+        let z = &proof.scalar_responses;
+        let mut g1_commitments = proof.g1_commitments.iter();
+        let mut gt_commitments = proof.gt_commitments.iter();
+
+        // the responses satisfy every equation with the commitment plus x times the target
+        for equation in &self.equations {
+            match equation {
+                Equation::G1(terms, target) => {
+                    let d = g1_commitments.next().ok_or(ZkError::VerificationError)?;
+                    let mut items = terms.iter().map(|(p, v)| (*p, z[v.0])).collect::<Vec<_>>();
+                    items.push((*target, -x));
+                    items.push((*d, -Scalar::one()));
+                    check.add_g1(&items);
+                }
+                Equation::Gt(terms, target) => {
+                    let d = gt_commitments.next().ok_or(ZkError::VerificationError)?;
+                    let mut lhs = Vec::from([(*d, Scalar::one()), (*target, x)]);
+                    let mut gt_exp = Scalar::zero();
+                    let mut pairs = Vec::new();
+                    for term in terms {
+                        match term {
+                            GtTerm::Generator(v) => gt_exp = gt_exp + z[v.0],
+                            GtTerm::Pow(a, v) => lhs.push((*a, -z[v.0])),
+                            GtTerm::Pairing(p, w) => pairs.push((*p, proof.g2_responses[w.0])),
+                        }
+                    }
+                    check.add_gt(&lhs, gt_exp, &pairs);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+//! Extends the `TranscriptProtocol` of the range proofs with the domain separators and the
+//! group elements needed by the sigma protocols, so that both share one Merlin transcript.
+
+use bn::Gt;
+use merlin::Transcript;
+use crate::traits::ToBytes;
+
+/// Version of the sigma protocols, absorbed into every transcript.
+pub const SIGMA_PROTOCOL_VERSION: u64 = 1;
+
+pub trait SigmaTranscript {
+    /// Append a domain separator for a burn proof.
+    fn burn_domain_sep(&mut self);
+
+    /// Append a domain separator for a transfer proof.
+    fn transfer_domain_sep(&mut self);
+
+    /// Append a domain separator for a registration proof.
+    fn register_domain_sep(&mut self);
+
+    /// Append a domain separator for a transfer proof with `n` outputs.
+    fn multi_transfer_domain_sep(&mut self, n: u64);
+
+    /// Append a domain separator for a ring transfer proof with `n` members.
+    fn ring_transfer_domain_sep(&mut self, n: u64);
+
+    /// Append a Gt `element` with the given `label`.
+    fn append_gt(&mut self, label: &'static [u8], element: &Gt);
+}
+
+impl SigmaTranscript for Transcript {
+    fn burn_domain_sep(&mut self) {
+        self.append_message(b"dom-sep", b"faceless-burn");
+        self.append_u64(b"version", SIGMA_PROTOCOL_VERSION);
+    }
+
+    fn transfer_domain_sep(&mut self) {
+        self.append_message(b"dom-sep", b"faceless-transfer");
+        self.append_u64(b"version", SIGMA_PROTOCOL_VERSION);
+    }
+
+    fn register_domain_sep(&mut self) {
+        self.append_message(b"dom-sep", b"faceless-register");
+        self.append_u64(b"version", SIGMA_PROTOCOL_VERSION);
+    }
+
+    fn multi_transfer_domain_sep(&mut self, n: u64) {
+        self.append_message(b"dom-sep", b"faceless-multi-transfer");
+        self.append_u64(b"version", SIGMA_PROTOCOL_VERSION);
+        self.append_u64(b"n", n);
+    }
+
+    fn ring_transfer_domain_sep(&mut self, n: u64) {
+        self.append_message(b"dom-sep", b"faceless-ring-transfer");
+        self.append_u64(b"version", SIGMA_PROTOCOL_VERSION);
+        self.append_u64(b"n", n);
+    }
+
+    fn append_gt(&mut self, label: &'static [u8], element: &Gt) {
+        self.append_message(label, &element.to_bytes());
+    }
+}
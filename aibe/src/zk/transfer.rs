@@ -1,24 +1,95 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use bn::{Fr as Scalar, G1, G2, Gt, pairing, Group};
+use borsh::maybestd::vec::Vec;
+use bn::{Fr as Scalar, G1, G2, Gt, Group};
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use bulletproofs::transcript::TranscriptProtocol;
+use merlin::Transcript;
+use crate::encoding::impl_compressed_borsh;
 use crate::errors::ZkError;
-use crate::traits::ToBytes;
-use crate::utils::{hash_to_scalar};
-use rand::Rng;
+use crate::utils::scalar_to_u64;
+use crate::zk::{BALANCE_BITS, nonce_generator, verifier_rng};
+use crate::zk::batch::BatchCheck;
+use crate::zk::sigma::{GtTerm, Relation, SigmaProof};
+use crate::zk::transcript::SigmaTranscript;
+use rand::{CryptoRng, Rng};
 use core::ops::Neg;
-use borsh::maybestd::vec::Vec;
 
-#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone)]
+#[derive(Eq, PartialEq, Clone)]
 pub struct TransferStatement {
-    pub h1: G1,
     pub y: G1,
     pub y_bar: G1, 
+    /// Public key `e(y_bar, H(id_bar))` of the receiver, which identifies its account.
+    pub pk_id_bar: Gt,
     pub c1: G1,
     pub c2: Gt,
     pub c2_bar: Gt,
     pub c1_tilde: G1,
     pub c2_tilde: Gt,
+    /// Pedersen commitment (with `PedersenGens::default()`) to the transfer amount.
     pub c_b_star: G1,
+    /// Pedersen commitment (with `PedersenGens::default()`) to the remaining balance.
     pub c_b_prime: G1,
+    pub epoch: u64,
+    /// Nonce of the sender's identity key in `epoch`, see [`nonce`](crate::zk::nonce).
+    pub u: Gt,
+}
+
+impl_compressed_borsh!(TransferStatement { y, y_bar, pk_id_bar, c1, c2, c2_bar, c1_tilde, c2_tilde, c_b_star, c_b_prime, epoch, u });
+
+impl TransferStatement {
+    /// Absorb the domain separator and every element of the statement into the transcript.
+    fn append_to_transcript(&self, transcript: &mut Transcript) {
+        transcript.transfer_domain_sep();
+        transcript.append_point(b"y", &self.y);
+        transcript.append_point(b"y_bar", &self.y_bar);
+        transcript.append_gt(b"pk_id_bar", &self.pk_id_bar);
+        transcript.append_point(b"c1", &self.c1);
+        transcript.append_gt(b"c2", &self.c2);
+        transcript.append_gt(b"c2_bar", &self.c2_bar);
+        transcript.append_point(b"c1_tilde", &self.c1_tilde);
+        transcript.append_gt(b"c2_tilde", &self.c2_tilde);
+        transcript.append_point(b"c_b_star", &self.c_b_star);
+        transcript.append_point(b"c_b_prime", &self.c_b_prime);
+        transcript.append_u64(b"epoch", self.epoch);
+        transcript.append_gt(b"u", &self.u);
+    }
+
+    /// The relation proven by a transfer proof, over the secrets `r, s, r_star, r_prime, b_star,
+    /// b_prime` and `h_id, h_id * r, h_id_bar, h_id_bar * r, sk_id` of a [`TransferWitness`]:
+    /// `(c1, c2)` and `(c1, c2_bar)` encrypt the committed amount `b_star` under the identities of
+    /// `y` and `y_bar` with the randomness `r`, `pk_id_bar` is the public key of the identity of
+    /// `y_bar`, `(c1_tilde, c2_tilde)` decrypts to the committed remaining balance `b_prime` under
+    /// `sk_id = s * h_id`, and `u` is the nonce of `sk_id` in `epoch`.
+    pub fn relation(&self) -> Relation {
+        let pc_gens = PedersenGens::default();
+        let g_neg = G1::one().neg();
+        let mut relation = Relation::new();
+        let r = relation.scalar();
+        let s = relation.scalar();
+        let r_star = relation.scalar();
+        let r_prime = relation.scalar();
+        let b_star = relation.scalar();
+        let b_prime = relation.scalar();
+        let h_id = relation.g2();
+        let h_id_prime = relation.g2();
+        let h_id_bar = relation.g2();
+        let h_id_bar_prime = relation.g2();
+        let sk_id = relation.g2();
+
+        relation.g1_equation(&[(G1::one(), s)], self.y);
+        relation.g1_equation(&[(G1::one(), r)], self.c1);
+        relation.g1_equation(&[(pc_gens.B, b_star), (pc_gens.B_blinding, r_star)], self.c_b_star);
+        relation.g1_equation(&[(pc_gens.B, b_prime), (pc_gens.B_blinding, r_prime)], self.c_b_prime);
+        relation.gt_equation(&[GtTerm::Pairing(self.c1, h_id), GtTerm::Pairing(g_neg, h_id_prime)], Gt::one());
+        relation.gt_equation(&[GtTerm::Pairing(self.c1, h_id_bar), GtTerm::Pairing(g_neg, h_id_bar_prime)], Gt::one());
+        relation.gt_equation(&[GtTerm::Pairing(self.y, h_id), GtTerm::Pairing(g_neg, sk_id)], Gt::one());
+        relation.gt_equation(&[GtTerm::Pairing(self.y_bar, h_id_bar)], self.pk_id_bar);
+        relation.gt_equation(&[GtTerm::Generator(b_star), GtTerm::Pairing(self.y, h_id_prime)], self.c2);
+        relation.gt_equation(&[GtTerm::Generator(b_star), GtTerm::Pairing(self.y_bar, h_id_bar_prime)], self.c2_bar);
+        relation.gt_equation(&[GtTerm::Generator(b_prime), GtTerm::Pairing(self.c1_tilde, sk_id)], self.c2_tilde);
+        relation.gt_equation(&[GtTerm::Pairing(nonce_generator(self.epoch), sk_id)], self.u);
+        relation
+    }
 }
 
 #[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone, Debug)]
@@ -35,20 +106,25 @@ pub struct TransferWitness {
 
 }
 
-#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone, Debug)]
-pub struct TransferProof {
-    pub x: Scalar,
-    pub zr: Scalar,
-    pub zs: Scalar,
-    pub zr_star: Scalar,
-    pub zr_prime: Scalar,
-    pub zb_star: Scalar,
-    pub zb_prime: Scalar,
-    pub z_id: G2,
-    pub z_id_prime: G2,
-    pub z_id_bar: G2,
-    pub z_id_bar_prime: G2,
-    pub z_sk: G2,
+impl TransferWitness {
+    /// The values of the secrets of [`TransferStatement::relation`].
+    fn assignment(&self) -> (Vec<Scalar>, Vec<G2>) {
+        (
+            Vec::from([self.r, self.s, self.r_star, self.r_prime, self.b_star, self.b_prime]),
+            Vec::from([self.h_id, self.h_id * self.r, self.h_id_bar, self.h_id_bar * self.r, self.sk_id]),
+        )
+    }
+}
+
+/// A proof of [`TransferStatement::relation`].
+pub type TransferProof = SigmaProof;
+
+/// A transfer proof together with an aggregated range proof showing that both the transfer amount
+/// and the remaining balance lie in `[0, 2^BALANCE_BITS)`.
+#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone)]
+pub struct FullTransferProof {
+    pub proof: TransferProof,
+    pub range_proof: RangeProof,
 }
 
 pub struct TransferProver<R> {
@@ -57,7 +133,7 @@ pub struct TransferProver<R> {
 
 
 impl<R> TransferProver<R> 
-where R: Rng {
+where R: Rng + CryptoRng {
     pub fn new(rng: R) -> Self {
         Self {
             rng
@@ -65,78 +141,49 @@ where R: Rng {
     }
 
     pub fn generate_proof(&mut self, statement: TransferStatement, witness: TransferWitness) -> TransferProof {
-        let mr = Scalar::random(&mut self.rng); 
-        let ms = Scalar::random(&mut self.rng);
-        let mr_star = Scalar::random(&mut self.rng);
-        let mr_prime = Scalar::random(&mut self.rng);
-        let mb_star = Scalar::random(&mut self.rng);
-        let mb_prime = Scalar::random(&mut self.rng);
-
-        let m_id = G2::random(&mut self.rng);
-        let m_id_prime = G2::random(&mut self.rng);
-        let m_id_bar = G2::random(&mut self.rng);
-        let m_id_bar_prime = G2::random(&mut self.rng);
-        let m_sk = G2::random(&mut self.rng);
-
-
-        let d_y = G1::one() * ms;
-        let d_1 = G1::one() * mr;
-        let d_b_star = G1::one() * mb_star + statement.h1 * mr_star; 
-        let d_b_prime = G1::one() * mb_prime + statement.h1 * mr_prime;
-
-        let r = pairing(statement.c1, m_id) * pairing(G1::one().neg(), m_id_prime);
-        let r_bar = pairing(statement.c1, m_id_bar) * pairing(G1::one().neg(), m_id_bar_prime);
-        let r_sk = pairing(statement.y, m_id) * pairing(G1::one().neg(), m_sk);
-        
-        let gt = pairing(G1::one(), G2::one());
-        let d_2 = gt.pow(mb_star) * pairing(statement.y, m_id_prime);
-        let d_2_bar = gt.pow(mb_star) * pairing(statement.y_bar, m_id_bar_prime);
-        let d_2_tilde = gt.pow(mb_prime) * pairing(statement.c1_tilde, m_sk);
-        
-        let script = d_y.to_bytes().iter()
-            .chain(d_1.to_bytes().iter())
-            .chain(d_b_star.to_bytes().iter())
-            .chain(d_b_prime.to_bytes().iter())
-            .chain(r.to_bytes().iter())
-            .chain(r_bar.to_bytes().iter())
-            .chain(r_sk.to_bytes().iter())
-            .chain(d_2.to_bytes().iter())
-            .chain(d_2_bar.to_bytes().iter())
-            .chain(d_2_tilde.to_bytes().iter())
-            .map(|x| *x)
-            .collect::<Vec<_>>();
-        let x = hash_to_scalar(&script);
-
-        let zr = x * witness.r + mr;
-        let zs = x * witness.s + ms;
-        let zr_star = x * witness.r_star + mr_star;
-        let zr_prime = x * witness.r_prime + mr_prime;
-        let zb_star = x * witness.b_star + mb_star;
-        let zb_prime = x * witness.b_prime + mb_prime;
-
-        let h_id_prime = witness.h_id * witness.r;
-        let h_id_bar_prime = witness.h_id_bar * witness.r;
-
-        let z_id = witness.h_id * x + m_id; 
-        let z_id_prime = h_id_prime * x + m_id_prime;
-        let z_id_bar = witness.h_id_bar * x + m_id_bar;
-        let z_id_bar_prime = h_id_bar_prime * x + m_id_bar_prime;
-        let z_sk = witness.sk_id * x + m_sk;
-
-        TransferProof {
-            x,
-            zr,
-            zs,
-            zr_star,
-            zr_prime,
-            zb_star,
-            zb_prime,
-            z_id,
-            z_id_prime,
-            z_id_bar,
-            z_id_bar_prime,
-            z_sk,
+        let mut transcript = Transcript::new(b"faceless-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        self.prove_sigma(&mut transcript, statement, witness)
+    }
+
+    /// Generate a transfer proof together with an aggregated range proof on the committed
+    /// transfer amount and remaining balance. Both proofs share one transcript, so the challenge
+    /// of the transfer proof also binds the range proof.
+    pub fn generate_full_proof(&mut self, statement: TransferStatement, witness: TransferWitness) -> Result<FullTransferProof, ZkError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(BALANCE_BITS, 2);
+
+        let mut transcript = Transcript::new(b"faceless-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        let (range_proof, commitments) = RangeProof::prove_multiple_with_rng(
+            &bp_gens,
+            &pc_gens,
+            &mut transcript,
+            &[scalar_to_u64(witness.b_star), scalar_to_u64(witness.b_prime)],
+            &[witness.r_star, witness.r_prime],
+            BALANCE_BITS,
+            &mut self.rng,
+        ).map_err(|_| ZkError::ProofError)?;
+
+        if commitments != [statement.c_b_star, statement.c_b_prime] {
+            return Err(ZkError::ProofError);
         }
+
+        let proof = self.prove_sigma(&mut transcript, statement, witness);
+
+        Ok(FullTransferProof {
+            proof,
+            range_proof,
+        })
+    }
+
+    fn prove_sigma(&mut self, transcript: &mut Transcript, statement: TransferStatement, witness: TransferWitness) -> TransferProof {
+        let (scalars, g2s) = witness.assignment();
+        statement.relation()
+            .prove(transcript, &scalars, &g2s, &mut self.rng)
+            .expect("the witness assigns every secret of the relation; qed")
     }
 }
 
@@ -145,46 +192,50 @@ pub struct TransferVerifier;
 
 impl TransferVerifier {
     pub fn verify_proof(statement: TransferStatement, proof: TransferProof) -> Result<(), ZkError> {
-        let d_y = G1::one() * proof.zs - statement.y * proof.x;
-        let d_1 = G1::one() * proof.zr - statement.c1 * proof.x;
-        let d_b_star = G1::one() * proof.zb_star + statement.h1 * proof.zr_star - statement.c_b_star * proof.x;
-        let d_b_prime = G1::one() * proof.zb_prime + statement.h1 * proof.zr_prime - statement.c_b_prime * proof.x;
-
-        let r = pairing(statement.c1, proof.z_id) * pairing(G1::one().neg(), proof.z_id_prime);
-        let r_bar = pairing(statement.c1, proof.z_id_bar) * pairing(G1::one().neg(), proof.z_id_bar_prime);
-        let r_sk = pairing(statement.y, proof.z_id) * pairing(G1::one().neg(), proof.z_sk);
-
-        let gt = pairing(G1::one(), G2::one());
-        let d_2 = gt.pow(proof.zb_star) * 
-            pairing(statement.y, proof.z_id_prime) *
-            statement.c2.pow(proof.x).inverse().unwrap();
-        let d_2_bar = gt.pow(proof.zb_star) *
-            pairing(statement.y_bar, proof.z_id_bar_prime) *
-            statement.c2_bar.pow(proof.x).inverse().unwrap();
-        let d_2_tilde = gt.pow(proof.zb_prime) * 
-            pairing(statement.c1_tilde, proof.z_sk) *
-            statement.c2_tilde.pow(proof.x).inverse().unwrap();
-
-        let script = d_y.to_bytes().iter()
-            .chain(d_1.to_bytes().iter())
-            .chain(d_b_star.to_bytes().iter())
-            .chain(d_b_prime.to_bytes().iter())
-            .chain(r.to_bytes().iter())
-            .chain(r_bar.to_bytes().iter())
-            .chain(r_sk.to_bytes().iter())
-            .chain(d_2.to_bytes().iter())
-            .chain(d_2_bar.to_bytes().iter())
-            .chain(d_2_tilde.to_bytes().iter())
-            .map(|x| *x)
-            .collect::<Vec<_>>();
-        let x = hash_to_scalar(&script);
-
-        if x == proof.x {
-            Ok(())
-        }
-        else {
-            Err(ZkError::VerificationError)
+        let seed = (&statement, &proof).try_to_vec().map_err(|_| ZkError::VerificationError)?;
+        let mut check = BatchCheck::new(&seed);
+
+        let mut transcript = Transcript::new(b"faceless-transfer");
+        statement.append_to_transcript(&mut transcript);
+
+        statement.relation().add_to_check(&mut check, &mut transcript, &proof)?;
+        check.verify()
+    }
+
+    /// Verify a transfer proof together with the aggregated range proof on the committed
+    /// transfer amount and remaining balance.
+    pub fn verify_full_proof(statement: TransferStatement, proof: FullTransferProof) -> Result<(), ZkError> {
+        Self::verify_batch(&[(statement, proof)])
+    }
+
+    /// Verify many transfer proofs at once. The range proofs are verified one by one, while the
+    /// equations of all transfer proofs are checked with a single multi-pairing.
+    pub fn verify_batch(instances: &[(TransferStatement, FullTransferProof)]) -> Result<(), ZkError> {
+        let pc_gens = PedersenGens::default();
+        let bp_gens = BulletproofGens::new(BALANCE_BITS, 2);
+
+        let seed = instances.try_to_vec().map_err(|_| ZkError::VerificationError)?;
+        let mut check = BatchCheck::new(&seed);
+
+        for (statement, proof) in instances {
+            let mut transcript = Transcript::new(b"faceless-transfer");
+            statement.append_to_transcript(&mut transcript);
+
+            let mut rng = verifier_rng(&proof.try_to_vec().map_err(|_| ZkError::VerificationError)?);
+            proof.range_proof
+                .verify_multiple_with_rng(
+                    &bp_gens,
+                    &pc_gens,
+                    &mut transcript,
+                    &[statement.c_b_star, statement.c_b_prime],
+                    BALANCE_BITS,
+                    &mut rng,
+                )
+                .map_err(|_| ZkError::VerificationError)?;
+
+            statement.relation().add_to_check(&mut check, &mut transcript, &proof.proof)?;
         }
+
+        check.verify()
     }
 }
-
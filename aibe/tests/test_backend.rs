@@ -0,0 +1,83 @@
+use aibe::traits::IdentityBasedEncryption;
+use aibe::bf_ibe::{BFIbe, pairing};
+use aibe::utils::{u64_to_scalar, hash_to_g2, pedersen_commitment};
+use aibe::zk::nonce;
+use aibe::zk::transfer::{TransferStatement, TransferWitness, TransferProver, TransferVerifier, FullTransferProof};
+use bn::backend::{self, Backend, native};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// A backend that counts the operations it runs natively, as a host backend would.
+static COUNTING: Backend = Backend {
+    g1_multiexp: |items| { CALLS.fetch_add(1, Ordering::SeqCst); native::g1_multiexp(items) },
+    g2_multiexp: |items| { CALLS.fetch_add(1, Ordering::SeqCst); native::g2_multiexp(items) },
+    gt_pow: |a, s| { CALLS.fetch_add(1, Ordering::SeqCst); native::gt_pow(a, s) },
+    pairing_batch: |pairs| { CALLS.fetch_add(1, Ordering::SeqCst); native::pairing_batch(pairs) },
+};
+
+fn transfer_instance() -> (TransferStatement, FullTransferProof) {
+    let mut rng = rand::thread_rng();
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let (msk1, mpk1) = ibe.generate_key();
+    let (_, mpk2) = ibe.generate_key();
+    let sk1 = ibe.extract("zico1", &msk1);
+
+    let b_star = u64_to_scalar(40);
+    let b_prime = u64_to_scalar(20);
+    let c_balance = ibe.encrypt(&u64_to_scalar(60), "zico1", &mpk1);
+    let ((c_transfer, c_transfer_bar), _, r) =
+        ibe.encrypt_correlated_internal(&b_star, ("zico1", "zico2"), (&mpk1, &mpk2));
+
+    let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng);
+    let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+    let statement = TransferStatement {
+        y: mpk1,
+        y_bar: mpk2,
+        pk_id_bar: pairing(mpk2, hash_to_g2("zico2".as_bytes())),
+        c1: c_transfer.0,
+        c2: c_transfer.1,
+        c2_bar: c_transfer_bar.1,
+        c1_tilde: c_balance.0 - c_transfer.0,
+        c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
+        c_b_star,
+        c_b_prime,
+        epoch: 0,
+        u: nonce(&sk1, 0),
+    };
+    let witness = TransferWitness {
+        r,
+        s: msk1,
+        r_star,
+        r_prime,
+        b_star,
+        b_prime,
+        h_id: hash_to_g2("zico1".as_bytes()),
+        h_id_bar: hash_to_g2("zico2".as_bytes()),
+        sk_id: sk1,
+    };
+
+    let proof = TransferProver::new(rng).generate_full_proof(statement.clone(), witness).unwrap();
+    (statement, proof)
+}
+
+#[test]
+fn test_verify_on_backend() {
+    let (statement, proof) = transfer_instance();
+
+    // The pairings and multiexps of the verifier, including the range proof, run on the backend.
+    backend::set_backend(&COUNTING);
+    let result = TransferVerifier::verify_full_proof(statement.clone(), proof.clone());
+    backend::reset_backend();
+    assert!(result.is_ok());
+    assert!(CALLS.load(Ordering::SeqCst) > 0);
+
+    let calls = CALLS.load(Ordering::SeqCst);
+    let mut other = statement;
+    other.c2_bar = other.c2_bar * other.c2;
+    assert!(TransferVerifier::verify_full_proof(other, proof).is_err());
+    assert_eq!(CALLS.load(Ordering::SeqCst), calls);
+}
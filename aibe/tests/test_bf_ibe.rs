@@ -1,7 +1,7 @@
 
 use aibe::traits::{IdentityBasedEncryption};
 use aibe::bf_ibe::{BFIbe};
-use aibe::utils::{u64_to_scalar};
+use aibe::utils::{u64_to_scalar, i128_to_scalar};
 use rand::Rng;
 
 
@@ -42,3 +42,35 @@ fn test_bf_ibe() {
 }
 
 
+#[test]
+fn test_bf_ibe_signed() {
+    let rng = rand::thread_rng(); 
+    let bound: u64 = 100;
+
+    let mut ibe = BFIbe::new(rng);
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+
+    for plain in [-100i128, -37, -1, 0, 1, 37, 100] {
+        let cipher = ibe.encrypt(&i128_to_scalar(plain), "zico", &mpk);
+
+        assert_eq!(ibe.decrypt_signed(&cipher, "zico", &sk, bound), Ok(plain));
+        assert_eq!(ibe.decrypt(&cipher, "zico", &sk, bound), Ok(i128_to_scalar(plain)));
+    }
+}
+
+#[test]
+fn test_bf_ibe_overdrawn() {
+    let rng = rand::thread_rng(); 
+
+    let mut ibe = BFIbe::new(rng);
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+
+    // Withdrawing 25 from a balance of 10 leaves an encrypted -15.
+    let balance = ibe.encrypt(&u64_to_scalar(10), "zico", &mpk);
+    let withdraw = ibe.encrypt(&-u64_to_scalar(25), "zico", &mpk);
+    let remaining = BFIbe::<rand::rngs::ThreadRng>::add_ciphers(&balance, &withdraw);
+
+    assert_eq!(ibe.decrypt_signed(&remaining, "zico", &sk, 100), Ok(-15));
+}
@@ -0,0 +1,68 @@
+use aibe::bf_ibe::{BFIbe, G1, G2, Group, pairing};
+use aibe::dlog::DlogTable;
+use aibe::errors::IbeError;
+use aibe::traits::IdentityBasedEncryption;
+use aibe::utils::{u64_to_scalar, i128_to_scalar};
+use rand::rngs::ThreadRng;
+
+
+#[test]
+fn test_dlog_table() {
+    let g = pairing(G1::one(), G2::one());
+    let table = DlogTable::new(g, 1000);
+
+    for x in [-1000i128, -999, -31, -1, 0, 1, 31, 999, 1000] {
+        assert_eq!(table.solve(g.pow(i128_to_scalar(x))), Ok(x));
+    }
+    assert_eq!(table.solve(g.pow(u64_to_scalar(1_000_000))), Err(IbeError::OutOfBoundError));
+
+    // The giant steps reach m * m + m = 1056, but the table only answers up to its bound.
+    for x in [1001i128, -1001, 1056, -1056] {
+        assert_eq!(table.solve(g.pow(i128_to_scalar(x))), Err(IbeError::OutOfBoundError));
+    }
+}
+
+#[test]
+fn test_dlog_table_serialization() {
+    let g = pairing(G1::one(), G2::one());
+    let table = DlogTable::new(g, 1000);
+
+    let bytes = table.to_bytes();
+    // 8 bytes per baby step, the header and the Borsh encoded base.
+    assert_eq!(bytes.len(), 8 * 33 + 24 + 384);
+
+    let loaded = DlogTable::from_bytes(&bytes).unwrap();
+    assert!(loaded == table);
+    assert_eq!(loaded.solve(g.pow(-u64_to_scalar(77))), Ok(-77));
+
+    assert!(DlogTable::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(DlogTable::from_bytes(&bytes[1..]).is_err());
+}
+
+#[test]
+fn test_dlog_table_save_load() {
+    let g = pairing(G1::one(), G2::one());
+    let table = DlogTable::new(g, 1000);
+
+    let path = std::env::temp_dir().join(format!("aibe-dlog-{}.bin", std::process::id()));
+    table.save(&path).unwrap();
+    let loaded = DlogTable::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(loaded.unwrap() == table);
+}
+
+#[test]
+fn test_decrypt_with_table() {
+    let mut ibe = BFIbe::new(rand::thread_rng());
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+    let table = DlogTable::new(pairing(G1::one(), G2::one()), 1 << 20);
+
+    for plain in [-(1i128 << 20), -5, 0, 123_456, 1 << 20] {
+        let cipher = ibe.encrypt(&i128_to_scalar(plain), "zico", &mpk);
+
+        assert_eq!(BFIbe::<ThreadRng>::decrypt_signed_with_table(&cipher, &sk, &table), Ok(plain));
+        assert_eq!(BFIbe::<ThreadRng>::decrypt_with_table(&cipher, &sk, &table), Ok(i128_to_scalar(plain)));
+    }
+}
@@ -0,0 +1,94 @@
+use aibe::traits::{IdentityBasedEncryption};
+use aibe::bf_ibe::{BFIbe, CipherText};
+use aibe::encoding::Compressed;
+use aibe::utils::{u64_to_scalar, hash_to_g2};
+use aibe::zk::burn::{BurnStatement, BurnWitness, BurnProver, BurnVerifier, FullBurnProof};
+use aibe::zk::nonce;
+use bn::{Fr as Scalar, Gt, G1, G2, Group, pairing};
+use borsh::{BorshDeserialize, BorshSerialize};
+use bulletproofs::PedersenGens;
+
+
+#[test]
+fn test_compressed_points() {
+    let mut rng = rand::thread_rng();
+
+    let p = G1::random(&mut rng);
+    let q = G2::random(&mut rng);
+    let gt = pairing(p, q);
+
+    let bytes = Compressed((p, (q, gt))).try_to_vec().unwrap();
+    assert_eq!(bytes.len(), 33 + 65 + 192);
+
+    let decoded = Compressed::<(G1, (G2, Gt))>::try_from_slice(&bytes).unwrap();
+    assert!(decoded == Compressed((p, (q, gt))));
+
+    // truncated, trailing and corrupted input
+    assert!(Compressed::<(G1, (G2, Gt))>::try_from_slice(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Compressed::<(G1, (G2, Gt))>::try_from_slice(&[bytes.clone(), vec![0]].concat()).is_err());
+    let mut corrupted = bytes;
+    corrupted[0] = 7;
+    assert!(Compressed::<(G1, (G2, Gt))>::try_from_slice(&corrupted).is_err());
+}
+
+#[test]
+fn test_compressed_cipher() {
+    let mut ibe = BFIbe::new(rand::thread_rng());
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+    let cipher = ibe.encrypt(&u64_to_scalar(42), "zico", &mpk);
+
+    let bytes = Compressed(cipher).try_to_vec().unwrap();
+    assert_eq!(bytes.len(), 33 + 192);
+
+    let decoded = Compressed::<CipherText>::try_from_slice(&bytes).unwrap().0;
+    assert_eq!(ibe.decrypt(&decoded, "zico", &sk, 100), Ok(u64_to_scalar(42)));
+}
+
+#[test]
+fn test_compressed_burn() {
+    let mut rng = rand::thread_rng();
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+    let cipher = ibe.encrypt(&u64_to_scalar(60), "zico", &mpk);
+
+    let r_b = Scalar::random(&mut rng);
+    let statement = BurnStatement {
+        y: mpk,
+        c1_id: cipher.0,
+        c2_id: cipher.1,
+        c_b: PedersenGens::default().commit(u64_to_scalar(60), r_b),
+        epoch: 0,
+        u: nonce(&sk, 0),
+    };
+    let witness = BurnWitness {
+        b: u64_to_scalar(60),
+        s: msk,
+        r_b,
+        h_id: hash_to_g2("zico".as_bytes()),
+        sk_id: sk,
+    };
+
+    let mut prover = BurnProver::new(rng.clone());
+    let proof = prover.generate_full_proof(statement.clone(), witness, b"alice").unwrap();
+
+    let statement_bytes = statement.try_to_vec().unwrap();
+    assert_eq!(statement_bytes.len(), 33 + 33 + 192 + 33 + 8 + 192);
+    let proof_bytes = proof.try_to_vec().unwrap();
+    // the sigma proof (two G1 and three Gt commitments, three scalar and two G2 responses), then
+    // the compact range proof of BALANCE_BITS = 32 bits (four points, three scalars, five pairs
+    // of points and two scalars), each vector and the range proof prefixed with their length
+    assert_eq!(proof_bytes.len(), (4 * 4 + 2 * 33 + 3 * 192 + 3 * 32 + 2 * 65) + (4 + 4 * 33 + 3 * 32 + 5 * 2 * 33 + 2 * 32));
+
+    let statement = BurnStatement::try_from_slice(&statement_bytes).unwrap();
+    let proof = FullBurnProof::try_from_slice(&proof_bytes).unwrap();
+    assert!(BurnVerifier::verify_full_proof(statement, proof, b"alice").is_ok());
+
+    // A Gt element that is not in the order r subgroup is rejected.
+    let mut invalid = statement_bytes;
+    invalid[66..66 + 192].copy_from_slice(&[0u8; 192]);
+    invalid[66 + 31] = 1;
+    assert!(BurnStatement::try_from_slice(&invalid).is_err());
+}
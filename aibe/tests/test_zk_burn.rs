@@ -3,6 +3,9 @@ use aibe::traits::{IdentityBasedEncryption};
 use aibe::bf_ibe::{BFIbe};
 use aibe::utils::{u64_to_scalar, hash_to_g2};
 use aibe::zk::burn::{BurnStatement, BurnWitness, BurnProver, BurnVerifier};
+use aibe::zk::nonce;
+use bn::{Fr as Scalar, G1, G2, Group, pairing};
+use bulletproofs::PedersenGens;
 use rand::Rng;
 
 
@@ -42,23 +45,187 @@ fn test_zk_burn() {
     assert_eq!(result.unwrap(), plain);
 
 
+    let r_b = Scalar::random(&mut rng);
     let statement = BurnStatement {
         y: mpk,
         c1_id: cipher.0,
         c2_id: cipher.1,
+        c_b: PedersenGens::default().commit(plain, r_b),
+        epoch: 0,
+        u: nonce(&sk, 0),
     };
     let witness = BurnWitness {
         b: plain,
         s: msk,
+        r_b,
         h_id: hash_to_g2("zico".as_bytes()),
         sk_id: sk,
     };
 
     let mut prover = BurnProver::new(rng.clone());
-    let proof = prover.generate_proof(statement.clone(), witness);
+    let proof = prover.generate_proof(statement.clone(), witness, b"alice");
 
-    let result = BurnVerifier::verify_proof(statement, proof);
+    let result = BurnVerifier::verify_proof(statement, proof, b"alice");
     assert!(result.is_ok());
 }
 
 
+#[test]
+fn test_zk_partial_withdraw() {
+    let mut rng = rand::thread_rng(); 
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+
+    // Balance of 60, of which 25 is withdrawn.
+    let balance = ibe.encrypt(&u64_to_scalar(60), "zico", &mpk);
+    let amount = u64_to_scalar(25);
+    let remaining = (balance.0, balance.1 * pairing(G1::one(), G2::one()).pow(-amount));
+
+    let r_b = Scalar::random(&mut rng);
+    let statement = BurnStatement {
+        y: mpk,
+        c1_id: remaining.0,
+        c2_id: remaining.1,
+        c_b: PedersenGens::default().commit(u64_to_scalar(35), r_b),
+        epoch: 0,
+        u: nonce(&sk, 0),
+    };
+    let witness = BurnWitness {
+        b: u64_to_scalar(35),
+        s: msk,
+        r_b,
+        h_id: hash_to_g2("zico".as_bytes()),
+        sk_id: sk,
+    };
+
+    let mut prover = BurnProver::new(rng.clone());
+    let proof = prover.generate_full_proof(statement.clone(), witness, b"alice").unwrap();
+    assert!(BurnVerifier::verify_full_proof(statement.clone(), proof.clone(), b"alice").is_ok());
+
+    // The proof does not hold for a different withdrawn amount.
+    let mut other = statement;
+    other.c2_id = other.c2_id * pairing(G1::one(), G2::one()).pow(-u64_to_scalar(1));
+    assert!(BurnVerifier::verify_full_proof(other, proof, b"alice").is_err());
+}
+
+#[test]
+fn test_zk_overdrawn_withdraw() {
+    let mut rng = rand::thread_rng(); 
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+
+    // Withdrawing 70 from a balance of 60 leaves a negative value that cannot be range proven.
+    let balance = ibe.encrypt(&u64_to_scalar(60), "zico", &mpk);
+    let remaining = (balance.0, balance.1 * pairing(G1::one(), G2::one()).pow(-u64_to_scalar(70)));
+    let b = -u64_to_scalar(10);
+
+    let r_b = Scalar::random(&mut rng);
+    let statement = BurnStatement {
+        y: mpk,
+        c1_id: remaining.0,
+        c2_id: remaining.1,
+        c_b: PedersenGens::default().commit(b, r_b),
+        epoch: 0,
+        u: nonce(&sk, 0),
+    };
+    let witness = BurnWitness {
+        b,
+        s: msk,
+        r_b,
+        h_id: hash_to_g2("zico".as_bytes()),
+        sk_id: sk,
+    };
+
+    let mut prover = BurnProver::new(rng.clone());
+    assert!(prover.generate_full_proof(statement, witness, b"alice").is_err());
+}
+
+#[test]
+fn test_zk_burn_bound_to_statement() {
+    let mut rng = rand::thread_rng(); 
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let (msk, mpk) = ibe.generate_key();
+    let (_, other_mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+    let cipher = ibe.encrypt(&u64_to_scalar(60), "zico", &mpk);
+
+    let r_b = Scalar::random(&mut rng);
+    let statement = BurnStatement {
+        y: mpk,
+        c1_id: cipher.0,
+        c2_id: cipher.1,
+        c_b: PedersenGens::default().commit(u64_to_scalar(60), r_b),
+        epoch: 0,
+        u: nonce(&sk, 0),
+    };
+    let witness = BurnWitness {
+        b: u64_to_scalar(60),
+        s: msk,
+        r_b,
+        h_id: hash_to_g2("zico".as_bytes()),
+        sk_id: sk,
+    };
+
+    let mut prover = BurnProver::new(rng.clone());
+    let proof = prover.generate_full_proof(statement.clone(), witness, b"alice").unwrap();
+
+    // The proof does not verify in another context, e.g. for another destination.
+    assert!(BurnVerifier::verify_full_proof(statement.clone(), proof.clone(), b"mallory").is_err());
+
+    // The proof does not verify for a statement with another master public key.
+    let mut other = statement;
+    other.y = other_mpk;
+    assert!(BurnVerifier::verify_full_proof(other.clone(), proof.clone(), b"alice").is_err());
+    assert!(BurnVerifier::verify_proof(other, proof.proof, b"alice").is_err());
+}
+
+#[test]
+fn test_zk_burn_nonce() {
+    let mut rng = rand::thread_rng(); 
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+    let cipher = ibe.encrypt(&u64_to_scalar(60), "zico", &mpk);
+
+    // The nonce is the same for every proof in an epoch and changes with the epoch.
+    assert!(nonce(&sk, 7) == nonce(&sk, 7));
+    assert!(nonce(&sk, 7) != nonce(&sk, 8));
+
+    let r_b = Scalar::random(&mut rng);
+    let statement = BurnStatement {
+        y: mpk,
+        c1_id: cipher.0,
+        c2_id: cipher.1,
+        c_b: PedersenGens::default().commit(u64_to_scalar(60), r_b),
+        epoch: 7,
+        u: nonce(&sk, 7),
+    };
+    let witness = BurnWitness {
+        b: u64_to_scalar(60),
+        s: msk,
+        r_b,
+        h_id: hash_to_g2("zico".as_bytes()),
+        sk_id: sk,
+    };
+
+    let mut prover = BurnProver::new(rng.clone());
+    let proof = prover.generate_full_proof(statement.clone(), witness.clone(), b"alice").unwrap();
+    assert!(BurnVerifier::verify_full_proof(statement.clone(), proof.clone(), b"alice").is_ok());
+
+    // The proof does not verify in another epoch.
+    let mut other = statement.clone();
+    other.epoch = 8;
+    assert!(BurnVerifier::verify_full_proof(other, proof, b"alice").is_err());
+
+    // No proof can be made with a nonce that does not belong to the key.
+    let mut other = statement;
+    other.u = nonce(&(sk * u64_to_scalar(2)), 7);
+    let proof = prover.generate_full_proof(other.clone(), witness, b"alice").unwrap();
+    assert!(BurnVerifier::verify_full_proof(other, proof, b"alice").is_err());
+}
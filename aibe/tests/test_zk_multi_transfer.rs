@@ -0,0 +1,150 @@
+
+use aibe::traits::{IdentityBasedEncryption};
+use aibe::bf_ibe::{BFIbe, pairing};
+use aibe::utils::hash_to_g2;
+use aibe::utils::{u64_to_scalar, pedersen_commitment};
+use aibe::zk::nonce;
+use aibe::zk::multi_transfer::{MultiTransferStatement, MultiTransferWitness, MultiTransferProver, MultiTransferVerifier};
+use aibe::bf_ibe::Fr as Scalar;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+/// Build a transfer of `outputs` to as many recipients out of a balance of `b`.
+fn multi_transfer_instance(b: u64, outputs: &[Scalar]) -> (MultiTransferStatement, MultiTransferWitness) {
+    let mut rng = rand::thread_rng(); 
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let b = u64_to_scalar(b);
+    let b_prime = outputs.iter().fold(b, |acc, b_star| acc - *b_star);
+
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+    let recipients = outputs.iter().map(|_| ibe.generate_key().1).collect::<Vec<_>>();
+    let ids = (0..outputs.len()).map(|i| format!("zico{}", i)).collect::<Vec<_>>();
+    let recipients = ids.iter().map(|id| id.as_str()).zip(recipients.iter()).collect::<Vec<_>>();
+
+    let c_balance = ibe.encrypt(&b, "zico", &mpk);
+    let ((c_transfer, c_outputs), (h_id, h_id_bars), r) = ibe.encrypt_multi_internal(outputs, ("zico", &mpk), &recipients);
+
+    let (r_stars, c_b_stars) = outputs.iter().map(|b_star| pedersen_commitment(*b_star, &mut rng)).unzip();
+    let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng); 
+
+    let statement = MultiTransferStatement {
+        y: mpk,
+        y_bars: recipients.iter().map(|(_, mpk)| **mpk).collect(),
+        pk_id_bars: recipients.iter().zip(&h_id_bars).map(|((_, mpk), h_id_bar)| pairing(**mpk, *h_id_bar)).collect(),
+        c1: c_transfer.0,
+        c2: c_transfer.1,
+        c2_bars: c_outputs.iter().map(|c| c.1).collect(),
+        c1_tilde: c_balance.0 - c_transfer.0,
+        c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
+        c_b_stars,
+        c_b_prime,
+        epoch: 0,
+        u: nonce(&sk, 0),
+    };
+    let witness = MultiTransferWitness {
+        r,
+        s: msk,
+        r_stars,
+        r_prime,
+        b_stars: outputs.to_vec(),
+        b_prime,
+        h_id,
+        h_id_bars,
+        sk_id: sk,
+    };
+
+    (statement, witness)
+}
+
+#[test]
+fn test_zk_multi_transfer() {
+    for outputs in [vec![40], vec![10, 20], vec![5, 10, 15, 20]] {
+        let outputs = outputs.into_iter().map(u64_to_scalar).collect::<Vec<_>>();
+        let (statement, witness) = multi_transfer_instance(60, &outputs);
+
+        let mut prover = MultiTransferProver::new(rand::thread_rng());
+        let proof = prover.generate_full_proof(statement.clone(), witness).unwrap();
+
+        let statement = MultiTransferStatement::try_from_slice(&statement.try_to_vec().unwrap()).unwrap();
+        assert!(MultiTransferVerifier::verify_full_proof(statement, proof).is_ok());
+    }
+}
+
+#[test]
+fn test_zk_multi_transfer_decrypt() {
+    let mut ibe = BFIbe::new(rand::thread_rng());
+    let (msk1, mpk1) = ibe.generate_key();
+    let (msk2, mpk2) = ibe.generate_key();
+
+    let ((c_transfer, c_outputs), _, _) = ibe.encrypt_multi_internal(
+        &[u64_to_scalar(10), u64_to_scalar(20)],
+        ("zico", &mpk1),
+        &[("zico", &mpk1), ("zico2", &mpk2)],
+    );
+
+    let sk1 = ibe.extract("zico", &msk1);
+    let sk2 = ibe.extract("zico2", &msk2);
+    assert_eq!(ibe.decrypt_signed(&c_transfer, "zico", &sk1, 100), Ok(30));
+    assert_eq!(ibe.decrypt_signed(&c_outputs[0], "zico", &sk1, 100), Ok(10));
+    assert_eq!(ibe.decrypt_signed(&c_outputs[1], "zico2", &sk2, 100), Ok(20));
+}
+
+#[test]
+fn test_zk_multi_transfer_overdrawn() {
+    let outputs = [u64_to_scalar(40), u64_to_scalar(30)];
+    let (statement, witness) = multi_transfer_instance(60, &outputs);
+
+    let mut prover = MultiTransferProver::new(rand::thread_rng());
+    assert!(prover.generate_full_proof(statement, witness).is_err());
+}
+
+#[test]
+fn test_zk_multi_transfer_outputs_sum_to_deduction() {
+    let outputs = [u64_to_scalar(10), u64_to_scalar(20)];
+    let (statement, mut witness) = multi_transfer_instance(60, &outputs);
+
+    // Shifting value between the outputs keeps the sum, but the outputs no longer match their ciphertexts.
+    witness.b_stars = vec![u64_to_scalar(20), u64_to_scalar(10)];
+    let mut prover = MultiTransferProver::new(rand::thread_rng());
+    let proof = prover.generate_proof(statement.clone(), witness).unwrap();
+    assert!(MultiTransferVerifier::verify_proof(statement, proof).is_err());
+
+    // Crediting a recipient more than is deducted from the sender is rejected.
+    let (mut statement, witness) = multi_transfer_instance(60, &outputs);
+    let (other, _) = multi_transfer_instance(60, &[u64_to_scalar(10), u64_to_scalar(25)]);
+    statement.c2_bars[1] = other.c2_bars[1];
+    let proof = prover.generate_proof(statement.clone(), witness).unwrap();
+    assert!(MultiTransferVerifier::verify_proof(statement, proof).is_err());
+}
+
+#[test]
+fn test_zk_multi_transfer_mismatched_outputs() {
+    let outputs = [u64_to_scalar(10), u64_to_scalar(20)];
+    let (mut statement, witness) = multi_transfer_instance(60, &outputs);
+
+    let mut prover = MultiTransferProver::new(rand::thread_rng());
+    let proof = prover.generate_full_proof(statement.clone(), witness).unwrap();
+
+    statement.y_bars.pop();
+    assert!(MultiTransferVerifier::verify_full_proof(statement, proof).is_err());
+}
+
+#[test]
+fn test_zk_multi_transfer_bound_to_recipients() {
+    let outputs = [u64_to_scalar(10), u64_to_scalar(20)];
+    let (statement, witness) = multi_transfer_instance(60, &outputs);
+
+    let mut prover = MultiTransferProver::new(rand::thread_rng());
+    let proof = prover.generate_full_proof(statement.clone(), witness.clone()).unwrap();
+
+    // The proof does not verify for the public key of another recipient.
+    let mut other = statement.clone();
+    other.pk_id_bars[1] = pairing(statement.y_bars[1], hash_to_g2("zico3".as_bytes()));
+    assert!(MultiTransferVerifier::verify_full_proof(other.clone(), proof).is_err());
+
+    // Nor can a proof be made for it with the ciphertext of the actual recipient.
+    let proof = prover.generate_full_proof(other.clone(), witness).unwrap();
+    assert!(MultiTransferVerifier::verify_full_proof(other, proof).is_err());
+}
@@ -0,0 +1,67 @@
+
+use aibe::traits::{IdentityBasedEncryption};
+use aibe::bf_ibe::{BFIbe};
+use aibe::utils::hash_to_g2;
+use aibe::zk::register::{RegisterStatement, RegisterWitness, RegisterProver, RegisterVerifier};
+use rand_chacha::ChaCha20Rng;
+
+
+#[test]
+fn test_zk_register() {
+    let rng = rand::thread_rng(); 
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let (msk, mpk) = ibe.generate_key();
+    let sk = ibe.extract("zico", &msk);
+
+    let statement = RegisterStatement {
+        y: mpk,
+        pk_id: BFIbe::<ChaCha20Rng>::pk_id(&mpk, "zico"),
+    };
+    let witness = RegisterWitness {
+        h_id: hash_to_g2("zico".as_bytes()),
+        sk_id: sk,
+    };
+
+    let mut prover = RegisterProver::new(rng.clone());
+    let proof = prover.generate_proof(statement.clone(), witness, b"alice");
+
+    assert!(RegisterVerifier::verify_proof(statement.clone(), proof.clone(), b"alice").is_ok());
+    // The proof is bound to its context.
+    assert!(RegisterVerifier::verify_proof(statement, proof, b"bob").is_err());
+}
+
+#[test]
+fn test_zk_register_other_key() {
+    let rng = rand::thread_rng(); 
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let (msk, mpk) = ibe.generate_key();
+    let (other_msk, _) = ibe.generate_key();
+
+    // A key extracted under another master secret does not belong to the public key.
+    let statement = RegisterStatement {
+        y: mpk,
+        pk_id: BFIbe::<ChaCha20Rng>::pk_id(&mpk, "zico"),
+    };
+    let witness = RegisterWitness {
+        h_id: hash_to_g2("zico".as_bytes()),
+        sk_id: ibe.extract("zico", &other_msk),
+    };
+
+    let mut prover = RegisterProver::new(rng.clone());
+    let proof = prover.generate_proof(statement.clone(), witness, b"alice");
+    assert!(RegisterVerifier::verify_proof(statement, proof, b"alice").is_err());
+
+    // Nor does the key of another identity.
+    let statement = RegisterStatement {
+        y: mpk,
+        pk_id: BFIbe::<ChaCha20Rng>::pk_id(&mpk, "zico"),
+    };
+    let witness = RegisterWitness {
+        h_id: hash_to_g2("other".as_bytes()),
+        sk_id: ibe.extract("other", &msk),
+    };
+    let proof = prover.generate_proof(statement.clone(), witness, b"alice");
+    assert!(RegisterVerifier::verify_proof(statement, proof, b"alice").is_err());
+}
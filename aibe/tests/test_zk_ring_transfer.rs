@@ -0,0 +1,121 @@
+use aibe::traits::{IdentityBasedEncryption};
+use aibe::bf_ibe::{BFIbe, CipherText, G1, G2, Gt, Group, IdSecretKey, pairing};
+use aibe::utils::{u64_to_scalar, pedersen_commitment};
+use aibe::zk::nonce;
+use aibe::zk::ring_transfer::{RingTransferStatement, RingTransferWitness, RingTransferProver, RingTransferVerifier};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+
+/// A ring of `n` accounts with their identity keys and encrypted balances of `b`.
+fn ring(n: usize, b: u64) -> (Vec<Gt>, Vec<IdSecretKey>, Vec<CipherText>) {
+    let mut ibe = BFIbe::new(rand::thread_rng());
+    let mut pk_ids = Vec::new();
+    let mut sks = Vec::new();
+    let mut balances = Vec::new();
+    for i in 0..n {
+        let id = format!("zico{}", i);
+        let (msk, mpk) = ibe.generate_key();
+        pk_ids.push(BFIbe::<rand::rngs::ThreadRng>::pk_id(&mpk, &id));
+        sks.push(ibe.extract(&id, &msk));
+        balances.push(ibe.encrypt(&u64_to_scalar(b), &id, &mpk));
+    }
+    (pk_ids, sks, balances)
+}
+
+/// Build a transfer of `b_star` from member `sender` to member `receiver` of a ring of `n` accounts
+/// holding a balance of `b` each.
+fn ring_transfer_instance(n: usize, b: u64, b_star: u64, sender: usize, receiver: usize) -> (RingTransferStatement, RingTransferWitness, Vec<IdSecretKey>) {
+    let mut rng = rand::thread_rng();
+    let mut ibe = BFIbe::new(rng.clone());
+    let (pk_ids, sks, balances) = ring(n, b);
+
+    let b_star = u64_to_scalar(b_star);
+    let b_prime = u64_to_scalar(b) - b_star;
+    let ((c1, c2s), r) = ibe.encrypt_ring_internal(&b_star, &pk_ids, sender, receiver);
+
+    let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng);
+    let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+    let statement = RingTransferStatement {
+        pk_ids,
+        balances,
+        c1,
+        c2s,
+        c_b_star,
+        c_b_prime,
+        epoch: 0,
+        u: nonce(&sks[sender], 0),
+    };
+    let witness = RingTransferWitness {
+        sender: sender as u32,
+        receiver: receiver as u32,
+        r,
+        r_star,
+        r_prime,
+        b_star,
+        b_prime,
+        sk_id: sks[sender],
+    };
+
+    (statement, witness, sks)
+}
+
+#[test]
+fn test_zk_ring_transfer() {
+    for (n, sender, receiver) in [(2, 0, 1), (2, 1, 0), (4, 2, 1)] {
+        let (statement, witness, _) = ring_transfer_instance(n, 60, 40, sender, receiver);
+
+        let mut prover = RingTransferProver::new(rand::thread_rng());
+        let proof = prover.generate_full_proof(statement.clone(), witness).unwrap();
+
+        let statement = RingTransferStatement::try_from_slice(&statement.try_to_vec().unwrap()).unwrap();
+        assert!(RingTransferVerifier::verify_full_proof(statement, proof).is_ok());
+    }
+}
+
+#[test]
+fn test_zk_ring_transfer_decrypt() {
+    let (statement, _, sks) = ring_transfer_instance(3, 60, 40, 0, 2);
+
+    let mut ibe = BFIbe::new(rand::thread_rng());
+    let credited = (0..3)
+        .map(|i| ibe.decrypt_signed(&(statement.c1, statement.c2s[i]), "", &sks[i], 100).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(credited, vec![-40, 0, 40]);
+}
+
+#[test]
+fn test_zk_ring_transfer_overdrawn() {
+    let (statement, witness, _) = ring_transfer_instance(3, 30, 40, 0, 1);
+
+    let mut prover = RingTransferProver::new(rand::thread_rng());
+    assert!(prover.generate_full_proof(statement, witness).is_err());
+}
+
+#[test]
+fn test_zk_ring_transfer_conservation() {
+    let (mut statement, witness, _) = ring_transfer_instance(3, 60, 40, 0, 1);
+
+    // Crediting the receiver more than is deducted from the sender is rejected.
+    statement.c2s[1] = statement.c2s[1] * pairing(G1::one(), G2::one());
+    let mut prover = RingTransferProver::new(rand::thread_rng());
+    let proof = prover.generate_proof(statement.clone(), witness).unwrap();
+    assert!(RingTransferVerifier::verify_proof(statement, proof).is_err());
+
+    // So is crediting a third member of the ring.
+    let (mut statement, witness, _) = ring_transfer_instance(3, 60, 40, 0, 1);
+    statement.c2s[2] = statement.c2s[2] * pairing(G1::one(), G2::one());
+    let proof = prover.generate_proof(statement.clone(), witness).unwrap();
+    assert!(RingTransferVerifier::verify_proof(statement, proof).is_err());
+}
+
+#[test]
+fn test_zk_ring_transfer_requires_key_of_sender() {
+    let (statement, mut witness, sks) = ring_transfer_instance(3, 60, 40, 0, 1);
+
+    // The key of another member of the ring does not open the sender's balance.
+    witness.sk_id = sks[2];
+    let mut prover = RingTransferProver::new(rand::thread_rng());
+    let proof = prover.generate_proof(statement.clone(), witness).unwrap();
+    assert!(RingTransferVerifier::verify_proof(statement, proof).is_err());
+}
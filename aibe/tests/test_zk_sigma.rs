@@ -0,0 +1,62 @@
+use aibe::bf_ibe::{Fr as Scalar, G1, G2, Group, pairing};
+use aibe::zk::sigma::{GtTerm, Relation, SigmaProof};
+use borsh::{BorshDeserialize, BorshSerialize};
+use merlin::Transcript;
+
+
+/// `c1 = g^r` and `c2 = gt^b * pk^r` for a public `pk` in Gt, i.e. an encryption of `b` under `pk`.
+fn encryption_relation(c1: G1, c2: bn::Gt, pk: bn::Gt) -> Relation {
+    let mut relation = Relation::new();
+    let r = relation.scalar();
+    let b = relation.scalar();
+    relation.g1_equation(&[(G1::one(), r)], c1);
+    relation.gt_equation(&[GtTerm::Generator(b), GtTerm::Pow(pk, r)], c2);
+    relation
+}
+
+#[test]
+fn test_zk_sigma() {
+    let mut rng = rand::thread_rng();
+    let pk = pairing(G1::random(&mut rng), G2::random(&mut rng));
+    let (r, b) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+    let gt = pairing(G1::one(), G2::one());
+    let relation = encryption_relation(G1::one() * r, gt.pow(b) * pk.pow(r), pk);
+
+    let proof = relation.prove(&mut Transcript::new(b"test"), &[r, b], &[], &mut rng).unwrap();
+    let proof = SigmaProof::try_from_slice(&proof.try_to_vec().unwrap()).unwrap();
+    assert!(relation.verify(&mut Transcript::new(b"test"), &proof).is_ok());
+
+    // The proof is bound to the transcript it was made with.
+    assert!(relation.verify(&mut Transcript::new(b"other"), &proof).is_err());
+}
+
+#[test]
+fn test_zk_sigma_false_statement() {
+    let mut rng = rand::thread_rng();
+    let pk = pairing(G1::random(&mut rng), G2::random(&mut rng));
+    let (r, b) = (Scalar::random(&mut rng), Scalar::random(&mut rng));
+    let gt = pairing(G1::one(), G2::one());
+
+    // c2 encrypts b + 1 rather than b
+    let relation = encryption_relation(G1::one() * r, gt.pow(b + Scalar::one()) * pk.pow(r), pk);
+    let proof = relation.prove(&mut Transcript::new(b"test"), &[r, b], &[], &mut rng).unwrap();
+    assert!(relation.verify(&mut Transcript::new(b"test"), &proof).is_err());
+}
+
+#[test]
+fn test_zk_sigma_shape() {
+    let mut rng = rand::thread_rng();
+    let sk = G2::random(&mut rng);
+
+    let mut relation = Relation::new();
+    let x_sk = relation.g2();
+    relation.gt_equation(&[GtTerm::Pairing(G1::one(), x_sk)], pairing(G1::one(), sk));
+
+    // A witness must assign every secret, and a proof must answer for every secret.
+    assert!(relation.prove(&mut Transcript::new(b"test"), &[Scalar::one()], &[sk], &mut rng).is_err());
+
+    let mut proof = relation.prove(&mut Transcript::new(b"test"), &[], &[sk], &mut rng).unwrap();
+    assert!(relation.verify(&mut Transcript::new(b"test"), &proof).is_ok());
+    proof.g2_responses.push(sk);
+    assert!(relation.verify(&mut Transcript::new(b"test"), &proof).is_err());
+}
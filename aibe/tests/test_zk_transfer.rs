@@ -1,10 +1,12 @@
 
 use aibe::traits::{IdentityBasedEncryption};
-use aibe::bf_ibe::{BFIbe};
+use aibe::bf_ibe::{BFIbe, pairing};
 use aibe::utils::{u64_to_scalar, hash_to_g2, pedersen_commitment};
 use aibe::zk::transfer::{TransferStatement, TransferWitness, TransferProver, TransferVerifier};
+use aibe::zk::nonce;
 use rand::Rng;
-use bn::{G1, Group};
+use aibe::bf_ibe::{Fr as Scalar, G1};
+use aibe::zk::transfer::FullTransferProof;
 
 
 #[test]
@@ -41,18 +43,16 @@ fn test_zk_transfer() {
     let elapsed = now.elapsed();
     println!("[IBE encrypt]: {:.2?}", elapsed);
 
-    let h1 = G1::random(&mut rng);
-
     let now = Instant::now();
-    let (r_star, c_b_star) = pedersen_commitment(b_star, h1, &mut rng); 
-    let (r_prime, c_b_prime) = pedersen_commitment(b_prime, h1, &mut rng); 
+    let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng); 
+    let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng); 
     let elapsed = now.elapsed();
     println!("[Pedersen commitment]: {:.2?}", elapsed);
 
     let statement = TransferStatement {
-        h1,
         y: mpk1,
         y_bar: mpk2,
+        pk_id_bar: pairing(mpk2, h_id_bar),
         c1: c_transfer.0,
         c2: c_transfer.1,
         c2_bar: c_transfer_bar.1,
@@ -60,6 +60,8 @@ fn test_zk_transfer() {
         c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
         c_b_star,
         c_b_prime,
+        epoch: 0,
+        u: nonce(&sk1, 0),
     };
     let witness = TransferWitness {
         r,
@@ -80,4 +82,144 @@ fn test_zk_transfer() {
     assert!(result.is_ok());
 }
 
+/// Build a transfer of `b_star` out of a balance of `b`, committing to the transfer amount and
+/// to the remaining balance `b - b_star`.
+fn transfer_instance(b: u64, b_star: Scalar) -> (TransferStatement, TransferWitness) {
+    let mut rng = rand::thread_rng(); 
+    let mut ibe = BFIbe::new(rng.clone());
+
+    let b = u64_to_scalar(b);
+    let b_prime = b - b_star;
+
+    let (msk1, mpk1) = ibe.generate_key();
+    let (_, mpk2) = ibe.generate_key();
+    let sk1 = ibe.extract("zico1", &msk1);
+
+    let c_balance = ibe.encrypt(&b, "zico1", &mpk1);
+    let ((c_transfer, c_transfer_bar), (h_id, h_id_bar), r) = ibe.encrypt_correlated_internal(&b_star, ("zico1", "zico2"), (&mpk1, &mpk2));
+
+    let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng); 
+    let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng); 
+
+    let statement = TransferStatement {
+        y: mpk1,
+        y_bar: mpk2,
+        pk_id_bar: pairing(mpk2, h_id_bar),
+        c1: c_transfer.0,
+        c2: c_transfer.1,
+        c2_bar: c_transfer_bar.1,
+        c1_tilde: c_balance.0 - c_transfer.0,
+        c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
+        c_b_star,
+        c_b_prime,
+        epoch: 0,
+        u: nonce(&sk1, 0),
+    };
+    let witness = TransferWitness {
+        r,
+        s: msk1,
+        r_star,
+        r_prime,
+        b_star,
+        b_prime,
+        h_id,
+        h_id_bar,
+        sk_id: sk1,
+    };
 
+    (statement, witness)
+}
+
+#[test]
+fn test_zk_full_transfer() {
+    let (statement, witness) = transfer_instance(60, u64_to_scalar(40));
+
+    let mut prover = TransferProver::new(rand::thread_rng());
+    let proof = prover.generate_full_proof(statement.clone(), witness).unwrap();
+
+    assert!(TransferVerifier::verify_full_proof(statement, proof).is_ok());
+}
+
+#[test]
+fn test_zk_transfer_bound_to_receiver() {
+    let (statement, witness) = transfer_instance(60, u64_to_scalar(40));
+
+    let mut prover = TransferProver::new(rand::thread_rng());
+    let proof = prover.generate_full_proof(statement.clone(), witness.clone()).unwrap();
+
+    // The proof does not verify for the public key of another receiver.
+    let mut other = statement.clone();
+    other.pk_id_bar = pairing(statement.y_bar, hash_to_g2("zico3".as_bytes()));
+    assert!(TransferVerifier::verify_full_proof(other.clone(), proof).is_err());
+
+    // Nor can a proof be made for it with the ciphertext of the actual receiver.
+    let proof = prover.generate_full_proof(other.clone(), witness).unwrap();
+    assert!(TransferVerifier::verify_full_proof(other, proof).is_err());
+}
+
+#[test]
+fn test_zk_negative_transfer() {
+    // Transferring -10 would credit the sender, so no range proof can be generated for it.
+    let (statement, witness) = transfer_instance(60, -u64_to_scalar(10));
+
+    let mut prover = TransferProver::new(rand::thread_rng());
+    assert!(prover.generate_full_proof(statement.clone(), witness.clone()).is_err());
+
+    // The sigma proof alone holds, but it cannot be combined with a range proof on other values.
+    let proof = prover.generate_proof(statement.clone(), witness);
+    assert!(TransferVerifier::verify_proof(statement.clone(), proof.clone()).is_ok());
+
+    let (other_statement, other_witness) = transfer_instance(60, u64_to_scalar(10));
+    let other_proof = prover.generate_full_proof(other_statement, other_witness).unwrap();
+    let forged = FullTransferProof {
+        proof,
+        range_proof: other_proof.range_proof,
+    };
+    assert!(TransferVerifier::verify_full_proof(statement, forged).is_err());
+}
+
+#[test]
+fn test_zk_overdrawn_transfer() {
+    // Transferring 70 out of a balance of 60 leaves a negative remaining balance.
+    let (statement, witness) = transfer_instance(60, u64_to_scalar(70));
+
+    let mut prover = TransferProver::new(rand::thread_rng());
+    assert!(prover.generate_full_proof(statement, witness).is_err());
+}
+
+#[test]
+fn test_zk_transfer_bound_to_statement() {
+    let (statement, witness) = transfer_instance(60, u64_to_scalar(40));
+
+    let mut prover = TransferProver::new(rand::thread_rng());
+    let proof = prover.generate_full_proof(statement.clone(), witness).unwrap();
+
+    // The proof does not verify for a statement with another receiver.
+    let (other_statement, _) = transfer_instance(60, u64_to_scalar(40));
+    let mut other = statement;
+    other.y_bar = other_statement.y_bar;
+    assert!(TransferVerifier::verify_full_proof(other.clone(), proof.clone()).is_err());
+    assert!(TransferVerifier::verify_proof(other, proof.proof).is_err());
+}
+
+#[test]
+fn test_zk_transfer_batch() {
+    let mut prover = TransferProver::new(rand::thread_rng());
+    let mut batch = Vec::new();
+    for b_star in [10, 20, 40] {
+        let (statement, witness) = transfer_instance(60, u64_to_scalar(b_star));
+        let proof = prover.generate_full_proof(statement.clone(), witness).unwrap();
+        batch.push((statement, proof));
+    }
+    assert!(TransferVerifier::verify_batch(&batch).is_ok());
+
+    // A single false equation makes the whole batch fail.
+    let mut forged = batch.clone();
+    forged[1].1.proof.scalar_responses[4] = forged[1].1.proof.scalar_responses[4] + u64_to_scalar(1);
+    assert!(TransferVerifier::verify_batch(&forged).is_err());
+
+    // So does a proof paired with the statement of another proof.
+    let mut swapped = batch;
+    swapped[0].0 = swapped[2].0.clone();
+    assert!(TransferVerifier::verify_batch(&swapped).is_err());
+}
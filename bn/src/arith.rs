@@ -98,6 +98,21 @@ impl U512 {
         Ok(U512(n))
     }
 
+    pub fn to_big_endian(&self, s: &mut [u8]) -> Result<(), Error> {
+        if s.len() != 64 {
+            return Err(Error::InvalidLength {
+                expected: 64,
+                actual: s.len(),
+            });
+        }
+
+        for (l, i) in (0..4).rev().zip((0..4).map(|i| i * 16)) {
+            BigEndian::write_u128(&mut s[i..], self.0[l]);
+        }
+
+        Ok(())
+    }
+
     /// Get a random U512
     pub fn random<R: Rng>(rng: &mut R) -> U512 {
         U512(rng.gen())
@@ -0,0 +1,98 @@
+//! Pluggable implementations of the expensive operations of the curve.
+//!
+//! `G1::multiexp`, `G2::multiexp`, `Gt::pow`, `pairing` and `pairing_batch` run on the backend
+//! set with [`set_backend`], and on the [`native`] implementations of this crate if none is set.
+//! A Wasm runtime can set a backend that forwards these operations to host functions, so that
+//! everything built on top of this crate runs them as native code without any change.
+
+use crate::{Fr, Gt, G1, G2};
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+/// Implementations of the operations that can be replaced.
+pub struct Backend {
+    pub g1_multiexp: fn(&[(G1, Fr)]) -> G1,
+    pub g2_multiexp: fn(&[(G2, Fr)]) -> G2,
+    pub gt_pow: fn(Gt, Fr) -> Gt,
+    /// The product of the pairings of all pairs, with a single final exponentiation.
+    pub pairing_batch: fn(&[(G1, G2)]) -> Gt,
+}
+
+/// The implementations of this crate.
+pub static NATIVE: Backend = Backend {
+    g1_multiexp: native::g1_multiexp,
+    g2_multiexp: native::g2_multiexp,
+    gt_pow: native::gt_pow,
+    pairing_batch: native::pairing_batch,
+};
+
+static BACKEND: AtomicPtr<Backend> = AtomicPtr::new(ptr::null_mut());
+
+/// Run the operations on `backend` from now on.
+pub fn set_backend(backend: &'static Backend) {
+    BACKEND.store(backend as *const Backend as *mut Backend, Ordering::Release);
+}
+
+/// Run the operations on the native implementations of this crate again.
+pub fn reset_backend() {
+    BACKEND.store(ptr::null_mut(), Ordering::Release);
+}
+
+/// The backend the operations currently run on.
+pub fn backend() -> &'static Backend {
+    let backend = BACKEND.load(Ordering::Acquire);
+    if backend.is_null() {
+        &NATIVE
+    } else {
+        // only ever set from a `&'static Backend`
+        unsafe { &*backend }
+    }
+}
+
+/// The implementations of this crate, which never go through the backend. A backend that
+/// forwards to host functions calls these on the host.
+pub mod native {
+    use crate::fields::FieldElement;
+    use crate::{groups, Fr, Gt, G1, G2};
+    use alloc::vec::Vec;
+
+    pub fn g1_multiexp(items: &[(G1, Fr)]) -> G1 {
+        let items = items
+            .iter()
+            .filter_map(|e| match e.0 .0.to_affine() {
+                None => None,
+                Some(p) => Some((p, e.1.into_u256())),
+            })
+            .collect::<Vec<_>>();
+        G1(groups::pippenger(&items[..]))
+    }
+
+    pub fn g2_multiexp(items: &[(G2, Fr)]) -> G2 {
+        let items = items
+            .iter()
+            .filter_map(|e| match e.0 .0.to_affine() {
+                None => None,
+                Some(p) => Some((p, e.1.into_u256())),
+            })
+            .collect::<Vec<_>>();
+        G2(groups::pippenger(&items[..]))
+    }
+
+    pub fn gt_pow(a: Gt, exp: Fr) -> Gt {
+        Gt(a.0.pow(exp.0))
+    }
+
+    pub fn pairing_batch(pairs: &[(G1, G2)]) -> Gt {
+        if let [(p, q)] = pairs {
+            return Gt(groups::pairing(&p.0, &q.0));
+        }
+
+        let mut ps: Vec<groups::G1> = Vec::new();
+        let mut qs: Vec<groups::G2> = Vec::new();
+        for (p, q) in pairs {
+            ps.push(p.0);
+            qs.push(q.0);
+        }
+        Gt(groups::pairing_batch(&ps, &qs))
+    }
+}
@@ -132,6 +132,34 @@ impl Fq12 {
         Fq12::new(self.c0, -self.c1)
     }
 
+    /// Compress a unitary element `c0 + c1 w` (e.g. of the cyclotomic subgroup) to
+    /// `(1 + c0) / c1` in the torus T2(Fq6). One compresses to zero; the only other element
+    /// with `c1 = 0` is -1, which has order 2 and is never a pairing result.
+    pub fn torus_compress(&self) -> Fq6 {
+        match self.c1.inverse() {
+            Some(c1_inv) => (Fq6::one() + self.c0) * c1_inv,
+            None => Fq6::zero(),
+        }
+    }
+
+    /// Inverse of `torus_compress`: `(c + w) / (c - w) = (c^2 + v + 2c w) / (c^2 - v)`.
+    pub fn torus_decompress(c: Fq6) -> Fq12 {
+        if c.is_zero() {
+            return Fq12::one();
+        }
+
+        let v = Fq6::one().mul_by_nonresidue();
+        let c_squared = c.squared();
+        let denominator = (c_squared - v)
+            .inverse()
+            .expect("v is not a square in Fq6, so c^2 - v is not zero; qed");
+
+        Fq12 {
+            c0: (c_squared + v) * denominator,
+            c1: (c + c) * denominator,
+        }
+    }
+
     pub fn mul_by_024(&self, ell_0: Fq2, ell_vw: Fq2, ell_vv: Fq2) -> Fq12 {
         let z0 = self.c0.c0;
         let z1 = self.c0.c1;
@@ -0,0 +1,335 @@
+//! Hashing to G1 and G2 as specified in RFC 9380 ("Hashing to Elliptic Curves").
+//!
+//! Both groups use `expand_message_xmd` with SHA-256 and the Shallue–van de Woestijne map,
+//! i.e. the `BN254G1_XMD:SHA-256_SVDW_RO_` and `BN254G2_XMD:SHA-256_SVDW_RO_` suites. Points
+//! on the twist are moved into the order-r subgroup of G2 with the cofactor clearing of
+//! Fuentes-Castañeda, Knapp and Rodríguez-Henríquez.
+
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+use crate::arith::U256;
+use crate::fields::{FieldElement, Fq, Fq2, Fr};
+use super::{G, G1, G1Params, G2, G2Params, GroupElement, GroupParams};
+
+/// Bytes of uniform output reduced to one base field element, `ceil((ceil(log2(p)) + k) / 8)`
+/// with security parameter `k = 128`.
+const L: usize = 48;
+
+/// The BN parameter `x` of BN254.
+const BN_X: u64 = 4965661367192848881;
+
+lazy_static! {
+    static ref G1_SVDW: Svdw<Fq> = Svdw::new(Fq::one(), G1Params::coeff_b());
+    static ref G2_SVDW: Svdw<Fq2> = Svdw::new(Fq2::one(), G2Params::coeff_b());
+}
+
+/// `expand_message_xmd` with SHA-256, RFC 9380 section 5.3.1.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let oversize_dst;
+    let dst = if dst.len() > 255 {
+        oversize_dst = Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize();
+        &oversize_dst[..]
+    } else {
+        dst
+    };
+
+    let ell = len_in_bytes.div_ceil(32);
+    assert!(ell <= 255, "expand_message_xmd: requested output is too long");
+
+    let with_dst = |h: Sha256| h.chain_update(dst).chain_update([dst.len() as u8]);
+
+    let b_0 = with_dst(
+        Sha256::new()
+            .chain_update([0u8; 64])
+            .chain_update(msg)
+            .chain_update((len_in_bytes as u16).to_be_bytes())
+            .chain_update([0u8]),
+    )
+    .finalize();
+    let mut b_i = with_dst(Sha256::new().chain_update(b_0).chain_update([1u8])).finalize();
+
+    let mut uniform_bytes = Vec::with_capacity(ell * 32);
+    uniform_bytes.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let mut xored = [0u8; 32];
+        for (x, (a, b)) in xored.iter_mut().zip(b_0.iter().zip(b_i.iter())) {
+            *x = a ^ b;
+        }
+        b_i = with_dst(Sha256::new().chain_update(xored).chain_update([i as u8])).finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+
+    uniform_bytes
+}
+
+/// The operations the SvdW map needs beyond `FieldElement`.
+trait SvdwField: FieldElement {
+    /// Extension degree over Fq.
+    const DEGREE: usize;
+
+    /// The "sign" of an element, RFC 9380 section 4.1.
+    fn sgn0(&self) -> bool;
+
+    fn sqrt(&self) -> Option<Self>;
+
+    /// Reduce `DEGREE * L` bytes of uniform output to a field element.
+    fn from_okm(okm: &[u8]) -> Self;
+
+    fn is_square(&self) -> bool {
+        SvdwField::sqrt(self).is_some()
+    }
+}
+
+fn fq_from_okm(okm: &[u8]) -> Fq {
+    let mut buf = [0u8; 64];
+    buf[64 - L..].copy_from_slice(okm);
+    Fq::interpret(&buf)
+}
+
+impl SvdwField for Fq {
+    const DEGREE: usize = 1;
+
+    fn sgn0(&self) -> bool {
+        U256::from(*self).get_bit(0).expect("bit 0 always exist; qed")
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        Fq::sqrt(self)
+    }
+
+    fn from_okm(okm: &[u8]) -> Self {
+        fq_from_okm(okm)
+    }
+}
+
+impl SvdwField for Fq2 {
+    const DEGREE: usize = 2;
+
+    fn sgn0(&self) -> bool {
+        let real = self.real();
+        real.sgn0() || (real.is_zero() && self.imaginary().sgn0())
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        Fq2::sqrt(self)
+    }
+
+    fn from_okm(okm: &[u8]) -> Self {
+        Fq2::new(fq_from_okm(&okm[..L]), fq_from_okm(&okm[L..]))
+    }
+}
+
+/// Constants of the Shallue–van de Woestijne map to `y^2 = x^3 + b`, RFC 9380 section 6.6.1.
+struct Svdw<F> {
+    z: F,
+    b: F,
+    c1: F,
+    c2: F,
+    c3: F,
+    c4: F,
+}
+
+impl<F: SvdwField> Svdw<F> {
+    fn new(z: F, b: F) -> Self {
+        let two = F::one() + F::one();
+        let three = two + F::one();
+        let three_z2 = three * z.squared();
+
+        let c1 = z.squared() * z + b;
+        let c2 = -z * two.inverse().expect("2 has inverse in Fq and is static; qed");
+        let mut c3 = SvdwField::sqrt(&(-c1 * three_z2))
+            .expect("Z is chosen so that -g(Z) * 3Z^2 is a square; qed");
+        if c3.sgn0() {
+            c3 = -c3;
+        }
+        let c4 = -(two * two * c1) * three_z2.inverse().expect("3Z^2 is not zero; qed");
+
+        Svdw { z, b, c1, c2, c3, c4 }
+    }
+
+    fn g(&self, x: F) -> F {
+        x.squared() * x + self.b
+    }
+
+    fn map_to_curve(&self, u: F) -> (F, F) {
+        let tv1 = u.squared() * self.c1;
+        let tv2 = F::one() + tv1;
+        let tv1 = F::one() - tv1;
+        let tv3 = (tv1 * tv2).inverse().unwrap_or_else(F::zero);
+        let tv4 = u * tv1 * tv3 * self.c3;
+
+        let x1 = self.c2 - tv4;
+        let x2 = self.c2 + tv4;
+        let x3 = (tv2.squared() * tv3).squared() * self.c4 + self.z;
+
+        let x = if self.g(x1).is_square() {
+            x1
+        } else if self.g(x2).is_square() {
+            x2
+        } else {
+            x3
+        };
+
+        let mut y = SvdwField::sqrt(&self.g(x)).expect("one of g(x1), g(x2), g(x3) is a square; qed");
+        if u.sgn0() != y.sgn0() {
+            y = -y;
+        }
+
+        (x, y)
+    }
+}
+
+fn hash_to_field<F: SvdwField>(msg: &[u8], dst: &[u8]) -> (F, F) {
+    let okm = expand_message_xmd(msg, dst, 2 * F::DEGREE * L);
+    let (u0, u1) = okm.split_at(F::DEGREE * L);
+
+    (F::from_okm(u0), F::from_okm(u1))
+}
+
+fn map_to_curve<P: GroupParams>(svdw: &Svdw<P::Base>, u: P::Base) -> G<P>
+where
+    P::Base: SvdwField,
+{
+    let (x, y) = svdw.map_to_curve(u);
+    G::new(x, y, P::Base::one())
+}
+
+/// The endomorphism `psi = twist^-1 * frobenius * twist` of the twist curve.
+fn psi(p: G2) -> G2 {
+    match p.to_affine() {
+        Some(p) => p.mul_by_q().to_jacobian(),
+        None => G2::zero(),
+    }
+}
+
+/// Map a point of the twist into G2 by computing `[x]P + psi([3x]P) + psi^2([x]P) + psi^3(P)`.
+fn clear_cofactor_g2(p: G2) -> G2 {
+    let x = Fr::new(U256::from(BN_X)).expect("x is smaller than the group order; qed");
+    let xp = p * x;
+
+    xp + psi(xp + xp + xp) + psi(psi(xp)) + psi(psi(psi(p)))
+}
+
+/// Hash `msg` to G1 with the `BN254G1_XMD:SHA-256_SVDW_RO_` suite and the tag `dst`.
+pub fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1 {
+    let (u0, u1) = hash_to_field::<Fq>(msg, dst);
+
+    // G1 has cofactor 1.
+    map_to_curve(&G1_SVDW, u0) + map_to_curve(&G1_SVDW, u1)
+}
+
+/// Hash `msg` to G2 with the `BN254G2_XMD:SHA-256_SVDW_RO_` suite and the tag `dst`.
+pub fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2 {
+    let (u0, u1) = hash_to_field::<Fq2>(msg, dst);
+
+    clear_cofactor_g2(map_to_curve(&G2_SVDW, u0) + map_to_curve(&G2_SVDW, u1))
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate rustc_hex as hex;
+
+    use super::*;
+    use crate::groups::AffineG;
+    use self::hex::FromHex;
+
+    fn fq(s: &str) -> Fq {
+        Fq::new(U256::from_slice(&s.from_hex::<Vec<u8>>().unwrap()).unwrap()).unwrap()
+    }
+
+    fn affine<P: GroupParams>(p: G<P>) -> (P::Base, P::Base) {
+        let p: AffineG<P> = p.to_affine().unwrap();
+        (*p.x(), *p.y())
+    }
+
+    // RFC 9380, appendix K.1.
+    #[test]
+    fn expand_message_xmd_vectors() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let vectors: [(&[u8], usize, &str); 4] = [
+            (b"", 0x20, "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"),
+            (b"abc", 0x20, "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"),
+            (b"abcdef0123456789", 0x20, "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1"),
+            (b"", 0x80, "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbee0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dcc541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced"),
+        ];
+
+        for (msg, len, expected) in vectors.iter() {
+            assert_eq!(expand_message_xmd(msg, dst, *len), expected.from_hex::<Vec<u8>>().unwrap());
+        }
+    }
+
+    // BN254G1_XMD:SHA-256_SVDW_RO_ with the QUUX test tag, as used by gnark-crypto.
+    #[test]
+    fn hash_to_g1_vectors() {
+        let dst = b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+        let vectors: [(&[u8], &str, &str); 3] = [
+            (
+                b"",
+                "0a976ab906170db1f9638d376514dbf8c42aef256a54bbd48521f20749e59e86",
+                "02925ead66b9e68bfc309b014398640ab55f6619ab59bc1fab2210ad4c4d53d5",
+            ),
+            (
+                b"abc",
+                "23f717bee89b1003957139f193e6be7da1df5f1374b26a4643b0378b5baf53d1",
+                "04142f826b71ee574452dbc47e05bc3e1a647478403a7ba38b7b93948f4e151d",
+            ),
+            (
+                b"abcdef0123456789",
+                "187dbf1c3c89aceceef254d6548d7163fdfa43084145f92c4c91c85c21442d4a",
+                "0abd99d5b0000910b56058f9cc3b0ab0a22d47cf27615f588924fac1e5c63b4d",
+            ),
+        ];
+
+        for (msg, x, y) in vectors.iter() {
+            assert_eq!(affine(hash_to_g1(msg, dst)), (fq(x), fq(y)));
+        }
+    }
+
+    // BN254G2_XMD:SHA-256_SVDW_RO_ with the QUUX test tag, as used by gnark-crypto.
+    #[test]
+    fn hash_to_g2_vectors() {
+        let dst = b"QUUX-V01-CS02-with-BN254G2_XMD:SHA-256_SVDW_RO_";
+        let vectors: [(&[u8], [&str; 4]); 3] = [
+            (
+                b"",
+                [
+                    "1192005a0f121921a6d5629946199e4b27ff8ee4d6dd4f9581dc550ade851300",
+                    "1747d950a6f23c16156e2171bce95d1189b04148ad12628869ed21c96a8c9335",
+                    "0498f6bb5ac309a07d9a8b88e6ff4b8de0d5f27a075830e1eb0e68ea318201d8",
+                    "2c9755350ca363ef2cf541005437221c5740086c2e909b71d075152484e845f4",
+                ],
+            ),
+            (
+                b"abc",
+                [
+                    "16c88b54eec9af86a41569608cd0f60aab43464e52ce7e6e298bf584b94fccd2",
+                    "0b5db3ca7e8ef5edf3a33dfc3242357fbccead98099c3eb564b3d9d13cba4efd",
+                    "1c42ba524cb74db8e2c680449746c028f7bea923f245e69f89256af2d6c5f3ac",
+                    "22d02d2da7f288545ff8789e789902245ab08c6b1d253561eec789ec2c1bd630",
+                ],
+            ),
+            (
+                b"abcdef0123456789",
+                [
+                    "1435fd84aa43c699230e371f6fea3545ce7e053cbbb06a320296a2b81efddc70",
+                    "2a8a360585b6b05996ef69c3c09b2c6fb17afe2b1e944f07559c53178eabf171",
+                    "2820188dcdc13ffdca31694942418afa1d6dfaaf259d012fab4da52b0f592e38",
+                    "142f08e2441ec431defc24621b73cfe0252d19b243cb55b84bdeb85de039207a",
+                ],
+            ),
+        ];
+
+        for (msg, [x0, x1, y0, y1]) in vectors.iter() {
+            let p = hash_to_g2(msg, dst);
+            assert_eq!(
+                affine(p),
+                (Fq2::new(fq(x0), fq(x1)), Fq2::new(fq(y0), fq(y1)))
+            );
+            assert!(G2Params::subgroup_check(p));
+        }
+    }
+}
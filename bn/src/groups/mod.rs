@@ -13,6 +13,10 @@ use borsh::{BorshDeserialize, BorshSerialize};
 #[cfg(feature = "borsh")]
 use borsh::maybestd::io::{ErrorKind, Write};
 
+mod hash_to_curve;
+
+pub use self::hash_to_curve::{expand_message_xmd, hash_to_g1, hash_to_g2};
+
 // This is the NAF version of ate_loop_count. Entries are all mod 4, so 3 = -1
 // n.b. ate_loop_count = 0x19d797039be763ba8
 //                     = 11001110101111001011100000011100110111110011101100011101110101000
@@ -16,6 +16,7 @@ extern crate alloc;
 extern crate std as alloc;
 
 pub mod arith;
+pub mod backend;
 mod fields;
 mod groups;
 
@@ -376,11 +377,39 @@ impl G1 {
         Fq(G1Params::coeff_b())
     }
 
+    /// Hash `msg` to a point of G1 with the `BN254G1_XMD:SHA-256_SVDW_RO_` suite of RFC 9380,
+    /// domain separated by `dst`. Unlike `G1::one() * hash`, nobody learns the discrete log of
+    /// the result.
+    pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Self {
+        G1(groups::hash_to_g1(msg, dst))
+    }
+
+    /// Compressed encoding, the inverse of `from_compressed`: a prefix of 2 (even y) or 3 (odd y)
+    /// followed by the big-endian x coordinate. The point at infinity is 33 zero bytes.
+    pub fn to_compressed(&self) -> [u8; 33] {
+        let mut bytes = [0u8; 33];
+        if let Some(p) = AffineG1::from_jacobian(*self) {
+            bytes[0] = if p.y().into_u256().get_bit(0).expect("bit 0 always exist; qed") {
+                3
+            } else {
+                2
+            };
+            p.x()
+                .to_big_endian(&mut bytes[1..])
+                .expect("slice is 32 bytes; qed");
+        }
+        bytes
+    }
+
     pub fn from_compressed(bytes: &[u8]) -> Result<Self, CurveError> {
         if bytes.len() != 33 {
             return Err(CurveError::InvalidEncoding);
         }
 
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(G1::zero());
+        }
+
         let sign = bytes[0];
         let fq = Fq::from_slice(&bytes[1..])?;
         let x = fq;
@@ -403,14 +432,7 @@ impl G1 {
 
 impl Group for G1 {
     fn multiexp(items: &[(Self, Fr)]) -> Self {
-        let items = items
-            .iter()
-            .filter_map(|e| match e.0 .0.to_affine() {
-                None => None,
-                Some(p) => Some((p, e.1.into_u256())),
-            })
-            .collect::<Vec<_>>();
-        Self(crate::groups::pippenger(&items[..]))
+        (backend::backend().g1_multiexp)(items)
     }
 
     fn zero() -> Self {
@@ -541,11 +563,38 @@ impl G2 {
         Fq2(G2Params::coeff_b())
     }
 
+    /// Hash `msg` to a point of G2 with the `BN254G2_XMD:SHA-256_SVDW_RO_` suite of RFC 9380,
+    /// domain separated by `dst`.
+    pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Self {
+        G2(groups::hash_to_g2(msg, dst))
+    }
+
+    /// Compressed encoding, the inverse of `from_compressed`: a prefix of 10 (the smaller of y
+    /// and -y) or 11 (the larger) followed by the x coordinate as the big-endian integer
+    /// `c1 * q + c0`. The point at infinity is 65 zero bytes.
+    pub fn to_compressed(&self) -> [u8; 65] {
+        let mut bytes = [0u8; 65];
+        if let Some(p) = AffineG2::from_jacobian(*self) {
+            let y = p.y();
+            bytes[0] = if y.0.to_u512() > (-y).0.to_u512() { 11 } else { 10 };
+            p.x()
+                .0
+                .to_u512()
+                .to_big_endian(&mut bytes[1..])
+                .expect("slice is 64 bytes; qed");
+        }
+        bytes
+    }
+
     pub fn from_compressed(bytes: &[u8]) -> Result<Self, CurveError> {
         if bytes.len() != 65 {
             return Err(CurveError::InvalidEncoding);
         }
 
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(G2::zero());
+        }
+
         let sign = bytes[0];
         let x = Fq2::from_slice(&bytes[1..])?;
 
@@ -579,14 +628,7 @@ impl G2 {
 
 impl Group for G2 {
     fn multiexp(items: &[(Self, Fr)]) -> Self {
-        let items = items
-            .iter()
-            .filter_map(|e| match e.0 .0.to_affine() {
-                None => None,
-                Some(p) => Some((p, e.1.into_u256())),
-            })
-            .collect::<Vec<_>>();
-        Self(crate::groups::pippenger(&items[..]))
+        (backend::backend().g2_multiexp)(items)
     }
 
     fn zero() -> Self {
@@ -653,7 +695,7 @@ impl Gt {
         Gt(fields::Fq12::one())
     }
     pub fn pow(&self, exp: Fr) -> Self {
-        Gt(self.0.pow(exp.0))
+        (backend::backend().gt_pow)(*self, exp)
     }
     pub fn inverse(&self) -> Option<Self> {
         self.0.inverse().map(Gt)
@@ -661,6 +703,49 @@ impl Gt {
     pub fn final_exponentiation(&self) -> Option<Self> {
         self.0.final_exponentiation().map(Gt)
     }
+
+    /// Torus-based compressed encoding of 192 bytes, half the size of the full Fq12.
+    ///
+    /// The element is mapped to `(1 + c0) / c1` in Fq6, which is written as six big-endian Fq
+    /// elements. One is encoded as all zeros. Only meaningful for elements of Gt.
+    pub fn to_compressed(&self) -> [u8; 192] {
+        let c = self.0.torus_compress();
+        let mut bytes = [0u8; 192];
+        for (i, fq) in [c.c0, c.c1, c.c2]
+            .iter()
+            .flat_map(|c| [*c.real(), *c.imaginary()])
+            .enumerate()
+        {
+            Fq(fq)
+                .to_big_endian(&mut bytes[32 * i..32 * (i + 1)])
+                .expect("slice is 32 bytes; qed");
+        }
+        bytes
+    }
+
+    /// Decode `to_compressed`, checking that the result is in the order r subgroup.
+    pub fn from_compressed(bytes: &[u8]) -> Result<Self, CurveError> {
+        if bytes.len() != 192 {
+            return Err(CurveError::InvalidEncoding);
+        }
+
+        let mut fqs = [fields::Fq::zero(); 6];
+        for (i, fq) in fqs.iter_mut().enumerate() {
+            *fq = Fq::from_slice(&bytes[32 * i..32 * (i + 1)])?.0;
+        }
+        let c = fields::Fq6::new(
+            fields::Fq2::new(fqs[0], fqs[1]),
+            fields::Fq2::new(fqs[2], fqs[3]),
+            fields::Fq2::new(fqs[4], fqs[5]),
+        );
+
+        let gt = fields::Fq12::torus_decompress(c);
+        if gt.pow(fields::Fr::modulus()) != fields::Fq12::one() {
+            return Err(CurveError::NotMember);
+        }
+
+        Ok(Gt(gt))
+    }
 }
 
 impl Mul<Gt> for Gt {
@@ -671,18 +756,17 @@ impl Mul<Gt> for Gt {
     }
 }
 
+/// `expand_message_xmd` with SHA-256 as specified in RFC 9380.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    groups::expand_message_xmd(msg, dst, len_in_bytes)
+}
+
 pub fn pairing(p: G1, q: G2) -> Gt {
-    Gt(groups::pairing(&p.0, &q.0))
+    (backend::backend().pairing_batch)(&[(p, q)])
 }
 
 pub fn pairing_batch(pairs: &[(G1, G2)]) -> Gt {
-    let mut ps: Vec<groups::G1> = Vec::new();
-    let mut qs: Vec<groups::G2> = Vec::new();
-    for (p, q) in pairs {
-        ps.push(p.0);
-        qs.push(q.0);
-    }
-    Gt(groups::pairing_batch(&ps, &qs))
+    (backend::backend().pairing_batch)(pairs)
 }
 
 pub fn miller_loop_batch(pairs: &[(G2, G1)]) -> Result<Gt, CurveError> {
@@ -757,7 +841,7 @@ impl From<AffineG2> for G2 {
 #[cfg(test)]
 mod tests {
     extern crate rustc_hex as hex;
-    use super::{Fq, Fq2, G1, G2};
+    use super::{pairing, Fq, Fq2, Fr, Group, Gt, G1, G2};
     use alloc::vec::Vec;
 
     fn hex(s: &'static str) -> Vec<u8> {
@@ -862,4 +946,69 @@ mod tests {
             ).is_err()
         );
     }
+
+    #[test]
+    fn g1_compressed_round_trip() {
+        let bytes = hex("0230644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd46");
+        assert_eq!(G1::from_compressed(&bytes).unwrap().to_compressed().to_vec(), bytes);
+
+        let rng = &mut ::rand::thread_rng();
+        for _ in 0..10 {
+            let p = G1::random(rng);
+            assert_eq!(G1::from_compressed(&p.to_compressed()).unwrap(), p);
+            assert_eq!(G1::from_compressed(&(-p).to_compressed()).unwrap(), -p);
+        }
+        assert_eq!(G1::from_compressed(&G1::zero().to_compressed()).unwrap(), G1::zero());
+
+        // wrong length, invalid prefix and x with no point on the curve
+        assert!(G1::from_compressed(&bytes[1..]).is_err());
+        let mut invalid = bytes.clone();
+        invalid[0] = 4;
+        assert!(G1::from_compressed(&invalid).is_err());
+        let mut x = [0u8; 33];
+        x[0] = 2;
+        x[32] = 4;
+        assert!(G1::from_compressed(&x).is_err());
+    }
+
+    #[test]
+    fn g2_compressed_round_trip() {
+        let bytes = hex("0a023aed31b5a9e486366ea9988b05dba469c6206e58361d9c065bbea7d928204a761efc6e4fa08ed227650134b52c7f7dd0463963e8a4bf21f4899fe5da7f984a");
+        assert_eq!(G2::from_compressed(&bytes).unwrap().to_compressed().to_vec(), bytes);
+
+        let rng = &mut ::rand::thread_rng();
+        for _ in 0..10 {
+            let p = G2::random(rng);
+            assert_eq!(G2::from_compressed(&p.to_compressed()).unwrap(), p);
+            assert_eq!(G2::from_compressed(&(-p).to_compressed()).unwrap(), -p);
+        }
+        assert_eq!(G2::from_compressed(&G2::zero().to_compressed()).unwrap(), G2::zero());
+
+        assert!(G2::from_compressed(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn gt_compressed_round_trip() {
+        let rng = &mut ::rand::thread_rng();
+        for _ in 0..10 {
+            let gt = pairing(G1::random(rng), G2::random(rng));
+            assert!(Gt::from_compressed(&gt.to_compressed()).unwrap() == gt);
+            assert!(Gt::from_compressed(&gt.inverse().unwrap().to_compressed()).unwrap() == gt.inverse().unwrap());
+        }
+        assert!(Gt::from_compressed(&Gt::one().to_compressed()).unwrap() == Gt::one());
+        assert!(Gt::from_compressed(&[0u8; 192]).unwrap() == Gt::one());
+
+        let gt = pairing(G1::one(), G2::one()).pow(Fr::random(rng)).to_compressed();
+        assert!(Gt::from_compressed(&gt[1..]).is_err());
+
+        // not a canonical field element
+        let mut invalid = gt;
+        invalid[..32].copy_from_slice(&[0xff; 32]);
+        assert!(Gt::from_compressed(&invalid).is_err());
+
+        // a unitary element outside of the order r subgroup
+        let mut invalid = [0u8; 192];
+        invalid[31] = 1;
+        assert!(Gt::from_compressed(&invalid).is_err());
+    }
 }
@@ -14,18 +14,23 @@ use criterion::Criterion;
 // something to avoid the duplication.
 
 extern crate bulletproofs;
-extern crate curve25519_dalek;
+extern crate bn;
 extern crate merlin;
 extern crate rand;
 
 use bulletproofs::r1cs::*;
 use bulletproofs::{BulletproofGens, PedersenGens};
-use curve25519_dalek::ristretto::CompressedRistretto;
-use curve25519_dalek::scalar::Scalar;
+use bn::arith::U256;
+use bn::{Fr as Scalar, G1};
 use merlin::Transcript;
 use rand::seq::SliceRandom;
 use rand::Rng;
 
+/// The scalar `x`.
+fn scalar(x: u64) -> Scalar {
+    Scalar::new_mul_factor(U256::from(x))
+}
+
 // Shuffle gadget (documented in markdown file)
 
 /// A proof-of-shuffle.
@@ -85,11 +90,7 @@ impl ShuffleProof {
         input: &[Scalar],
         output: &[Scalar],
     ) -> Result<
-        (
-            ShuffleProof,
-            Vec<CompressedRistretto>,
-            Vec<CompressedRistretto>,
-        ),
+        (ShuffleProof, Vec<G1>, Vec<G1>),
         R1CSError,
     > {
         // Apply a domain separator with the shuffle parameters to the transcript
@@ -129,8 +130,8 @@ impl ShuffleProof {
         pc_gens: &'b PedersenGens,
         bp_gens: &'b BulletproofGens,
         transcript: &'a mut Transcript,
-        input_commitments: &Vec<CompressedRistretto>,
-        output_commitments: &Vec<CompressedRistretto>,
+        input_commitments: &Vec<G1>,
+        output_commitments: &Vec<G1>,
     ) -> Result<(), R1CSError> {
         // Apply a domain separator with the shuffle parameters to the transcript
         // XXX should this be part of the gadget?
@@ -175,7 +176,7 @@ fn bench_kshuffle_prove(c: &mut Criterion) {
             let mut rng = rand::thread_rng();
             let (min, max) = (0u64, std::u64::MAX);
             let input: Vec<Scalar> = (0..*k)
-                .map(|_| Scalar::from(rng.gen_range(min..max)))
+                .map(|_| scalar(rng.gen_range(min..max)))
                 .collect();
             let mut output = input.clone();
             output.shuffle(&mut rand::thread_rng());
@@ -217,7 +218,7 @@ fn bench_kshuffle_verify(c: &mut Criterion) {
                 let mut rng = rand::thread_rng();
                 let (min, max) = (0u64, std::u64::MAX);
                 let input: Vec<Scalar> = (0..*k)
-                    .map(|_| Scalar::from(rng.gen_range(min..max)))
+                    .map(|_| scalar(rng.gen_range(min..max)))
                     .collect();
                 let mut output = input.clone();
                 output.shuffle(&mut rand::thread_rng());
@@ -27,13 +27,17 @@ pub enum ProofError {
     /// bitsize other than \\(8\\), \\(16\\), \\(32\\), or \\(64\\).
     #[cfg_attr(feature = "std", error("Invalid bitsize, must have n = 8,16,32,64."))]
     InvalidBitsize,
-    /// This error occurs when attempting to create an aggregated
-    /// proof with non-power-of-two aggregation size.
+    /// This error occurs when attempting to create or verify an
+    /// aggregated proof for no values.
     #[cfg_attr(
         feature = "std",
-        error("Invalid aggregation size, m must be a power of 2.")
+        error("Invalid aggregation size, m must be at least 1.")
     )]
     InvalidAggregation,
+    /// This error occurs when the interval of a proof is empty,
+    /// or does not contain the value to prove.
+    #[cfg_attr(feature = "std", error("Invalid range, the value must be in [min, max]."))]
+    InvalidRange,
     /// This error occurs when there are insufficient generators for the proof.
     #[cfg_attr(
         feature = "std",
@@ -80,10 +84,10 @@ pub enum MPCError {
     #[cfg_attr(feature = "std", error("Invalid bitsize, must have n = 8,16,32,64"))]
     InvalidBitsize,
     /// This error occurs when attempting to create an aggregated
-    /// proof with non-power-of-two aggregation size.
+    /// proof for no parties.
     #[cfg_attr(
         feature = "std",
-        error("Invalid aggregation size, m must be a power of 2")
+        error("Invalid aggregation size, m must be at least 1")
     )]
     InvalidAggregation,
     /// This error occurs when there are insufficient generators for the proof.
@@ -165,3 +169,79 @@ impl From<ProofError> for R1CSError {
         }
     }
 }
+
+/// Represents an error while running the aggregated rangeproof MPC
+/// protocol over a network with the
+/// [`runner`](crate::range_proof_mpc::runner).
+#[cfg(feature = "mpc-runner")]
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum RunnerError {
+    /// This error occurs when parties do not send their message
+    /// to the dealer before the timeout.
+    #[error("Parties {parties:?} did not answer in time")]
+    PartyTimeout {
+        /// The positions of the parties that did not answer.
+        parties: Vec<usize>,
+    },
+    /// This error occurs when the connections to parties fail, or
+    /// they send a message that does not decode or does not fit the
+    /// current step of the session.
+    #[error("Malformed messages from parties {parties:?}")]
+    MalformedMessages {
+        /// The positions of the parties that sent malformed messages.
+        parties: Vec<usize>,
+    },
+    /// This error occurs when the dealer does not send its message
+    /// to a party before the timeout.
+    #[error("The dealer did not answer in time")]
+    DealerTimeout,
+    /// This error occurs when the connection to the dealer fails, or
+    /// it sends a message that does not decode or does not fit the
+    /// current step of the session.
+    #[error("Malformed message from the dealer")]
+    MalformedDealerMessage,
+    /// This error occurs when the dealer aborts the session, because
+    /// another party timed out or misbehaved.
+    #[error("The dealer aborted the session")]
+    Aborted,
+    /// This error occurs when the MPC protocol fails, in particular
+    /// with [`MPCError::MalformedProofShares`] for the parties that
+    /// sent invalid proof shares.
+    #[error("MPC protocol error: {0}")]
+    MPC(MPCError),
+    /// This error occurs when the dealer is set up with invalid
+    /// parameters for the proof.
+    #[error("Proof error: {0}")]
+    Proof(ProofError),
+}
+
+#[cfg(feature = "mpc-runner")]
+impl RunnerError {
+    /// The positions of the parties that are to blame for the error,
+    /// which can be left out of the next session.
+    pub fn bad_parties(&self) -> &[usize] {
+        match self {
+            RunnerError::PartyTimeout { parties } => parties,
+            RunnerError::MalformedMessages { parties } => parties,
+            RunnerError::MPC(MPCError::MalformedProofShares { bad_shares }) => bad_shares,
+            _ => &[],
+        }
+    }
+}
+
+#[cfg(feature = "mpc-runner")]
+impl From<MPCError> for RunnerError {
+    fn from(e: MPCError) -> RunnerError {
+        RunnerError::MPC(e)
+    }
+}
+
+#[cfg(feature = "mpc-runner")]
+impl From<ProofError> for RunnerError {
+    fn from(e: ProofError) -> RunnerError {
+        match e {
+            ProofError::ProvingError(e) => RunnerError::MPC(e),
+            _ => RunnerError::Proof(e),
+        }
+    }
+}
@@ -7,16 +7,16 @@
 extern crate alloc;
 
 use alloc::vec::Vec;
-use digest::Digest;
 use bn::{G1, Fr as Scalar, Group};
-use rand::SeedableRng;
-use rand_chacha::ChaCha20Rng;
-use sha3::Sha3_256;
 use crate::util::{hash_to_g1};
 use crate::ext_traits::{PointToBytes};
 
 const PED_GEN_DOMAIN: &[u8; 20] = b"bulletproofs-ped-gen";
 
+/// Domain separation tag of the `GeneratorsChain` hash to G1. The version must be bumped
+/// whenever the chain changes, since generators of different versions are unrelated.
+const GENERATORS_CHAIN_DST: &[u8] = b"BULLETPROOFS-V01-GeneratorsChain-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+
 /// Represents a pair of base points for Pedersen commitments.
 ///
 /// The Bulletproofs implementation and API is designed to support
@@ -25,9 +25,8 @@ const PED_GEN_DOMAIN: &[u8; 20] = b"bulletproofs-ped-gen";
 ///
 /// The default generators are:
 ///
-/// * `B`: the `ristretto255` basepoint;
-/// * `B_blinding`: the result of `ristretto255` SHA3-512
-/// hash-to-group on input `B_bytes`.
+/// * `B`: the hash to G1 of the compressed `B_blinding` and a domain separator;
+/// * `B_blinding`: the G1 generator.
 #[derive(Copy, Clone)]
 pub struct PedersenGens {
     /// Base for the committed value
@@ -69,22 +68,20 @@ impl Default for PedersenGens {
 }
 
 /// The `GeneratorsChain` creates an arbitrary-long sequence of
-/// orthogonal generators.  The sequence can be deterministically
-/// produced starting with an arbitrary point.
+/// orthogonal generators.  The i-th generator is the hash to G1 of
+/// `label || i`, so nobody knows the discrete log between any two of them.
 struct GeneratorsChain {
-    rng: ChaCha20Rng,
+    label: Vec<u8>,
+    counter: u64,
 }
 
 impl GeneratorsChain {
-    /// Creates a chain of generators, determined by the hash of `label`.
+    /// Creates a chain of generators, determined by `label`.
     fn new(label: &[u8]) -> Self {
-        // TODO: check if we use Shake256 / Sha3 anywhere else
-        let mut sha3 = Sha3_256::new();
-        sha3.update(b"GeneratorsChain");
-        sha3.update(label);
-
-        let rng = ChaCha20Rng::from_seed(sha3.finalize().into());
-        GeneratorsChain { rng }
+        GeneratorsChain {
+            label: label.to_vec(),
+            counter: 0,
+        }
     }
 }
 
@@ -98,7 +95,18 @@ impl Iterator for GeneratorsChain {
     type Item = G1;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(G1::random(&mut self.rng))
+        let msg = self.label.iter()
+            .chain(self.counter.to_le_bytes().iter())
+            .copied()
+            .collect::<Vec<u8>>();
+        self.counter += 1;
+
+        Some(G1::hash_to_curve(&msg, GENERATORS_CHAIN_DST))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.counter += n as u64;
+        self.next()
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -115,9 +123,8 @@ impl Iterator for GeneratorsChain {
 /// described in the Bulletproofs paper, we construct each party's
 /// generators separately.
 ///
-/// To construct an arbitrary-length chain of generators, we apply
-/// SHAKE256 to a domain separator label, and feed each 64 bytes of
-/// XOF output into the `ristretto255` hash-to-group function.
+/// To construct an arbitrary-length chain of generators, we hash a
+/// domain separator label followed by a counter to G1.
 /// Each of the `m` parties' generators are constructed using a
 /// different domain separation label, and proving and verification
 /// uses the first `n` elements of the arbitrary-length chain.
@@ -12,9 +12,11 @@ use core::iter;
 use merlin::Transcript;
 
 use crate::errors::ProofError;
+use crate::range_proof::{FIELD_BYTES_LEN, G_COMP_BYTES_LEN};
 use crate::transcript::TranscriptProtocol;
+use crate::util;
 
-#[derive(Eq, PartialEq, BorshSerialize, BorshDeserialize, Clone, Debug)]
+#[derive(Eq, PartialEq, Clone, Debug)]
 pub struct InnerProductProof {
     pub(crate) L_vec: Vec<G1>,
     pub(crate) R_vec: Vec<G1>,
@@ -327,96 +329,103 @@ impl InnerProductProof {
     /// product proof.
     ///
     /// For vectors of length `n` the proof size is
-    /// \\(32 \cdot (2\lg n+2)\\) bytes.
+    /// \\(33 \cdot 2\lg n + 32 \cdot 2\\) bytes.
     pub fn serialized_size(&self) -> usize {
-        (self.L_vec.len() * 2) * 48 + 2 * 32
+        (self.L_vec.len() * 2) * G_COMP_BYTES_LEN + 2 * FIELD_BYTES_LEN
     }
 
-    ///// Serializes the proof into a byte array of \\(2n+2\\) 32-byte elements.
-    ///// The layout of the inner product proof is:
-    ///// * \\(n\\) pairs of compressed Ristretto points \\(L_0, R_0 \dots, L_{n-1}, R_{n-1}\\),
-    ///// * two scalars \\(a, b\\).
-    //#[allow(dead_code)]
-    //pub fn to_bytes(&self) -> Vec<u8> {
-        //let mut buf = Vec::with_capacity(self.serialized_size());
-        //for (l, r) in self.L_vec.iter().zip(self.R_vec.iter()) {
-            //buf.extend_from_slice(&l.to_compressed());
-            //buf.extend_from_slice(&r.to_compressed());
-        //}
-        //buf.extend_from_slice(&self.a.to_bytes_le());
-        //buf.extend_from_slice(&self.b.to_bytes_le());
-        //buf
-    //}
-
-    ///// Converts the proof into a byte iterator over serialized view of the proof.
-    ///// The layout of the inner product proof is:
-    ///// * \\(n\\) pairs of compressed Ristretto points \\(L_0, R_0 \dots, L_{n-1}, R_{n-1}\\),
-    ///// * two scalars \\(a, b\\).
-    //#[inline]
-    //pub(crate) fn to_bytes_iter(&self) -> impl Iterator<Item = u8> + '_ {
-        //self.L_vec
-            //.iter()
-            //.zip(self.R_vec.iter())
-            //.flat_map(|(l, r)| {
-                //l.to_compressed()
-                    //.iter()
-                    //.copied()
-                    //.chain(r.to_compressed())
-                    //.collect::<Vec<_>>()
-            //})
-            //.chain(self.a.to_bytes_le())
-            //.chain(self.b.to_bytes_le())
-    //}
-
-    ///// Deserializes the proof from a byte slice.
-    ///// Returns an error in the following cases:
-    ///// * the slice does not have \\(2n\\) 48-byte elements + 2 32-byte elements,
-    ///// * \\(n\\) is larger or equal to 32 (proof is too big),
-    ///// * any of \\(2n\\) points are not valid compressed bls12-381 G1 points,
-    ///// * any of 2 scalars are not canonical scalars modulo bls12-381 G1 group order.
-    //pub fn from_bytes(slice: &[u8]) -> Result<InnerProductProof, ProofError> {
-        //let b = slice.len();
-        //if b < 2 * 32 {
-            //return Err(ProofError::FormatError);
-        //}
-        //if (b - 32 * 2) % 48 != 0 {
-            //// last two elements are scalars,
-            //return Err(ProofError::FormatError);
-        //}
-        //let num_points = (b - 32 * 2) / 48;
-        //if num_points % 2 != 0 {
-            //return Err(ProofError::FormatError);
-        //}
-
-        //let lg_n = num_points / 2;
-        //if lg_n >= 32 {
-            //return Err(ProofError::FormatError);
-        //}
-
-        //use crate::util::{read32, read48};
-
-        //let mut L_vec: Vec<G1> = Vec::with_capacity(lg_n);
-        //let mut R_vec: Vec<G1> = Vec::with_capacity(lg_n);
-        //for i in 0..lg_n {
-            //let pos = 2 * i * 48;
-            //L_vec.push(
-                //Option::from(G1Projective::from_compressed(&read48(&slice[pos..])))
-                    //.ok_or(ProofError::FormatError)?,
-            //);
-            //R_vec.push(
-                //Option::from(G1Projective::from_compressed(&read48(&slice[pos + 48..])))
-                    //.ok_or(ProofError::FormatError)?,
-            //);
-        //}
-
-        //let pos = 2 * lg_n * 48;
-        //let a = Option::from(Scalar::from_bytes_le(&read32(&slice[pos..])))
-            //.ok_or(ProofError::FormatError)?;
-        //let b = Option::from(Scalar::from_bytes_le(&read32(&slice[pos + 32..])))
-            //.ok_or(ProofError::FormatError)?;
-
-        //Ok(InnerProductProof { L_vec, R_vec, a, b })
-    //}
+    /// Serializes the proof into a byte array of \\(2n\\) 33-byte points and 2 32-byte scalars.
+    /// The layout of the inner product proof is:
+    /// * \\(n\\) pairs of compressed G1 points \\(L_0, R_0 \dots, L_{n-1}, R_{n-1}\\),
+    /// * two scalars \\(a, b\\).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.serialized_size());
+        for (l, r) in self.L_vec.iter().zip(self.R_vec.iter()) {
+            buf.extend_from_slice(&l.to_compressed());
+            buf.extend_from_slice(&r.to_compressed());
+        }
+        buf.extend_from_slice(&util::scalar_to_bytes(&self.a));
+        buf.extend_from_slice(&util::scalar_to_bytes(&self.b));
+        buf
+    }
+
+    /// Converts the proof into a byte iterator over serialized view of the proof.
+    /// The layout of the inner product proof is:
+    /// * \\(n\\) pairs of compressed G1 points \\(L_0, R_0 \dots, L_{n-1}, R_{n-1}\\),
+    /// * two scalars \\(a, b\\).
+    #[inline]
+    pub(crate) fn to_bytes_iter(&self) -> impl Iterator<Item = u8> + '_ {
+        self.L_vec
+            .iter()
+            .zip(self.R_vec.iter())
+            .flat_map(|(l, r)| l.to_compressed().into_iter().chain(r.to_compressed()))
+            .chain(util::scalar_to_bytes(&self.a))
+            .chain(util::scalar_to_bytes(&self.b))
+    }
+
+    /// Deserializes the proof from a byte slice.
+    /// Returns an error in the following cases:
+    /// * the slice does not have \\(2n\\) 33-byte elements + 2 32-byte elements,
+    /// * \\(n\\) is larger or equal to 32 (proof is too big),
+    /// * any of \\(2n\\) points are not valid compressed BN254 G1 points,
+    /// * any of 2 scalars are not canonical scalars modulo the BN254 group order.
+    pub fn from_bytes(slice: &[u8]) -> Result<InnerProductProof, ProofError> {
+        let b = slice.len();
+        if b < 2 * FIELD_BYTES_LEN {
+            return Err(ProofError::FormatError);
+        }
+        if (b - 2 * FIELD_BYTES_LEN) % (2 * G_COMP_BYTES_LEN) != 0 {
+            // last two elements are scalars,
+            // the points before them come in pairs
+            return Err(ProofError::FormatError);
+        }
+
+        let lg_n = (b - 2 * FIELD_BYTES_LEN) / (2 * G_COMP_BYTES_LEN);
+        if lg_n >= 32 {
+            return Err(ProofError::FormatError);
+        }
+
+        let mut L_vec: Vec<G1> = Vec::with_capacity(lg_n);
+        let mut R_vec: Vec<G1> = Vec::with_capacity(lg_n);
+        for i in 0..lg_n {
+            let pos = 2 * i * G_COMP_BYTES_LEN;
+            L_vec.push(util::read_point(&slice[pos..]).ok_or(ProofError::FormatError)?);
+            R_vec.push(
+                util::read_point(&slice[pos + G_COMP_BYTES_LEN..])
+                    .ok_or(ProofError::FormatError)?,
+            );
+        }
+
+        let pos = 2 * lg_n * G_COMP_BYTES_LEN;
+        let a = util::scalar_from_bytes(&util::read32(&slice[pos..]))
+            .ok_or(ProofError::FormatError)?;
+        let b = util::scalar_from_bytes(&util::read32(&slice[pos + FIELD_BYTES_LEN..]))
+            .ok_or(ProofError::FormatError)?;
+
+        Ok(InnerProductProof { L_vec, R_vec, a, b })
+    }
+}
+
+/// Borsh writes the compact encoding of [`InnerProductProof::to_bytes`], prefixed with its length.
+impl BorshSerialize for InnerProductProof {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> core::result::Result<(), borsh::maybestd::io::Error> {
+        BorshSerialize::serialize(&self.to_bytes(), writer)
+    }
+}
+
+impl BorshDeserialize for InnerProductProof {
+    fn deserialize(buf: &mut &[u8]) -> core::result::Result<Self, borsh::maybestd::io::Error> {
+        let bytes = Vec::<u8>::deserialize(buf)?;
+        InnerProductProof::from_bytes(&bytes).map_err(|_| {
+            borsh::maybestd::io::Error::new(
+                borsh::maybestd::io::ErrorKind::InvalidData,
+                "invalid inner product proof",
+            )
+        })
+    }
 }
 
 /// Computes an inner product of two vectors
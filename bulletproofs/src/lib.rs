@@ -28,7 +28,7 @@ mod errors;
 mod generators;
 mod inner_product_proof;
 mod range_proof;
-mod transcript;
+pub mod transcript;
 mod alt_bn128;
 pub mod ext_traits;
 
@@ -48,6 +48,10 @@ pub mod range_proof_mpc {
     pub use crate::range_proof::dealer;
     pub use crate::range_proof::messages;
     pub use crate::range_proof::party;
+    #[cfg(feature = "mpc-runner")]
+    pub use crate::errors::RunnerError;
+    #[cfg(feature = "mpc-runner")]
+    pub use crate::range_proof::runner;
 }
 
 #[cfg(feature = "yoloproofs")]
@@ -1,8 +1,11 @@
 //! Definition of linear combinations.
 
-use curve25519_dalek::scalar::Scalar;
-use std::iter::FromIterator;
-use std::ops::{Add, Mul, Neg, Sub};
+use alloc::vec::Vec;
+use bn::Fr as Scalar;
+use core::iter::FromIterator;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::util::u64_to_scalar;
 
 /// Represents a variable in a constraint system.
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -27,14 +30,20 @@ impl From<Variable> for LinearCombination {
     }
 }
 
-impl<S: Into<Scalar>> From<S> for LinearCombination {
-    fn from(s: S) -> LinearCombination {
+impl From<Scalar> for LinearCombination {
+    fn from(s: Scalar) -> LinearCombination {
         LinearCombination {
-            terms: vec![(Variable::One(), s.into())],
+            terms: vec![(Variable::One(), s)],
         }
     }
 }
 
+impl From<u64> for LinearCombination {
+    fn from(x: u64) -> LinearCombination {
+        u64_to_scalar(x).into()
+    }
+}
+
 // Arithmetic on variables produces linear combinations
 
 impl Neg for Variable {
@@ -61,16 +70,24 @@ impl<L: Into<LinearCombination>> Sub<L> for Variable {
     }
 }
 
-impl<S: Into<Scalar>> Mul<S> for Variable {
+impl Mul<Scalar> for Variable {
     type Output = LinearCombination;
 
-    fn mul(self, other: S) -> Self::Output {
+    fn mul(self, other: Scalar) -> Self::Output {
         LinearCombination {
-            terms: vec![(self, other.into())],
+            terms: vec![(self, other)],
         }
     }
 }
 
+impl Mul<u64> for Variable {
+    type Output = LinearCombination;
+
+    fn mul(self, other: u64) -> Self::Output {
+        self * u64_to_scalar(other)
+    }
+}
+
 // Arithmetic on scalars with variables produces linear combinations
 
 impl Add<Variable> for Scalar {
@@ -155,7 +172,7 @@ impl<L: Into<LinearCombination>> Sub<L> for LinearCombination {
 
     fn sub(mut self, rhs: L) -> Self::Output {
         self.terms
-            .extend(rhs.into().terms.iter().map(|(var, coeff)| (*var, -coeff)));
+            .extend(rhs.into().terms.iter().map(|(var, coeff)| (*var, -*coeff)));
         LinearCombination { terms: self.terms }
     }
 }
@@ -184,14 +201,21 @@ impl Neg for LinearCombination {
     }
 }
 
-impl<S: Into<Scalar>> Mul<S> for LinearCombination {
+impl Mul<Scalar> for LinearCombination {
     type Output = Self;
 
-    fn mul(mut self, other: S) -> Self::Output {
-        let other = other.into();
+    fn mul(mut self, other: Scalar) -> Self::Output {
         for (_, s) in self.terms.iter_mut() {
-            *s *= other
+            *s = *s * other
         }
         self
     }
 }
+
+impl Mul<u64> for LinearCombination {
+    type Output = Self;
+
+    fn mul(self, other: u64) -> Self::Output {
+        self * u64_to_scalar(other)
+    }
+}
@@ -1,12 +1,13 @@
 #![allow(non_snake_case)]
 //! Definition of the proof struct.
 
-use curve25519_dalek::ristretto::CompressedRistretto;
-use curve25519_dalek::scalar::Scalar;
-use curve25519_dalek::traits::{Identity, IsIdentity};
+use alloc::vec::Vec;
+use bn::{Fr as Scalar, Group, G1};
+use borsh::{BorshDeserialize, BorshSerialize};
 
 use crate::errors::R1CSError;
 use crate::inner_product_proof::InnerProductProof;
+use crate::range_proof::{FIELD_BYTES_LEN, G_COMP_BYTES_LEN};
 use crate::util;
 
 use serde::de::Visitor;
@@ -31,31 +32,31 @@ const TWO_PHASE_COMMITMENTS: u8 = 1;
 /// the constraint system using
 /// [`VerifierCS::verify`](::r1cs::VerifierCS::verify) to verify the
 /// proof.
-#[derive(Clone, Debug)]
+#[derive(Eq, PartialEq, BorshSerialize, BorshDeserialize, Clone, Debug)]
 #[allow(non_snake_case)]
 pub struct R1CSProof {
     /// Commitment to the values of input wires in the first phase.
-    pub(super) A_I1: CompressedRistretto,
+    pub(super) A_I1: G1,
     /// Commitment to the values of output wires in the first phase.
-    pub(super) A_O1: CompressedRistretto,
+    pub(super) A_O1: G1,
     /// Commitment to the blinding factors in the first phase.
-    pub(super) S1: CompressedRistretto,
+    pub(super) S1: G1,
     /// Commitment to the values of input wires in the second phase.
-    pub(super) A_I2: CompressedRistretto,
+    pub(super) A_I2: G1,
     /// Commitment to the values of output wires in the second phase.
-    pub(super) A_O2: CompressedRistretto,
+    pub(super) A_O2: G1,
     /// Commitment to the blinding factors in the second phase.
-    pub(super) S2: CompressedRistretto,
+    pub(super) S2: G1,
     /// Commitment to the \\(t_1\\) coefficient of \\( t(x) \\)
-    pub(super) T_1: CompressedRistretto,
+    pub(super) T_1: G1,
     /// Commitment to the \\(t_3\\) coefficient of \\( t(x) \\)
-    pub(super) T_3: CompressedRistretto,
+    pub(super) T_3: G1,
     /// Commitment to the \\(t_4\\) coefficient of \\( t(x) \\)
-    pub(super) T_4: CompressedRistretto,
+    pub(super) T_4: G1,
     /// Commitment to the \\(t_5\\) coefficient of \\( t(x) \\)
-    pub(super) T_5: CompressedRistretto,
+    pub(super) T_5: G1,
     /// Commitment to the \\(t_6\\) coefficient of \\( t(x) \\)
-    pub(super) T_6: CompressedRistretto,
+    pub(super) T_6: G1,
     /// Evaluation of the polynomial \\(t(x)\\) at the challenge point \\(x\\)
     pub(super) t_x: Scalar,
     /// Blinding factor for the synthetic commitment to \\( t(x) \\)
@@ -68,121 +69,117 @@ pub struct R1CSProof {
 }
 
 impl R1CSProof {
-    /// Serializes the proof into a byte array of 1 version byte + \\((13 or 16) + 2k\\) 32-byte elements,
+    /// Serializes the proof into a byte array of 1 version byte, (8 or 11) 33-byte points,
+    /// 3 32-byte scalars and the inner-product proof of \\(2k\\) 33-byte points and 2 32-byte scalars,
     /// where \\(k=\lceil \log_2(n) \rceil\\) and \\(n\\) is the number of multiplication gates.
     ///
     /// # Layout
     ///
     /// The layout of the r1cs proof encoding is:
     /// * 1 version byte indicating whether the proof contains second-phase commitments or not,
-    /// * 8 or 11 compressed Ristretto points \\(A_{I1},A_{O1},S_1,(A_{I2},A_{O2},S_2),T_1,...,T_6\\)
+    /// * 8 or 11 compressed G1 points \\(A_{I1},A_{O1},S_1,(A_{I2},A_{O2},S_2),T_1,...,T_6\\)
     ///   (\\(A_{I2},A_{O2},S_2\\) are skipped if there were no multipliers added in the randomized phase),
     /// * three scalars \\(t_x, \tilde{t}_x, \tilde{e}\\),
-    /// * \\(k\\) pairs of compressed Ristretto points \\(L_0,R_0\dots,L_{k-1},R_{k-1}\\),
+    /// * \\(k\\) pairs of compressed G1 points \\(L_0,R_0\dots,L_{k-1},R_{k-1}\\),
     /// * two scalars \\(a, b\\).
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(self.serialized_size());
         if self.missing_phase2_commitments() {
             buf.push(ONE_PHASE_COMMITMENTS);
-            buf.extend_from_slice(self.A_I1.as_bytes());
-            buf.extend_from_slice(self.A_O1.as_bytes());
-            buf.extend_from_slice(self.S1.as_bytes());
+            buf.extend_from_slice(&self.A_I1.to_compressed());
+            buf.extend_from_slice(&self.A_O1.to_compressed());
+            buf.extend_from_slice(&self.S1.to_compressed());
         } else {
             buf.push(TWO_PHASE_COMMITMENTS);
-            buf.extend_from_slice(self.A_I1.as_bytes());
-            buf.extend_from_slice(self.A_O1.as_bytes());
-            buf.extend_from_slice(self.S1.as_bytes());
-            buf.extend_from_slice(self.A_I2.as_bytes());
-            buf.extend_from_slice(self.A_O2.as_bytes());
-            buf.extend_from_slice(self.S2.as_bytes());
+            buf.extend_from_slice(&self.A_I1.to_compressed());
+            buf.extend_from_slice(&self.A_O1.to_compressed());
+            buf.extend_from_slice(&self.S1.to_compressed());
+            buf.extend_from_slice(&self.A_I2.to_compressed());
+            buf.extend_from_slice(&self.A_O2.to_compressed());
+            buf.extend_from_slice(&self.S2.to_compressed());
         }
-        buf.extend_from_slice(self.T_1.as_bytes());
-        buf.extend_from_slice(self.T_3.as_bytes());
-        buf.extend_from_slice(self.T_4.as_bytes());
-        buf.extend_from_slice(self.T_5.as_bytes());
-        buf.extend_from_slice(self.T_6.as_bytes());
-        buf.extend_from_slice(self.t_x.as_bytes());
-        buf.extend_from_slice(self.t_x_blinding.as_bytes());
-        buf.extend_from_slice(self.e_blinding.as_bytes());
+        buf.extend_from_slice(&self.T_1.to_compressed());
+        buf.extend_from_slice(&self.T_3.to_compressed());
+        buf.extend_from_slice(&self.T_4.to_compressed());
+        buf.extend_from_slice(&self.T_5.to_compressed());
+        buf.extend_from_slice(&self.T_6.to_compressed());
+        buf.extend_from_slice(&util::scalar_to_bytes(&self.t_x));
+        buf.extend_from_slice(&util::scalar_to_bytes(&self.t_x_blinding));
+        buf.extend_from_slice(&util::scalar_to_bytes(&self.e_blinding));
         buf.extend(self.ipp_proof.to_bytes_iter());
         buf
     }
 
     /// Returns the size in bytes required to serialize the `R1CSProof`.
     pub fn serialized_size(&self) -> usize {
-        // version tag + (11 or 14) elements + the ipp
-        let elements = if self.missing_phase2_commitments() {
-            11
+        // version tag + (8 or 11) points + 3 scalars + the ipp
+        let points = if self.missing_phase2_commitments() {
+            8
         } else {
-            14
+            11
         };
-        1 + elements * 32 + self.ipp_proof.serialized_size()
+        1 + points * G_COMP_BYTES_LEN + 3 * FIELD_BYTES_LEN + self.ipp_proof.serialized_size()
     }
 
     fn missing_phase2_commitments(&self) -> bool {
-        self.A_I2.is_identity() && self.A_O2.is_identity() && self.S2.is_identity()
+        self.A_I2.is_zero() && self.A_O2.is_zero() && self.S2.is_zero()
     }
 
     /// Deserializes the proof from a byte slice.
     ///
     /// Returns an error if the byte slice cannot be parsed into a `R1CSProof`.
     pub fn from_bytes(slice: &[u8]) -> Result<R1CSProof, R1CSError> {
-        if slice.len() < 1 {
+        if slice.is_empty() {
             return Err(R1CSError::FormatError);
         }
         let version = slice[0];
         let mut slice = &slice[1..];
 
-        if slice.len() % 32 != 0 {
-            return Err(R1CSError::FormatError);
-        }
-
-        let minlength = match version {
-            ONE_PHASE_COMMITMENTS => 11 * 32,
-            TWO_PHASE_COMMITMENTS => 14 * 32,
+        let points = match version {
+            ONE_PHASE_COMMITMENTS => 8,
+            TWO_PHASE_COMMITMENTS => 11,
             _ => return Err(R1CSError::FormatError),
         };
 
-        if slice.len() < minlength {
+        if slice.len() < points * G_COMP_BYTES_LEN + 3 * FIELD_BYTES_LEN {
             return Err(R1CSError::FormatError);
         }
 
-        // This macro takes care of counting bytes in the slice
-        macro_rules! read32 {
+        // These macros take care of counting bytes in the slice
+        macro_rules! read_point {
+            () => {{
+                let tmp = util::read_point(slice).ok_or(R1CSError::FormatError)?;
+                slice = &slice[G_COMP_BYTES_LEN..];
+                tmp
+            }};
+        }
+        macro_rules! read_scalar {
             () => {{
-                let tmp = util::read32(slice);
-                slice = &slice[32..];
+                let tmp = util::scalar_from_bytes(&util::read32(slice))
+                    .ok_or(R1CSError::FormatError)?;
+                slice = &slice[FIELD_BYTES_LEN..];
                 tmp
             }};
         }
 
-        let A_I1 = CompressedRistretto(read32!());
-        let A_O1 = CompressedRistretto(read32!());
-        let S1 = CompressedRistretto(read32!());
+        let A_I1 = read_point!();
+        let A_O1 = read_point!();
+        let S1 = read_point!();
         let (A_I2, A_O2, S2) = if version == ONE_PHASE_COMMITMENTS {
-            (
-                CompressedRistretto::identity(),
-                CompressedRistretto::identity(),
-                CompressedRistretto::identity(),
-            )
+            (G1::zero(), G1::zero(), G1::zero())
         } else {
-            (
-                CompressedRistretto(read32!()),
-                CompressedRistretto(read32!()),
-                CompressedRistretto(read32!()),
-            )
+            (read_point!(), read_point!(), read_point!())
         };
-        let T_1 = CompressedRistretto(read32!());
-        let T_3 = CompressedRistretto(read32!());
-        let T_4 = CompressedRistretto(read32!());
-        let T_5 = CompressedRistretto(read32!());
-        let T_6 = CompressedRistretto(read32!());
-        let t_x = Scalar::from_canonical_bytes(read32!()).ok_or(R1CSError::FormatError)?;
-        let t_x_blinding = Scalar::from_canonical_bytes(read32!()).ok_or(R1CSError::FormatError)?;
-        let e_blinding = Scalar::from_canonical_bytes(read32!()).ok_or(R1CSError::FormatError)?;
-
-        // XXX: IPPProof from_bytes gives ProofError.
-        let ipp_proof = InnerProductProof::from_bytes(slice).map_err(|_| R1CSError::FormatError)?;
+        let T_1 = read_point!();
+        let T_3 = read_point!();
+        let T_4 = read_point!();
+        let T_5 = read_point!();
+        let T_6 = read_point!();
+        let t_x = read_scalar!();
+        let t_x_blinding = read_scalar!();
+        let e_blinding = read_scalar!();
+
+        let ipp_proof = InnerProductProof::from_bytes(slice)?;
 
         Ok(R1CSProof {
             A_I1,
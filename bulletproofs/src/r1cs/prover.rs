@@ -1,11 +1,10 @@
 #![allow(non_snake_case)]
 
-use clear_on_drop::clear::Clear;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use bn::{Fr as Scalar, Group, G1};
 use core::borrow::BorrowMut;
 use core::mem;
-use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
-use curve25519_dalek::scalar::Scalar;
-use curve25519_dalek::traits::{Identity, MultiscalarMul};
 use merlin::Transcript;
 
 use super::{
@@ -14,6 +13,7 @@ use super::{
 };
 
 use crate::errors::R1CSError;
+use crate::ext_traits::ScalarToBytes;
 use crate::generators::{BulletproofGens, PedersenGens};
 use crate::inner_product_proof::InnerProductProof;
 use crate::r1cs::Metrics;
@@ -74,24 +74,19 @@ pub struct RandomizingProver<'g, T: BorrowMut<Transcript>> {
 /// Overwrite secrets with null bytes when they go out of scope.
 impl Drop for Secrets {
     fn drop(&mut self) {
-        self.v.clear();
-        self.v_blinding.clear();
-
-        // Important: due to how ClearOnDrop auto-implements InitializableFromZeroed
-        // for T: Default, calling .clear() on Vec compiles, but does not
-        // clear the content. Instead, it only clears the Vec's header.
-        // Clearing the underlying buffer item-by-item will do the job, but will
-        // keep the header as-is, which is fine since the header does not contain secrets.
-        for e in self.a_L.iter_mut() {
-            e.clear();
-        }
-        for e in self.a_R.iter_mut() {
-            e.clear();
-        }
-        for e in self.a_O.iter_mut() {
-            e.clear();
+        // `Scalar` is not `Default`, so `clear_on_drop` cannot zero it:
+        // overwrite the buffers item-by-item instead. The headers of the vectors
+        // are kept as-is, which is fine since they do not contain secrets.
+        for e in self
+            .v
+            .iter_mut()
+            .chain(self.v_blinding.iter_mut())
+            .chain(self.a_L.iter_mut())
+            .chain(self.a_R.iter_mut())
+            .chain(self.a_O.iter_mut())
+        {
+            *e = Scalar::zero();
         }
-        // XXX use ClearOnDrop instead of doing the above
     }
 }
 
@@ -290,15 +285,15 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
     ///
     /// # Returns
     ///
-    /// Returns a pair of a Pedersen commitment (as a compressed Ristretto point),
+    /// Returns a pair of a Pedersen commitment (as a `G1` point),
     /// and a [`Variable`] corresponding to it, which can be used to form constraints.
-    pub fn commit(&mut self, v: Scalar, v_blinding: Scalar) -> (CompressedRistretto, Variable) {
+    pub fn commit(&mut self, v: Scalar, v_blinding: Scalar) -> (G1, Variable) {
         let i = self.secrets.v.len();
         self.secrets.v.push(v);
         self.secrets.v_blinding.push(v_blinding);
 
         // Add the commitment to the transcript.
-        let V = self.pc_gens.commit(v, v_blinding).compress();
+        let V = self.pc_gens.commit(v, v_blinding);
         self.transcript.borrow_mut().append_point(b"V", &V);
 
         (V, Variable::Committed(i))
@@ -332,23 +327,23 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
             for (var, coeff) in &lc.terms {
                 match var {
                     Variable::MultiplierLeft(i) => {
-                        wL[*i] += exp_z * coeff;
+                        wL[*i] = wL[*i] + exp_z * *coeff;
                     }
                     Variable::MultiplierRight(i) => {
-                        wR[*i] += exp_z * coeff;
+                        wR[*i] = wR[*i] + exp_z * *coeff;
                     }
                     Variable::MultiplierOutput(i) => {
-                        wO[*i] += exp_z * coeff;
+                        wO[*i] = wO[*i] + exp_z * *coeff;
                     }
                     Variable::Committed(i) => {
-                        wV[*i] -= exp_z * coeff;
+                        wV[*i] = wV[*i] - exp_z * *coeff;
                     }
                     Variable::One() => {
                         // The prover doesn't need to handle constant terms
                     }
                 }
             }
-            exp_z *= z;
+            exp_z = exp_z * *z;
         }
 
         (wL, wR, wO, wV)
@@ -359,7 +354,7 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
         lc.terms
             .iter()
             .map(|(var, coeff)| {
-                coeff
+                *coeff
                     * match var {
                         Variable::MultiplierLeft(i) => self.secrets.a_L[*i],
                         Variable::MultiplierRight(i) => self.secrets.a_R[*i],
@@ -368,7 +363,7 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
                         Variable::One() => Scalar::one(),
                     }
             })
-            .sum()
+            .fold(Scalar::zero(), |sum, term| sum + term)
     }
 
     /// Calls all remembered callbacks with an API that
@@ -406,7 +401,7 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
         bp_gens: &BulletproofGens,
     ) -> Result<(R1CSProof, T), R1CSError> {
         use crate::util;
-        use std::iter;
+        use core::iter;
 
         // Commit a length _suffix_ for the number of high-level variables.
         // We cannot do this in advance because user can commit variables one-by-one,
@@ -434,7 +429,7 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
 
             // Commit the blinding factors for the input wires
             for v_b in &self.secrets.v_blinding {
-                builder = builder.rekey_with_witness_bytes(b"v_blinding", v_b.as_bytes());
+                builder = builder.rekey_with_witness_bytes(b"v_blinding", &v_b.to_bytes_le());
             }
 
             use rand::thread_rng;
@@ -459,33 +454,30 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
         let mut s_R1: Vec<Scalar> = (0..n1).map(|_| Scalar::random(&mut rng)).collect();
 
         // A_I = <a_L, G> + <a_R, H> + i_blinding * B_blinding
-        let A_I1 = RistrettoPoint::multiscalar_mul(
+        let A_I1 = multiscalar_mul(
             iter::once(&i_blinding1)
                 .chain(self.secrets.a_L.iter())
                 .chain(self.secrets.a_R.iter()),
             iter::once(&self.pc_gens.B_blinding)
                 .chain(gens.G(n1))
                 .chain(gens.H(n1)),
-        )
-        .compress();
+        );
 
         // A_O = <a_O, G> + o_blinding * B_blinding
-        let A_O1 = RistrettoPoint::multiscalar_mul(
+        let A_O1 = multiscalar_mul(
             iter::once(&o_blinding1).chain(self.secrets.a_O.iter()),
             iter::once(&self.pc_gens.B_blinding).chain(gens.G(n1)),
-        )
-        .compress();
+        );
 
         // S = <s_L, G> + <s_R, H> + s_blinding * B_blinding
-        let S1 = RistrettoPoint::multiscalar_mul(
+        let S1 = multiscalar_mul(
             iter::once(&s_blinding1)
                 .chain(s_L1.iter())
                 .chain(s_R1.iter()),
             iter::once(&self.pc_gens.B_blinding)
                 .chain(gens.G(n1))
                 .chain(gens.H(n1)),
-        )
-        .compress();
+        );
 
         let transcript = self.transcript.borrow_mut();
         transcript.append_point(b"A_I1", &A_I1);
@@ -527,42 +519,35 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
         let (A_I2, A_O2, S2) = if has_2nd_phase_commitments {
             (
                 // A_I = <a_L, G> + <a_R, H> + i_blinding * B_blinding
-                RistrettoPoint::multiscalar_mul(
+                multiscalar_mul(
                     iter::once(&i_blinding2)
                         .chain(self.secrets.a_L.iter().skip(n1))
                         .chain(self.secrets.a_R.iter().skip(n1)),
                     iter::once(&self.pc_gens.B_blinding)
                         .chain(gens.G(n).skip(n1))
                         .chain(gens.H(n).skip(n1)),
-                )
-                .compress(),
+                ),
                 // A_O = <a_O, G> + o_blinding * B_blinding
-                RistrettoPoint::multiscalar_mul(
+                multiscalar_mul(
                     iter::once(&o_blinding2).chain(self.secrets.a_O.iter().skip(n1)),
                     iter::once(&self.pc_gens.B_blinding).chain(gens.G(n).skip(n1)),
-                )
-                .compress(),
+                ),
                 // S = <s_L, G> + <s_R, H> + s_blinding * B_blinding
-                RistrettoPoint::multiscalar_mul(
+                multiscalar_mul(
                     iter::once(&s_blinding2)
                         .chain(s_L2.iter())
                         .chain(s_R2.iter()),
                     iter::once(&self.pc_gens.B_blinding)
                         .chain(gens.G(n).skip(n1))
                         .chain(gens.H(n).skip(n1)),
-                )
-                .compress(),
+                ),
             )
         } else {
             // Since we are using zero blinding factors and
             // there are no variables to commit,
             // the commitments _must_ be identity points,
-            // so we can hardcode them saving 3 mults+compressions.
-            (
-                CompressedRistretto::identity(),
-                CompressedRistretto::identity(),
-                CompressedRistretto::identity(),
-            )
+            // so we can hardcode them saving 3 multiexps.
+            (G1::zero(), G1::zero(), G1::zero())
         };
 
         let transcript = self.transcript.borrow_mut();
@@ -581,7 +566,7 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
         let mut r_poly = util::VecPoly3::zero(n);
 
         let mut exp_y = Scalar::one(); // y^n starting at n=0
-        let y_inv = y.invert();
+        let y_inv = y.inverse().ok_or(R1CSError::FormatError)?;
         let exp_y_inv = util::exp_iter(y_inv).take(padded_n).collect::<Vec<_>>();
 
         let sLsR = s_L1
@@ -602,7 +587,7 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
             r_poly.1[i] = exp_y * self.secrets.a_R[i] + wL[i];
             // r_poly.2 = 0
             // r_poly.3 = y^n * s_R
-            r_poly.3[i] = exp_y * sr;
+            r_poly.3[i] = exp_y * *sr;
 
             exp_y = exp_y * y; // y^i -> y^(i+1)
         }
@@ -615,11 +600,11 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
         let t_5_blinding = Scalar::random(&mut rng);
         let t_6_blinding = Scalar::random(&mut rng);
 
-        let T_1 = self.pc_gens.commit(t_poly.t1, t_1_blinding).compress();
-        let T_3 = self.pc_gens.commit(t_poly.t3, t_3_blinding).compress();
-        let T_4 = self.pc_gens.commit(t_poly.t4, t_4_blinding).compress();
-        let T_5 = self.pc_gens.commit(t_poly.t5, t_5_blinding).compress();
-        let T_6 = self.pc_gens.commit(t_poly.t6, t_6_blinding).compress();
+        let T_1 = self.pc_gens.commit(t_poly.t1, t_1_blinding);
+        let T_3 = self.pc_gens.commit(t_poly.t3, t_3_blinding);
+        let T_4 = self.pc_gens.commit(t_poly.t4, t_4_blinding);
+        let T_5 = self.pc_gens.commit(t_poly.t5, t_5_blinding);
+        let T_6 = self.pc_gens.commit(t_poly.t6, t_6_blinding);
 
         let transcript = self.transcript.borrow_mut();
         transcript.append_point(b"T_1", &T_1);
@@ -636,8 +621,8 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
         let t_2_blinding = wV
             .iter()
             .zip(self.secrets.v_blinding.iter())
-            .map(|(c, v_blinding)| c * v_blinding)
-            .sum();
+            .map(|(c, v_blinding)| *c * *v_blinding)
+            .fold(Scalar::zero(), |sum, term| sum + term);
 
         let t_blinding_poly = util::Poly6 {
             t1: t_1_blinding,
@@ -674,7 +659,7 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
 
         // Get a challenge value to combine statements for the IPP
         let w = transcript.challenge_scalar(b"w");
-        let Q = w * self.pc_gens.B;
+        let Q = self.pc_gens.B * w;
 
         let G_factors = iter::repeat(Scalar::one())
             .take(n1)
@@ -683,7 +668,7 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
         let H_factors = exp_y_inv
             .into_iter()
             .zip(G_factors.iter())
-            .map(|(y, u_or_1)| y * u_or_1)
+            .map(|(y, u_or_1)| y * *u_or_1)
             .collect::<Vec<_>>();
 
         let ipp_proof = InnerProductProof::create(
@@ -695,18 +680,16 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
             gens.H(padded_n).cloned().collect(),
             l_vec,
             r_vec,
-        );
+        )?;
 
-        // We do not yet have a ClearOnDrop wrapper for Vec<Scalar>.
-        // When PR 202 [1] is merged, we can simply wrap s_L and s_R at the point of creation.
-        // [1] https://github.com/dalek-cryptography/curve25519-dalek/pull/202
+        // Overwrite the blinding vectors, see `Secrets::drop`.
         for scalar in s_L1
             .iter_mut()
             .chain(s_L2.iter_mut())
             .chain(s_R1.iter_mut())
             .chain(s_R2.iter_mut())
         {
-            scalar.clear();
+            *scalar = Scalar::zero();
         }
         let proof = R1CSProof {
             A_I1,
@@ -728,3 +711,17 @@ impl<'g, T: BorrowMut<Transcript>> Prover<'g, T> {
         Ok((proof, self.transcript))
     }
 }
+
+/// Computes `sum(s_i * P_i)` with a single `G1::multiexp`.
+fn multiscalar_mul<'a, S, P>(scalars: S, points: P) -> G1
+where
+    S: IntoIterator<Item = &'a Scalar>,
+    P: IntoIterator<Item = &'a G1>,
+{
+    let items = scalars
+        .into_iter()
+        .zip(points)
+        .map(|(s, P)| (*P, *s))
+        .collect::<Vec<_>>();
+    G1::multiexp(&items)
+}
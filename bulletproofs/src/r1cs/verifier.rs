@@ -1,10 +1,10 @@
 #![allow(non_snake_case)]
 
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use bn::{Fr as Scalar, Group, G1};
 use core::borrow::BorrowMut;
 use core::mem;
-use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
-use curve25519_dalek::scalar::Scalar;
-use curve25519_dalek::traits::VartimeMultiscalarMul;
 use merlin::Transcript;
 
 use super::{
@@ -38,7 +38,7 @@ pub struct Verifier<T: BorrowMut<Transcript>> {
     /// `Missing`), so the `num_vars` isn't kept implicitly in the
     /// variable assignments.
     num_vars: usize,
-    V: Vec<CompressedRistretto>,
+    V: Vec<G1>,
 
     /// This list holds closures that will be called in the second phase of the protocol,
     /// when non-randomized variables are committed.
@@ -241,9 +241,9 @@ impl<T: BorrowMut<Transcript>> Verifier<T> {
     ///
     /// # Returns
     ///
-    /// Returns a pair of a Pedersen commitment (as a compressed Ristretto point),
-    /// and a [`Variable`] corresponding to it, which can be used to form constraints.
-    pub fn commit(&mut self, commitment: CompressedRistretto) -> Variable {
+    /// Returns a [`Variable`] corresponding to the commitment,
+    /// which can be used to form constraints.
+    pub fn commit(&mut self, commitment: G1) -> Variable {
         let i = self.V.len();
         self.V.push(commitment);
 
@@ -286,23 +286,23 @@ impl<T: BorrowMut<Transcript>> Verifier<T> {
             for (var, coeff) in &lc.terms {
                 match var {
                     Variable::MultiplierLeft(i) => {
-                        wL[*i] += exp_z * coeff;
+                        wL[*i] = wL[*i] + exp_z * *coeff;
                     }
                     Variable::MultiplierRight(i) => {
-                        wR[*i] += exp_z * coeff;
+                        wR[*i] = wR[*i] + exp_z * *coeff;
                     }
                     Variable::MultiplierOutput(i) => {
-                        wO[*i] += exp_z * coeff;
+                        wO[*i] = wO[*i] + exp_z * *coeff;
                     }
                     Variable::Committed(i) => {
-                        wV[*i] -= exp_z * coeff;
+                        wV[*i] = wV[*i] - exp_z * *coeff;
                     }
                     Variable::One() => {
-                        wc -= exp_z * coeff;
+                        wc = wc - exp_z * *coeff;
                     }
                 }
             }
-            exp_z *= z;
+            exp_z = exp_z * *z;
         }
 
         (wL, wR, wO, wV, wc)
@@ -378,7 +378,7 @@ impl<T: BorrowMut<Transcript>> Verifier<T> {
 
         use crate::inner_product_proof::inner_product;
         use crate::util;
-        use std::iter;
+        use core::iter;
 
         if bp_gens.gens_capacity < padded_n {
             return Err(R1CSError::InvalidGeneratorsLength);
@@ -420,14 +420,14 @@ impl<T: BorrowMut<Transcript>> Verifier<T> {
         let a = proof.ipp_proof.a;
         let b = proof.ipp_proof.b;
 
-        let y_inv = y.invert();
+        let y_inv = y.inverse().ok_or(R1CSError::VerificationError)?;
         let y_inv_vec = util::exp_iter(y_inv)
             .take(padded_n)
             .collect::<Vec<Scalar>>();
         let yneg_wR = wR
             .into_iter()
             .zip(y_inv_vec.iter())
-            .map(|(wRi, exp_y_inv)| wRi * exp_y_inv)
+            .map(|(wRi, exp_y_inv)| wRi * *exp_y_inv)
             .chain(iter::repeat(Scalar::zero()).take(pad))
             .collect::<Vec<Scalar>>();
 
@@ -443,7 +443,7 @@ impl<T: BorrowMut<Transcript>> Verifier<T> {
             .iter()
             .zip(u_for_g)
             .zip(s.iter().take(padded_n))
-            .map(|((yneg_wRi, u_or_1), s_i)| u_or_1 * (x * yneg_wRi - a * s_i));
+            .map(|((yneg_wRi, u_or_1), s_i)| u_or_1 * (x * *yneg_wRi - a * *s_i));
 
         let h_scalars = y_inv_vec
             .iter()
@@ -452,7 +452,7 @@ impl<T: BorrowMut<Transcript>> Verifier<T> {
             .zip(wL.into_iter().chain(iter::repeat(Scalar::zero()).take(pad)))
             .zip(wO.into_iter().chain(iter::repeat(Scalar::zero()).take(pad)))
             .map(|((((y_inv_i, u_or_1), s_i_inv), wLi), wOi)| {
-                u_or_1 * (y_inv_i * (x * wLi + wOi - b * s_i_inv) - Scalar::one())
+                u_or_1 * (*y_inv_i * (x * wLi + wOi - b * *s_i_inv) - Scalar::one())
             });
 
         // Create a `TranscriptRng` from the transcript. The verifier
@@ -474,43 +474,45 @@ impl<T: BorrowMut<Transcript>> Verifier<T> {
         let T_scalars = [r * x, rxx * x, rxx * xx, rxx * xxx, rxx * xx * xx];
         let T_points = [proof.T_1, proof.T_3, proof.T_4, proof.T_5, proof.T_6];
 
-        let mega_check = RistrettoPoint::optional_multiscalar_mul(
-            iter::once(x) // A_I1
-                .chain(iter::once(xx)) // A_O1
-                .chain(iter::once(xxx)) // S1
-                .chain(iter::once(u * x)) // A_I2
-                .chain(iter::once(u * xx)) // A_O2
-                .chain(iter::once(u * xxx)) // S2
-                .chain(wV.iter().map(|wVi| wVi * rxx)) // V
-                .chain(T_scalars.iter().cloned()) // T_points
-                .chain(iter::once(
-                    w * (proof.t_x - a * b) + r * (xx * (wc + delta) - proof.t_x),
-                )) // B
-                .chain(iter::once(-proof.e_blinding - r * proof.t_x_blinding)) // B_blinding
-                .chain(g_scalars) // G
-                .chain(h_scalars) // H
-                .chain(u_sq.iter().cloned()) // ipp_proof.L_vec
-                .chain(u_inv_sq.iter().cloned()), // ipp_proof.R_vec
-            iter::once(proof.A_I1.decompress())
-                .chain(iter::once(proof.A_O1.decompress()))
-                .chain(iter::once(proof.S1.decompress()))
-                .chain(iter::once(proof.A_I2.decompress()))
-                .chain(iter::once(proof.A_O2.decompress()))
-                .chain(iter::once(proof.S2.decompress()))
-                .chain(self.V.iter().map(|V_i| V_i.decompress()))
-                .chain(T_points.iter().map(|T_i| T_i.decompress()))
-                .chain(iter::once(Some(pc_gens.B)))
-                .chain(iter::once(Some(pc_gens.B_blinding)))
-                .chain(gens.G(padded_n).map(|&G_i| Some(G_i)))
-                .chain(gens.H(padded_n).map(|&H_i| Some(H_i)))
-                .chain(proof.ipp_proof.L_vec.iter().map(|L_i| L_i.decompress()))
-                .chain(proof.ipp_proof.R_vec.iter().map(|R_i| R_i.decompress())),
-        )
-        .ok_or_else(|| R1CSError::VerificationError)?;
-
-        use curve25519_dalek::traits::IsIdentity;
-
-        if !mega_check.is_identity() {
+        let scalars = iter::once(x) // A_I1
+            .chain(iter::once(xx)) // A_O1
+            .chain(iter::once(xxx)) // S1
+            .chain(iter::once(u * x)) // A_I2
+            .chain(iter::once(u * xx)) // A_O2
+            .chain(iter::once(u * xxx)) // S2
+            .chain(wV.iter().map(|wVi| *wVi * rxx)) // V
+            .chain(T_scalars.iter().cloned()) // T_points
+            .chain(iter::once(
+                w * (proof.t_x - a * b) + r * (xx * (wc + delta) - proof.t_x),
+            )) // B
+            .chain(iter::once(-proof.e_blinding - r * proof.t_x_blinding)) // B_blinding
+            .chain(g_scalars) // G
+            .chain(h_scalars) // H
+            .chain(u_sq.iter().cloned()) // ipp_proof.L_vec
+            .chain(u_inv_sq.iter().cloned()); // ipp_proof.R_vec
+
+        let points = iter::once(proof.A_I1)
+            .chain(iter::once(proof.A_O1))
+            .chain(iter::once(proof.S1))
+            .chain(iter::once(proof.A_I2))
+            .chain(iter::once(proof.A_O2))
+            .chain(iter::once(proof.S2))
+            .chain(self.V.iter().copied())
+            .chain(T_points.iter().copied())
+            .chain(iter::once(pc_gens.B))
+            .chain(iter::once(pc_gens.B_blinding))
+            .chain(gens.G(padded_n).copied())
+            .chain(gens.H(padded_n).copied())
+            .chain(proof.ipp_proof.L_vec.iter().copied())
+            .chain(proof.ipp_proof.R_vec.iter().copied());
+
+        let mega_check = scalars
+            .zip(points)
+            .map(|(s, P)| (P, s))
+            .collect::<Vec<_>>();
+        let mega_check: G1 = G1::multiexp(&mega_check);
+
+        if !mega_check.is_zero() {
             return Err(R1CSError::VerificationError);
         }
 
@@ -10,7 +10,7 @@ extern crate alloc;
 
 use alloc::vec::Vec;
 
-use bn::{G1, Fr as Scalar};
+use bn::{G1, Fr as Scalar, Group};
 use merlin::Transcript;
 use borsh::maybestd::format;
 
@@ -22,7 +22,7 @@ use crate::{inner_product_proof, ProofError};
 
 use rand::{CryptoRng, RngCore};
 
-use crate::util::{self, alt_bn128_g1_sum};
+use crate::util::{self, alt_bn128_g1_sum, u64_to_scalar};
 
 #[cfg(feature = "std")]
 use rand::thread_rng;
@@ -34,6 +34,12 @@ pub struct Dealer {}
 
 impl Dealer {
     /// Creates a new dealer coordinating `m` parties proving `n`-bit ranges.
+    ///
+    /// If `m` is not a power of two, the dealer pads the aggregation with parties of its own,
+    /// which prove that zero is in range for a commitment to zero with a zero blinding, i.e.
+    /// the identity. The verifier pads the value commitments the same way, so the padding is
+    /// transparent to both the parties and the verifier. The generators must have capacity for
+    /// the padded aggregation size.
     pub fn new<'a, 'b>(
         bp_gens: &'b BulletproofGens,
         pc_gens: &'b PedersenGens,
@@ -44,13 +50,13 @@ impl Dealer {
         if !(n == 8 || n == 16 || n == 32 || n == 64) {
             return Err(MPCError::InvalidBitsize);
         }
-        if !m.is_power_of_two() {
+        if m == 0 {
             return Err(MPCError::InvalidAggregation);
         }
         if bp_gens.gens_capacity < n {
             return Err(MPCError::InvalidGeneratorsLength);
         }
-        if bp_gens.party_capacity < m {
+        if bp_gens.party_capacity < m.next_power_of_two() {
             return Err(MPCError::InvalidGeneratorsLength);
         }
 
@@ -68,7 +74,7 @@ impl Dealer {
         // state.
         let initial_transcript = transcript.clone();
 
-        transcript.rangeproof_domain_sep(n as u64, m as u64);
+        transcript.rangeproof_domain_sep(n as u64, m.next_power_of_two() as u64);
 
         Ok(DealerAwaitingBitCommitments {
             bp_gens,
@@ -97,12 +103,16 @@ impl<'a, 'b> DealerAwaitingBitCommitments<'a, 'b> {
     /// Receive each party's [`BitCommitment`]s and compute the [`BitChallenge`].
     pub fn receive_bit_commitments(
         self,
-        bit_commitments: Vec<BitCommitment>,
+        mut bit_commitments: Vec<BitCommitment>,
     ) -> Result<(DealerAwaitingPolyCommitments<'a, 'b>, BitChallenge), MPCError> {
         if self.m != bit_commitments.len() {
             return Err(MPCError::WrongNumBitCommitments);
         }
 
+        bit_commitments.extend(
+            (self.m..self.m.next_power_of_two()).map(|j| padding::bit_commitment(self.bp_gens, self.n, j)),
+        );
+
         // Commit each V_j individually
         for vc in bit_commitments.iter() {
             self.transcript.append_point(b"V", &vc.V_j);
@@ -161,12 +171,14 @@ impl<'a, 'b> DealerAwaitingPolyCommitments<'a, 'b> {
     /// [`PolyChallenge`].
     pub fn receive_poly_commitments(
         self,
-        poly_commitments: Vec<PolyCommitment>,
+        mut poly_commitments: Vec<PolyCommitment>,
     ) -> Result<(DealerAwaitingProofShares<'a, 'b>, PolyChallenge), MPCError> {
         if self.m != poly_commitments.len() {
             return Err(MPCError::WrongNumPolyCommitments);
         }
 
+        poly_commitments.resize(self.m.next_power_of_two(), padding::poly_commitment());
+
         // Commit sums of T_1_j's and T_2_j's
         let T_1: G1 = alt_bn128_g1_sum(&poly_commitments.iter().map(|pc| pc.T_1_j).collect::<Vec<_>>())
             .map_err(|e| MPCError::InvalidG1Aggregate {msg: format!("{:?}", e)})?;
@@ -246,6 +258,12 @@ impl<'a, 'b> DealerAwaitingProofShares<'a, 'b> {
             return Err(MPCError::MalformedProofShares { bad_shares }.into());
         }
 
+        let padded_m = self.m.next_power_of_two();
+        let padding_shares: Vec<ProofShare> = (self.m..padded_m)
+            .map(|j| padding::proof_share(self.n, j, &self.bit_challenge))
+            .collect();
+        let proof_shares: Vec<&ProofShare> = proof_shares.iter().chain(padding_shares.iter()).collect();
+
         // todo: replace fold() with sum() when supported in blstrs
         let t_x: Scalar = proof_shares
             .iter()
@@ -269,11 +287,11 @@ impl<'a, 'b> DealerAwaitingProofShares<'a, 'b> {
         let w = self.transcript.challenge_scalar(b"w");
         let Q = self.pc_gens.B * w;
 
-        let G_factors: Vec<Scalar> = iter::repeat(Scalar::one()).take(self.n * self.m).collect();
+        let G_factors: Vec<Scalar> = iter::repeat(Scalar::one()).take(self.n * padded_m).collect();
         let H_factors: Vec<Scalar> = util::exp_iter(
             Option::from(self.bit_challenge.y.inverse()).ok_or(ProofError::FormatError)?,
         )
-        .take(self.n * self.m)
+        .take(self.n * padded_m)
         .collect();
 
         let l_vec: Vec<Scalar> = proof_shares
@@ -290,8 +308,8 @@ impl<'a, 'b> DealerAwaitingProofShares<'a, 'b> {
             &Q,
             &G_factors,
             &H_factors,
-            self.bp_gens.G(self.n, self.m).cloned().collect(),
-            self.bp_gens.H(self.n, self.m).cloned().collect(),
+            self.bp_gens.G(self.n, padded_m).cloned().collect(),
+            self.bp_gens.H(self.n, padded_m).cloned().collect(),
             l_vec,
             r_vec,
         )?;
@@ -353,7 +371,8 @@ impl<'a, 'b> DealerAwaitingProofShares<'a, 'b> {
         {
             Ok(proof)
         } else {
-            // Proof verification failed. Now audit the parties:
+            // Proof verification failed. Now audit the parties, leaving
+            // out the padding parties of the dealer:
             let mut bad_shares = Vec::new();
             for j in 0..self.m {
                 match proof_shares[j].audit_share(
@@ -394,3 +413,62 @@ impl<'a, 'b> DealerAwaitingProofShares<'a, 'b> {
         self.assemble_shares(proof_shares)
     }
 }
+
+/// The messages of the parties a dealer pads the aggregation with. A padding party proves that
+/// zero is in range for a commitment to zero with a zero blinding. It has nothing to hide, so
+/// all of its blindings are zero as well and the dealer computes its messages without a rng.
+mod padding {
+    use super::*;
+
+    /// The bit commitment of the padding party at position `j`, with
+    /// `A = <a_L, G> + <a_R, H> = -sum(H)` since every bit of zero is unset.
+    pub(super) fn bit_commitment(bp_gens: &BulletproofGens, n: usize, j: usize) -> BitCommitment {
+        let A = bp_gens
+            .share(j)
+            .H(n)
+            .fold(G1::zero(), |sum, H_i| sum - *H_i);
+
+        BitCommitment {
+            V_j: G1::zero(),
+            A_j: A,
+            S_j: G1::zero(),
+        }
+    }
+
+    /// The polynomial commitment of a padding party. With `s_L = s_R = 0` both
+    /// \\(t_1\\) and \\(t_2\\) are zero.
+    pub(super) fn poly_commitment() -> PolyCommitment {
+        PolyCommitment {
+            T_1_j: G1::zero(),
+            T_2_j: G1::zero(),
+        }
+    }
+
+    /// The proof share of the padding party at position `j`, as `Party` computes it with
+    /// `v = 0` and zero blindings.
+    pub(super) fn proof_share(
+        n: usize,
+        j: usize,
+        bit_challenge: &BitChallenge,
+    ) -> ProofShare {
+        let (y, z) = (bit_challenge.y, bit_challenge.z);
+        let offset_y = util::scalar_exp_vartime(&y, (j * n) as u64);
+        let offset_zz = z * z * util::scalar_exp_vartime(&z, j as u64);
+
+        // l = a_L - z and r = y^n * (a_R + z) + z^(2+j) * 2^n with a_L = 0 and a_R = -1
+        let l_vec: Vec<Scalar> = iter::repeat_n(-z, n).collect();
+        let r_vec: Vec<Scalar> = util::exp_iter(y)
+            .zip(util::exp_iter(u64_to_scalar(2u64)))
+            .take(n)
+            .map(|(exp_y, exp_2)| offset_y * exp_y * (z - Scalar::one()) + offset_zz * exp_2)
+            .collect();
+
+        ProofShare {
+            t_x: inner_product_proof::inner_product(&l_vec, &r_vec),
+            t_x_blinding: Scalar::zero(),
+            e_blinding: Scalar::zero(),
+            l_vec,
+            r_vec,
+        }
+    }
+}
@@ -8,6 +8,7 @@ extern crate alloc;
 #[cfg(feature = "std")]
 use rand::thread_rng;
 
+use alloc::vec;
 use alloc::vec::Vec;
 //use group::ff::Field;
 //use group::{Curve, Group};
@@ -38,6 +39,8 @@ pub const G_COMP_BYTES_LEN: usize = 33;
 pub mod dealer;
 pub mod messages;
 pub mod party;
+#[cfg(feature = "mpc-runner")]
+pub mod runner;
 
 /// The `RangeProof` struct represents a proof that one or more values
 /// are in a range.
@@ -50,11 +53,16 @@ pub mod party;
 /// the values, are not included in the proof, and must be known to
 /// the verifier.
 ///
-/// This implementation requires that both the bitsize `n` and the
-/// aggregation size `m` be powers of two, so that `n = 8, 16, 32, 64`
-/// and `m = 1, 2, 4, 8, 16, ...`.  Note that the aggregation size is
-/// not given as an explicit parameter, but is determined by the
-/// number of values or commitments passed to the prover or verifier.
+/// This implementation requires that the bitsize `n` be a power of two,
+/// so that `n = 8, 16, 32, 64`.  The aggregation size `m` can be any
+/// positive number: the prover pads the aggregation to the next power
+/// of two with commitments to zero, which the verifier adds as well.
+/// Note that the aggregation size is not given as an explicit
+/// parameter, but is determined by the number of values or commitments
+/// passed to the prover or verifier.
+///
+/// Values in an arbitrary interval `[min, max]` are proven with
+/// [`RangeProof::prove_in_range`] and [`RangeProof::verify_in_range`].
 ///
 /// # Note
 ///
@@ -62,7 +70,7 @@ pub mod party;
 /// protocol locally.  That API is exposed in the [`aggregation`](::range_proof_mpc)
 /// module and can be used to perform online aggregation between
 /// parties without revealing secret values to each other.
-#[derive(Eq, PartialEq, BorshDeserialize, BorshSerialize, Clone, Debug)]
+#[derive(Eq, PartialEq, Clone, Debug)]
 pub struct RangeProof {
     /// Commitment to the bits of the value
     A: G1,
@@ -360,12 +368,116 @@ impl RangeProof {
         n: usize,
         rng: &mut T,
     ) -> Result<(), ProofError> {
-        //let value_commitments: Vec<G1> = value_commitments
-            //.iter()
-            //.map(|c| G1Projective::from(c))
-            //.collect();
+        RangeProof::batch_verify_with_rng(
+            bp_gens,
+            pc_gens,
+            &mut [(self, transcript, value_commitments, n)],
+            rng,
+        )
+    }
+
+    /// Verifies any number of independent rangeproofs with a single multiscalar multiplication.
+    ///
+    /// Each item is a proof with its own transcript, value commitments and bitsize, as they
+    /// would be passed to [`RangeProof::verify_multiple_with_rng`], so the proofs can have
+    /// different bitsizes and aggregation sizes. The verification equation of every proof is
+    /// multiplied by a random weight, and the scalars of the generators shared by the proofs
+    /// are summed up, so the batch costs about as much as the largest of its proofs plus the
+    /// points of the others.
+    ///
+    /// Returns an error if any of the proofs is malformed or does not verify, without
+    /// telling which one.
+    pub fn batch_verify_with_rng<T: RngCore + CryptoRng>(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        proofs: &mut [(&RangeProof, &mut Transcript, &[G1], usize)],
+        rng: &mut T,
+    ) -> Result<(), ProofError> {
+        let max_n = proofs.iter().map(|(_, _, _, n)| *n).max().unwrap_or(0);
+        let max_m = proofs
+            .iter()
+            .map(|(_, _, V, _)| V.len().next_power_of_two())
+            .max()
+            .unwrap_or(0);
+
+        let mut B = Scalar::zero();
+        let mut B_blinding = Scalar::zero();
+        // The scalars of the G and H generators of party j are at j * max_n..(j + 1) * max_n.
+        let mut g = vec![Scalar::zero(); max_n * max_m];
+        let mut h = vec![Scalar::zero(); max_n * max_m];
+        let mut terms = Vec::new();
+
+        for (i, (proof, transcript, value_commitments, n)) in proofs.iter_mut().enumerate() {
+            // The first equation does not need to be randomized.
+            let weight = if i == 0 {
+                Scalar::one()
+            } else {
+                Scalar::random(rng)
+            };
+            let equation =
+                proof.verification_equation(bp_gens, transcript, value_commitments, *n, rng)?;
+
+            B = B + weight * equation.B;
+            B_blinding = B_blinding + weight * equation.B_blinding;
+            for (k, (g_k, h_k)) in equation.g.into_iter().zip(equation.h).enumerate() {
+                let idx = (k / *n) * max_n + k % *n;
+                g[idx] = g[idx] + weight * g_k;
+                h[idx] = h[idx] + weight * h_k;
+            }
+            terms.extend(equation.terms.into_iter().map(|(P, s)| (P, weight * s)));
+        }
+
+        let gens = (0..max_m).flat_map(|j| {
+            let share = bp_gens.share(j);
+            share.G(max_n).zip(share.H(max_n))
+        });
+
+        let mega_check = terms
+            .into_iter()
+            .chain(iter::once((pc_gens.B, B)))
+            .chain(iter::once((pc_gens.B_blinding, B_blinding)))
+            .chain(
+                gens.zip(g.into_iter().zip(h))
+                    .flat_map(|((G, H), (g, h))| iter::once((*G, g)).chain(iter::once((*H, h)))),
+            )
+            .collect::<Vec<_>>();
+        let mega_check: G1 = G1::multiexp(&mega_check);
+
+        if mega_check.is_zero() {
+            Ok(())
+        } else {
+            Err(ProofError::VerificationError)
+        }
+    }
+
+    /// Verifies any number of independent rangeproofs with a single multiscalar multiplication.
+    /// This is a convenience wrapper around [`RangeProof::batch_verify_with_rng`],
+    /// passing in a threadsafe RNG.
+    #[cfg(feature = "std")]
+    pub fn batch_verify(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        proofs: &mut [(&RangeProof, &mut Transcript, &[G1], usize)],
+    ) -> Result<(), ProofError> {
+        RangeProof::batch_verify_with_rng(bp_gens, pc_gens, proofs, &mut thread_rng())
+    }
 
-        let m = value_commitments.len();
+    /// Replays the protocol of the proof on the transcript to recompute all challenges,
+    /// and returns the terms of its verification equation.
+    fn verification_equation<T: RngCore + CryptoRng>(
+        &self,
+        bp_gens: &BulletproofGens,
+        transcript: &mut Transcript,
+        value_commitments: &[G1],
+        n: usize,
+        rng: &mut T,
+    ) -> Result<VerificationEquation, ProofError> {
+        if value_commitments.is_empty() {
+            return Err(ProofError::InvalidAggregation);
+        }
+        // The prover pads the aggregation to a power of two with commitments to zero
+        // with a zero blinding, which are the identity and add nothing to the equation.
+        let m = value_commitments.len().next_power_of_two();
 
         // First, replay the "interactive" protocol using the proof
         // data to recompute all challenges.
@@ -381,10 +493,11 @@ impl RangeProof {
 
         transcript.rangeproof_domain_sep(n as u64, m as u64);
 
-        for V in value_commitments.iter() {
+        let padding = iter::repeat(G1::zero()).take(m - value_commitments.len());
+        for V in value_commitments.iter().copied().chain(padding) {
             // Allow the commitments to be zero (0 value, 0 blinding)
             // See https://github.com/dalek-cryptography/bulletproofs/pull/248#discussion_r255167177
-            transcript.append_point(b"V", V);
+            transcript.append_point(b"V", &V);
         }
 
         transcript.validate_and_append_point(b"A", &self.A.into())?;
@@ -433,42 +546,22 @@ impl RangeProof {
         let value_commitment_scalars = util::exp_iter(z).take(m).map(|z_exp| c * zz * z_exp);
         let basepoint_scalar = w * (self.t_x - a * b) + c * (delta(n, m, &y, &z) - self.t_x);
 
-        // TODO: replace this dot product with blst_p1s_mult_pippenger once it's supported in blstrs
-
-        let scalars = iter::once(Scalar::one())
-            .chain(iter::once(x))
-            .chain(iter::once(c * x))
-            .chain(iter::once(c * x * x))
-            .chain(x_sq.iter().cloned())
-            .chain(x_inv_sq.iter().cloned())
-            .chain(iter::once(-self.e_blinding - c * self.t_x_blinding))
-            .chain(iter::once(basepoint_scalar))
-            .chain(g)
-            .chain(h)
-            .chain(value_commitment_scalars);
-
-        let points = iter::once(self.A.clone())
-            .chain(iter::once(self.S.clone()))
-            .chain(iter::once(self.T_1.clone()))
-            .chain(iter::once(self.T_2.clone()))
-            .chain(self.ipp_proof.L_vec.iter().copied())
-            .chain(self.ipp_proof.R_vec.iter().copied())
-            .chain(iter::once(pc_gens.B_blinding))
-            .chain(iter::once(pc_gens.B))
-            .chain(bp_gens.G(n, m).copied())
-            .chain(bp_gens.H(n, m).copied())
-            .chain(value_commitments.iter().copied());
-
-        let mega_check = scalars.zip(points)
-            .map(|(s, P)| (P, s))
-            .collect::<Vec<_>>();
-        let mega_check: G1 = G1::multiexp(&mega_check);
+        let terms = iter::once((self.A, Scalar::one()))
+            .chain(iter::once((self.S, x)))
+            .chain(iter::once((self.T_1, c * x)))
+            .chain(iter::once((self.T_2, c * x * x)))
+            .chain(self.ipp_proof.L_vec.iter().copied().zip(x_sq))
+            .chain(self.ipp_proof.R_vec.iter().copied().zip(x_inv_sq))
+            .chain(value_commitments.iter().copied().zip(value_commitment_scalars))
+            .collect();
 
-        if mega_check.is_zero() {
-            Ok(())
-        } else {
-            Err(ProofError::VerificationError)
-        }
+        Ok(VerificationEquation {
+            terms,
+            B: basepoint_scalar,
+            B_blinding: -self.e_blinding - c * self.t_x_blinding,
+            g: g.collect(),
+            h: h.collect(),
+        })
     }
 
     /// Verifies an aggregated rangeproof for the given value commitments.
@@ -493,122 +586,307 @@ impl RangeProof {
         )
     }
 
-    ///// Serializes the proof into a byte array of \\(2 \lg n + 9\\)
-    ///// 32-byte elements, where \\(n\\) is the number of secret bits.
-    /////
-    ///// # Layout
-    /////
-    ///// The layout of the range proof encoding is:
-    /////
-    ///// * four compressed Ristretto points \\(A,S,T_1,T_2\\),
-    ///// * three scalars \\(t_x, \tilde{t}_x, \tilde{e}\\),
-    ///// * \\(n\\) pairs of compressed Ristretto points \\(L_0,R_0\dots,L_{n-1},R_{n-1}\\),
-    ///// * two scalars \\(a, b\\).
-    //pub fn to_bytes(&self) -> Vec<u8> {
-        //// 7 elements: points A, S, T1, T2, scalars tx, tx_bl, e_bl.
-        //let mut buf = Vec::with_capacity(7 * 32 + self.ipp_proof.serialized_size());
-        //buf.extend_from_slice(&self.A.to_compressed());
-        //buf.extend_from_slice(&self.S.to_compressed());
-        //buf.extend_from_slice(&self.T_1.to_compressed());
-        //buf.extend_from_slice(&self.T_2.to_compressed());
-        //buf.extend_from_slice(&self.t_x.to_bytes_le());
-        //buf.extend_from_slice(&self.t_x_blinding.to_bytes_le());
-        //buf.extend_from_slice(&self.e_blinding.to_bytes_le());
-        //buf.extend(self.ipp_proof.to_bytes_iter());
-        //buf
-    //}
-
-    ///// Deserializes the proof from a byte slice.
-    /////
-    ///// Returns an error if the byte slice cannot be parsed into a `RangeProof`.
-    //pub fn from_bytes(slice: &[u8]) -> Result<RangeProof, ProofError> {
-        //if slice.len() < 4 * 48 {
-            //return Err(ProofError::FormatError);
-        //}
-        //if (slice.len() - 4 * 48) % 32 != 0 {
-            //return Err(ProofError::FormatError);
-        //}
-        //if (slice.len() - 4 * 48) < 3 * 32 {
-            //return Err(ProofError::FormatError);
-        //}
-
-        //use crate::util::{read32, read48};
-
-        //let A = Option::from(G1::deserialize(&read48(&slice[0 * 48..])))
-            //.ok_or(ProofError::FormatError)?;
-        //let S = Option::from(G1Affine::from_compressed(&read48(&slice[1 * 48..])))
-            //.ok_or(ProofError::FormatError)?;
-        //let T_1 = Option::from(G1Affine::from_compressed(&read48(&slice[2 * 48..])))
-            //.ok_or(ProofError::FormatError)?;
-        //let T_2 = Option::from(G1Affine::from_compressed(&read48(&slice[3 * 48..])))
-            //.ok_or(ProofError::FormatError)?;
-
-        //let t_x = Option::from(Scalar::from_bytes_le(&read32(&slice[4 * 48 + 0 * 32..])))
-            //.ok_or(ProofError::FormatError)?;
-
-        //let t_x_blinding =
-            //Option::from(Scalar::from_bytes_le(&read32(&slice[(4 * 48 + 1 * 32)..])))
-                //.ok_or(ProofError::FormatError)?;
-
-        //let e_blinding = Option::from(Scalar::from_bytes_le(&read32(&slice[4 * 48 + 2 * 32..])))
-            //.ok_or(ProofError::FormatError)?;
-
-        //let ipp_proof = InnerProductProof::from_bytes(&slice[4 * 48 + 3 * 32..])?;
-
-        //Ok(RangeProof {
-            //A,
-            //S,
-            //T_1,
-            //T_2,
-            //t_x,
-            //t_x_blinding,
-            //e_blinding,
-            //ipp_proof,
-        //})
-    //}
+    /// Create a rangeproof that the value `v` lies in the interval `[min, max]`, for a
+    /// commitment to `v` with the blinding scalar `v_blinding`.
+    ///
+    /// The proof is an aggregated rangeproof for the two shifted values `v - min` and
+    /// `max - v`, which are committed to by \\(V - \mathrm{min} \cdot B\\) and
+    /// \\(\mathrm{max} \cdot B - V\\), with the smallest bitsize `n` for which
+    /// `max - min < 2^n`. Both values being in `[0, 2^n)` implies that `v` is in `[min, max]`.
+    /// The generators must have capacity for the bitsize and an aggregation size of 2.
+    ///
+    /// Returns [`ProofError::InvalidRange`] if the interval is empty or does not contain `v`.
+    ///
+    /// # Example
+    /// ```
+    /// extern crate rand;
+    /// use rand::thread_rng;
+    ///
+    /// use bn::Fr as Scalar;
+    /// use merlin::Transcript;
+    /// use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+    ///
+    /// # fn main() {
+    /// let pc_gens = PedersenGens::default();
+    /// let bp_gens = BulletproofGens::new(64, 2);
+    ///
+    /// // A transfer of at most 10000 units, with a minimum of 5
+    /// let (min, max) = (5, 10000);
+    /// let blinding = Scalar::random(&mut thread_rng());
+    ///
+    /// let mut prover_transcript = Transcript::new(b"doctest example");
+    /// let (proof, committed_value) = RangeProof::prove_in_range(
+    ///     &bp_gens,
+    ///     &pc_gens,
+    ///     &mut prover_transcript,
+    ///     4242,
+    ///     &blinding,
+    ///     min,
+    ///     max,
+    /// ).expect("A real program could handle errors");
+    ///
+    /// let mut verifier_transcript = Transcript::new(b"doctest example");
+    /// assert!(
+    ///     proof
+    ///         .verify_in_range(&bp_gens, &pc_gens, &mut verifier_transcript, &committed_value, min, max)
+    ///         .is_ok()
+    /// );
+    /// # }
+    /// ```
+    pub fn prove_in_range_with_rng<T: RngCore + CryptoRng>(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        v: u64,
+        v_blinding: &Scalar,
+        min: u64,
+        max: u64,
+        rng: &mut T,
+    ) -> Result<(RangeProof, G1), ProofError> {
+        let n = interval_bitsize(min, max)?;
+        if v < min || v > max {
+            return Err(ProofError::InvalidRange);
+        }
+
+        transcript.interval_domain_sep(min, max);
+
+        let (proof, Vs) = RangeProof::prove_multiple_with_rng(
+            bp_gens,
+            pc_gens,
+            transcript,
+            &[v - min, max - v],
+            &[*v_blinding, -*v_blinding],
+            n,
+            rng,
+        )?;
+        Ok((proof, Vs[0] + pc_gens.B * u64_to_scalar(min)))
+    }
+
+    /// Create a rangeproof that the value `v` lies in the interval `[min, max]`.
+    /// This is a convenience wrapper around [`RangeProof::prove_in_range_with_rng`],
+    /// passing in a threadsafe RNG.
+    #[cfg(feature = "std")]
+    pub fn prove_in_range(
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        v: u64,
+        v_blinding: &Scalar,
+        min: u64,
+        max: u64,
+    ) -> Result<(RangeProof, G1), ProofError> {
+        RangeProof::prove_in_range_with_rng(
+            bp_gens,
+            pc_gens,
+            transcript,
+            v,
+            v_blinding,
+            min,
+            max,
+            &mut thread_rng(),
+        )
+    }
+
+    /// Verifies a rangeproof that the value committed to by \\(V\\) lies in the
+    /// interval `[min, max]`, as created by [`RangeProof::prove_in_range_with_rng`].
+    pub fn verify_in_range_with_rng<T: RngCore + CryptoRng>(
+        &self,
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        V: &G1,
+        min: u64,
+        max: u64,
+        rng: &mut T,
+    ) -> Result<(), ProofError> {
+        let n = interval_bitsize(min, max)?;
+
+        transcript.interval_domain_sep(min, max);
+
+        let value_commitments = [
+            *V - pc_gens.B * u64_to_scalar(min),
+            pc_gens.B * u64_to_scalar(max) - *V,
+        ];
+        self.verify_multiple_with_rng(bp_gens, pc_gens, transcript, &value_commitments, n, rng)
+    }
+
+    /// Verifies a rangeproof that the value committed to by \\(V\\) lies in the
+    /// interval `[min, max]`.
+    /// This is a convenience wrapper around [`RangeProof::verify_in_range_with_rng`],
+    /// passing in a threadsafe RNG.
+    #[cfg(feature = "std")]
+    pub fn verify_in_range(
+        &self,
+        bp_gens: &BulletproofGens,
+        pc_gens: &PedersenGens,
+        transcript: &mut Transcript,
+        V: &G1,
+        min: u64,
+        max: u64,
+    ) -> Result<(), ProofError> {
+        self.verify_in_range_with_rng(bp_gens, pc_gens, transcript, V, min, max, &mut thread_rng())
+    }
+
+    /// Serializes the proof into a byte array of 4 33-byte points,
+    /// followed by \\(2 \lg n\\) 33-byte points and 5 32-byte scalars,
+    /// where \\(n\\) is the number of secret bits.
+    ///
+    /// # Layout
+    ///
+    /// The layout of the range proof encoding is:
+    ///
+    /// * four compressed G1 points \\(A,S,T_1,T_2\\),
+    /// * three scalars \\(t_x, \tilde{t}_x, \tilde{e}\\),
+    /// * \\(n\\) pairs of compressed G1 points \\(L_0,R_0\dots,L_{n-1},R_{n-1}\\),
+    /// * two scalars \\(a, b\\).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.serialized_size());
+        buf.extend_from_slice(&self.A.to_compressed());
+        buf.extend_from_slice(&self.S.to_compressed());
+        buf.extend_from_slice(&self.T_1.to_compressed());
+        buf.extend_from_slice(&self.T_2.to_compressed());
+        buf.extend_from_slice(&util::scalar_to_bytes(&self.t_x));
+        buf.extend_from_slice(&util::scalar_to_bytes(&self.t_x_blinding));
+        buf.extend_from_slice(&util::scalar_to_bytes(&self.e_blinding));
+        buf.extend(self.ipp_proof.to_bytes_iter());
+        buf
+    }
+
+    /// Returns the size in bytes required to serialize the `RangeProof`.
+    pub fn serialized_size(&self) -> usize {
+        // 7 elements: points A, S, T1, T2, scalars tx, tx_bl, e_bl.
+        4 * G_COMP_BYTES_LEN + 3 * FIELD_BYTES_LEN + self.ipp_proof.serialized_size()
+    }
+
+    /// Deserializes the proof from a byte slice.
+    ///
+    /// Returns an error if the byte slice cannot be parsed into a `RangeProof`.
+    pub fn from_bytes(slice: &[u8]) -> Result<RangeProof, ProofError> {
+        if slice.len() < 4 * G_COMP_BYTES_LEN + 3 * FIELD_BYTES_LEN {
+            return Err(ProofError::FormatError);
+        }
+
+        let point = |i: usize| {
+            util::read_point(&slice[i * G_COMP_BYTES_LEN..]).ok_or(ProofError::FormatError)
+        };
+        let A = point(0)?;
+        let S = point(1)?;
+        let T_1 = point(2)?;
+        let T_2 = point(3)?;
+
+        let scalar = |i: usize| {
+            let pos = 4 * G_COMP_BYTES_LEN + i * FIELD_BYTES_LEN;
+            util::scalar_from_bytes(&util::read32(&slice[pos..])).ok_or(ProofError::FormatError)
+        };
+        let t_x = scalar(0)?;
+        let t_x_blinding = scalar(1)?;
+        let e_blinding = scalar(2)?;
+
+        let ipp_proof =
+            InnerProductProof::from_bytes(&slice[4 * G_COMP_BYTES_LEN + 3 * FIELD_BYTES_LEN..])?;
+
+        Ok(RangeProof {
+            A,
+            S,
+            T_1,
+            T_2,
+            t_x,
+            t_x_blinding,
+            e_blinding,
+            ipp_proof,
+        })
+    }
 }
 
-//impl Serialize for RangeProof {
-    //fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    //where
-        //S: Serializer,
-    //{
-        //serializer.serialize_bytes(&self.to_bytes()[..])
-    //}
-//}
-
-//impl<'de> Deserialize<'de> for RangeProof {
-    //fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    //where
-        //D: Deserializer<'de>,
-    //{
-        //struct RangeProofVisitor;
-
-        //impl<'de> Visitor<'de> for RangeProofVisitor {
-            //type Value = RangeProof;
-
-            //fn expecting(&self, formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                //formatter.write_str("a valid RangeProof")
-            //}
-
-            //fn visit_bytes<E>(self, v: &[u8]) -> Result<RangeProof, E>
-            //where
-                //E: serde::de::Error,
-            //{
-                //// Using Error::custom requires T: Display, which our error
-                //// type only implements when it implements std::error::Error.
-                //#[cfg(feature = "std")]
-                //return RangeProof::from_bytes(v).map_err(serde::de::Error::custom);
-                //// In no-std contexts, drop the error message.
-                //#[cfg(not(feature = "std"))]
-                //return RangeProof::from_bytes(v)
-                    //.map_err(|_| serde::de::Error::custom("deserialization error"));
-            //}
-        //}
-
-        //deserializer.deserialize_bytes(RangeProofVisitor)
-    //}
-//}
+impl Serialize for RangeProof {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.to_bytes()[..])
+    }
+}
+
+impl<'de> Deserialize<'de> for RangeProof {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RangeProofVisitor;
+
+        impl<'de> Visitor<'de> for RangeProofVisitor {
+            type Value = RangeProof;
+
+            fn expecting(&self, formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                formatter.write_str("a valid RangeProof")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<RangeProof, E>
+            where
+                E: serde::de::Error,
+            {
+                // Using Error::custom requires T: Display, which our error
+                // type only implements when it implements std::error::Error.
+                #[cfg(feature = "std")]
+                return RangeProof::from_bytes(v).map_err(serde::de::Error::custom);
+                // In no-std contexts, drop the error message.
+                #[cfg(not(feature = "std"))]
+                return RangeProof::from_bytes(v)
+                    .map_err(|_| serde::de::Error::custom("deserialization error"));
+            }
+        }
+
+        deserializer.deserialize_bytes(RangeProofVisitor)
+    }
+}
+
+/// Borsh writes the compact encoding of [`RangeProof::to_bytes`], prefixed with its length,
+/// so that a proof embedded in a larger Borsh value takes the same space as on its own.
+impl BorshSerialize for RangeProof {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> core::result::Result<(), borsh::maybestd::io::Error> {
+        BorshSerialize::serialize(&self.to_bytes(), writer)
+    }
+}
+
+impl BorshDeserialize for RangeProof {
+    fn deserialize(buf: &mut &[u8]) -> core::result::Result<Self, borsh::maybestd::io::Error> {
+        let bytes = <Vec<u8> as BorshDeserialize>::deserialize(buf)?;
+        RangeProof::from_bytes(&bytes).map_err(|_| {
+            borsh::maybestd::io::Error::new(
+                borsh::maybestd::io::ErrorKind::InvalidData,
+                "invalid range proof",
+            )
+        })
+    }
+}
+
+/// The smallest supported bitsize `n` with `max - min < 2^n`, which is what a proof for the
+/// interval `[min, max]` needs.
+fn interval_bitsize(min: u64, max: u64) -> Result<usize, ProofError> {
+    if min > max {
+        return Err(ProofError::InvalidRange);
+    }
+    let bits = (64 - (max - min).leading_zeros()) as usize;
+    Ok([8, 16, 32, 64].iter().copied().find(|&n| bits <= n).unwrap_or(64))
+}
+
+/// The verification equation of a rangeproof, which holds if the sum of its terms and of
+/// the generators times their scalars is zero.
+struct VerificationEquation {
+    /// The points of the proof and the value commitments with their scalars.
+    terms: Vec<(G1, Scalar)>,
+    /// The scalar of the Pedersen base point \\(B\\).
+    B: Scalar,
+    /// The scalar of the Pedersen blinding base point \\(\tilde{B}\\).
+    B_blinding: Scalar,
+    /// The scalars of the generators `bp_gens.G(n, m)`.
+    g: Vec<Scalar>,
+    /// The scalars of the generators `bp_gens.H(n, m)`.
+    h: Vec<Scalar>,
+}
 
 /// Compute
 /// \\[
@@ -0,0 +1,630 @@
+//! The `runner` module runs the aggregated rangeproof MPC protocol between a
+//! dealer and parties on different machines, e.g. wallets that jointly prove
+//! the outputs of a multi-party payment with a single aggregated proof.
+//!
+//! The dealer and the parties exchange length-prefixed [`Envelope`]s over a
+//! [`Connection`], which wraps any stream implementing `AsyncRead + AsyncWrite`:
+//! a `tokio::net::TcpStream` over the network, or a `tokio::io::DuplexStream`
+//! as an in-memory transport. The dealer runs a session with
+//! [`DealerRunner::run`], and each party with [`PartyRunner::run`].
+//!
+//! Every message is tagged with a random session id chosen by the dealer, and
+//! a failed session leaves no state behind: the transcript of the dealer is
+//! only updated once the proof is assembled, and a party starts every session
+//! with a fresh [`Party`] and fresh blinding factors. Both sides can simply run
+//! again after a failure, possibly without the parties that
+//! [`RunnerError::bad_parties`] blames for it.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::iter;
+use core::time::Duration;
+use std::io;
+
+use bn::{Fr as Scalar, G1};
+use merlin::Transcript;
+use rand::{thread_rng, RngCore};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::time::{timeout, timeout_at, Instant};
+
+use crate::errors::{ProofError, RunnerError};
+use crate::generators::{BulletproofGens, PedersenGens};
+use crate::range_proof::{RangeProof, FIELD_BYTES_LEN, G_COMP_BYTES_LEN};
+use crate::util;
+
+use super::dealer::Dealer;
+use super::messages::*;
+use super::party::Party;
+
+/// The length of the longest envelope a [`Connection`] accepts, well above the
+/// length of the messages of any aggregation the generators support.
+pub const MAX_ENVELOPE_LEN: usize = 1 << 16;
+
+/// A message of the protocol.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum Message {
+    /// Sent by the dealer to open a session for `n`-bit proofs of `m`
+    /// parties, with the position `j` of the receiving party.
+    Start {
+        /// The bitsize of the proof.
+        n: u32,
+        /// The number of parties.
+        m: u32,
+        /// The position of the receiving party.
+        j: u32,
+    },
+    /// Sent by a party, see [`BitCommitment`].
+    BitCommitment(BitCommitment),
+    /// Sent by the dealer, see [`BitChallenge`].
+    BitChallenge(BitChallenge),
+    /// Sent by a party, see [`PolyCommitment`].
+    PolyCommitment(PolyCommitment),
+    /// Sent by the dealer, see [`PolyChallenge`].
+    PolyChallenge(PolyChallenge),
+    /// Sent by a party, see [`ProofShare`].
+    ProofShare(ProofShare),
+    /// Sent by the dealer to close a session with the aggregated proof.
+    Proof {
+        /// The value commitments of all parties, in the order of their positions.
+        value_commitments: Vec<G1>,
+        /// The aggregated proof.
+        proof: RangeProof,
+    },
+    /// Sent by the dealer to close a session that failed.
+    Abort,
+}
+
+/// A [`Message`] tagged with the session it belongs to.
+#[derive(Clone, Debug)]
+pub struct Envelope {
+    /// The id of the session, chosen at random by the dealer.
+    pub session: u64,
+    /// The message.
+    pub message: Message,
+}
+
+impl Envelope {
+    /// Serializes the envelope into a byte array.
+    ///
+    /// # Layout
+    ///
+    /// The session id as 8 little-endian bytes, a byte for the kind of the
+    /// message, then the fields of the message in the order of their
+    /// declaration. Points are compressed, scalars are in their canonical
+    /// 32-byte encoding, integers and the lengths of vectors are 4
+    /// little-endian bytes, and proofs are encoded with [`RangeProof::to_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        fn point(buf: &mut Vec<u8>, point: &G1) {
+            buf.extend_from_slice(&point.to_compressed());
+        }
+        fn scalar(buf: &mut Vec<u8>, scalar: &Scalar) {
+            buf.extend_from_slice(&util::scalar_to_bytes(scalar));
+        }
+        fn int(buf: &mut Vec<u8>, int: usize) {
+            buf.extend_from_slice(&(int as u32).to_le_bytes());
+        }
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&self.session.to_le_bytes());
+        match &self.message {
+            Message::Start { n, m, j } => {
+                buf.push(0);
+                for x in [n, m, j] {
+                    buf.extend_from_slice(&x.to_le_bytes());
+                }
+            }
+            Message::BitCommitment(c) => {
+                buf.push(1);
+                point(&mut buf, &c.V_j);
+                point(&mut buf, &c.A_j);
+                point(&mut buf, &c.S_j);
+            }
+            Message::BitChallenge(c) => {
+                buf.push(2);
+                scalar(&mut buf, &c.y);
+                scalar(&mut buf, &c.z);
+            }
+            Message::PolyCommitment(c) => {
+                buf.push(3);
+                point(&mut buf, &c.T_1_j);
+                point(&mut buf, &c.T_2_j);
+            }
+            Message::PolyChallenge(c) => {
+                buf.push(4);
+                scalar(&mut buf, &c.x);
+            }
+            Message::ProofShare(share) => {
+                buf.push(5);
+                scalar(&mut buf, &share.t_x);
+                scalar(&mut buf, &share.t_x_blinding);
+                scalar(&mut buf, &share.e_blinding);
+                for vec in [&share.l_vec, &share.r_vec] {
+                    int(&mut buf, vec.len());
+                    vec.iter().for_each(|s| scalar(&mut buf, s));
+                }
+            }
+            Message::Proof {
+                proof,
+                value_commitments,
+            } => {
+                buf.push(6);
+                int(&mut buf, value_commitments.len());
+                value_commitments.iter().for_each(|V| point(&mut buf, V));
+                buf.extend_from_slice(&proof.to_bytes());
+            }
+            Message::Abort => buf.push(7),
+        }
+        buf
+    }
+
+    /// Deserializes the envelope from a byte slice.
+    ///
+    /// Returns an error if the byte slice cannot be parsed into an `Envelope`.
+    pub fn from_bytes(slice: &[u8]) -> Result<Envelope, ProofError> {
+        let mut reader = Reader(slice);
+
+        let session = reader.u64()?;
+        let message = match reader.u8()? {
+            0 => Message::Start {
+                n: reader.u32()?,
+                m: reader.u32()?,
+                j: reader.u32()?,
+            },
+            1 => Message::BitCommitment(BitCommitment {
+                V_j: reader.point()?,
+                A_j: reader.point()?,
+                S_j: reader.point()?,
+            }),
+            2 => Message::BitChallenge(BitChallenge {
+                y: reader.scalar()?,
+                z: reader.scalar()?,
+            }),
+            3 => Message::PolyCommitment(PolyCommitment {
+                T_1_j: reader.point()?,
+                T_2_j: reader.point()?,
+            }),
+            4 => Message::PolyChallenge(PolyChallenge { x: reader.scalar()? }),
+            5 => Message::ProofShare(ProofShare {
+                t_x: reader.scalar()?,
+                t_x_blinding: reader.scalar()?,
+                e_blinding: reader.scalar()?,
+                l_vec: reader.scalars()?,
+                r_vec: reader.scalars()?,
+            }),
+            6 => {
+                let len = reader.u32()?;
+                let value_commitments = (0..len)
+                    .map(|_| reader.point())
+                    .collect::<Result<Vec<_>, _>>()?;
+                let proof = RangeProof::from_bytes(reader.take(reader.0.len())?)?;
+                Message::Proof {
+                    proof,
+                    value_commitments,
+                }
+            }
+            7 => Message::Abort,
+            _ => return Err(ProofError::FormatError),
+        };
+
+        if !reader.0.is_empty() {
+            return Err(ProofError::FormatError);
+        }
+        Ok(Envelope { session, message })
+    }
+}
+
+/// Reads the fields of an encoded [`Envelope`] one after the other.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ProofError> {
+        if self.0.len() < len {
+            return Err(ProofError::FormatError);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, ProofError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, ProofError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, ProofError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn point(&mut self) -> Result<G1, ProofError> {
+        util::read_point(self.take(G_COMP_BYTES_LEN)?).ok_or(ProofError::FormatError)
+    }
+
+    fn scalar(&mut self) -> Result<Scalar, ProofError> {
+        let bytes = self.take(FIELD_BYTES_LEN)?.try_into().unwrap();
+        util::scalar_from_bytes(bytes).ok_or(ProofError::FormatError)
+    }
+
+    fn scalars(&mut self) -> Result<Vec<Scalar>, ProofError> {
+        let len = self.u32()?;
+        (0..len).map(|_| self.scalar()).collect()
+    }
+}
+
+/// A connection between the dealer and a party, which sends and receives
+/// [`Envelope`]s prefixed with their length as 4 little-endian bytes.
+///
+/// Receiving is not cancel safe: a connection on which
+/// [`recv`](Connection::recv) was cancelled, e.g. by a timeout, may be in the
+/// middle of an envelope and should be dropped.
+pub struct Connection<S> {
+    stream: S,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Connection<S> {
+    /// Creates a connection over `stream`.
+    pub fn new(stream: S) -> Self {
+        Connection { stream }
+    }
+
+    /// Returns the underlying stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    /// Sends an envelope.
+    pub async fn send(&mut self, envelope: &Envelope) -> io::Result<()> {
+        let bytes = envelope.to_bytes();
+        self.stream
+            .write_all(&(bytes.len() as u32).to_le_bytes())
+            .await?;
+        self.stream.write_all(&bytes).await?;
+        self.stream.flush().await
+    }
+
+    /// Receives an envelope.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the envelope is longer than
+    /// [`MAX_ENVELOPE_LEN`] or does not decode.
+    pub async fn recv(&mut self) -> io::Result<Envelope> {
+        let len = self.stream.read_u32_le().await? as usize;
+        if len > MAX_ENVELOPE_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "envelope too long",
+            ));
+        }
+
+        let mut bytes = vec![0u8; len];
+        self.stream.read_exact(&mut bytes).await?;
+        Envelope::from_bytes(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Runs the dealer side of the protocol over the network.
+pub struct DealerRunner<'a> {
+    bp_gens: &'a BulletproofGens,
+    pc_gens: &'a PedersenGens,
+    n: usize,
+    timeout: Duration,
+}
+
+impl<'a> DealerRunner<'a> {
+    /// Creates a runner for sessions of `n`-bit proofs, which waits at most
+    /// `timeout` for the messages of the parties in each step.
+    pub fn new(
+        bp_gens: &'a BulletproofGens,
+        pc_gens: &'a PedersenGens,
+        n: usize,
+        timeout: Duration,
+    ) -> Self {
+        DealerRunner {
+            bp_gens,
+            pc_gens,
+            n,
+            timeout,
+        }
+    }
+
+    /// Runs a session with the parties behind `connections`, where the party
+    /// behind `connections[j]` gets the position `j`, and returns the
+    /// aggregated proof with the value commitments of the parties, which are
+    /// sent to every party as well.
+    ///
+    /// The proof is chained to `transcript`, which is only updated if the
+    /// session succeeds. If it fails, every party is sent [`Message::Abort`],
+    /// and the error names the parties to blame, if any: those that timed out,
+    /// sent malformed messages, or sent invalid proof shares, which are found
+    /// by auditing the shares as in
+    /// [`receive_shares`](super::dealer::DealerAwaitingProofShares::receive_shares).
+    pub async fn run<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        transcript: &mut Transcript,
+        connections: &mut [Connection<S>],
+    ) -> Result<(RangeProof, Vec<G1>), RunnerError> {
+        let session = thread_rng().next_u64();
+        let mut session_transcript = transcript.clone();
+
+        match self
+            .run_session(session, &mut session_transcript, connections)
+            .await
+        {
+            Ok((proof, value_commitments)) => {
+                *transcript = session_transcript;
+                Ok((proof, value_commitments))
+            }
+            Err(e) => {
+                self.notify_all(session, connections, Message::Abort).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn run_session<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        session: u64,
+        transcript: &mut Transcript,
+        connections: &mut [Connection<S>],
+    ) -> Result<(RangeProof, Vec<G1>), RunnerError> {
+        let m = connections.len();
+        let dealer = Dealer::new(self.bp_gens, self.pc_gens, transcript, self.n, m)?;
+
+        let start = (0..m).map(|j| Message::Start {
+            n: self.n as u32,
+            m: m as u32,
+            j: j as u32,
+        });
+        self.send_all(session, connections, start).await?;
+
+        let bit_commitments = self
+            .gather(session, connections, |message| match message {
+                Message::BitCommitment(c) => Some(c),
+                _ => None,
+            })
+            .await?;
+        let value_commitments: Vec<G1> = bit_commitments.iter().map(|c| c.V_j).collect();
+        let (dealer, bit_challenge) = dealer.receive_bit_commitments(bit_commitments)?;
+        let bit_challenge = iter::repeat(Message::BitChallenge(bit_challenge));
+        self.send_all(session, connections, bit_challenge).await?;
+
+        let poly_commitments = self
+            .gather(session, connections, |message| match message {
+                Message::PolyCommitment(c) => Some(c),
+                _ => None,
+            })
+            .await?;
+        let (dealer, poly_challenge) = dealer.receive_poly_commitments(poly_commitments)?;
+        let poly_challenge = iter::repeat(Message::PolyChallenge(poly_challenge));
+        self.send_all(session, connections, poly_challenge).await?;
+
+        let proof_shares = self
+            .gather(session, connections, |message| match message {
+                Message::ProofShare(share) => Some(share),
+                _ => None,
+            })
+            .await?;
+        let proof = dealer.receive_shares(&proof_shares)?;
+
+        // The proof is valid whether or not the parties receive it.
+        let message = Message::Proof {
+            proof: proof.clone(),
+            value_commitments: value_commitments.clone(),
+        };
+        self.notify_all(session, connections, message).await;
+
+        Ok((proof, value_commitments))
+    }
+
+    /// Sends the `j`-th of `messages` to the `j`-th party, and fails if
+    /// any of the parties cannot be reached.
+    async fn send_all<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        session: u64,
+        connections: &mut [Connection<S>],
+        messages: impl Iterator<Item = Message>,
+    ) -> Result<(), RunnerError> {
+        let deadline = Instant::now() + self.timeout;
+        let mut timed_out = Vec::new();
+        let mut failed = Vec::new();
+
+        for (j, (connection, message)) in connections.iter_mut().zip(messages).enumerate() {
+            match timeout_at(deadline, connection.send(&Envelope { session, message })).await {
+                Ok(Ok(())) => {}
+                Ok(Err(_)) => failed.push(j),
+                Err(_) => timed_out.push(j),
+            }
+        }
+
+        if !failed.is_empty() {
+            Err(RunnerError::MalformedMessages { parties: failed })
+        } else if !timed_out.is_empty() {
+            Err(RunnerError::PartyTimeout { parties: timed_out })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sends `message` to every party that can be reached.
+    async fn notify_all<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        session: u64,
+        connections: &mut [Connection<S>],
+        message: Message,
+    ) {
+        let _ = self
+            .send_all(session, connections, iter::repeat(message))
+            .await;
+    }
+
+    /// Receives a message of the current step from every party, and fails
+    /// if any of them is not received in time or is not the expected one.
+    async fn gather<S: AsyncRead + AsyncWrite + Unpin, T>(
+        &self,
+        session: u64,
+        connections: &mut [Connection<S>],
+        expected: impl Fn(Message) -> Option<T>,
+    ) -> Result<Vec<T>, RunnerError> {
+        // The parties answer concurrently, so a single deadline for
+        // the whole step bounds the wait for every one of them.
+        let deadline = Instant::now() + self.timeout;
+        let mut items = Vec::with_capacity(connections.len());
+        let mut timed_out = Vec::new();
+        let mut malformed = Vec::new();
+
+        for (j, connection) in connections.iter_mut().enumerate() {
+            match timeout_at(deadline, connection.recv()).await {
+                Ok(Ok(envelope)) if envelope.session == session => {
+                    match expected(envelope.message) {
+                        Some(item) => items.push(item),
+                        None => malformed.push(j),
+                    }
+                }
+                Ok(_) => malformed.push(j),
+                Err(_) => timed_out.push(j),
+            }
+        }
+
+        if !malformed.is_empty() {
+            Err(RunnerError::MalformedMessages { parties: malformed })
+        } else if !timed_out.is_empty() {
+            Err(RunnerError::PartyTimeout { parties: timed_out })
+        } else {
+            Ok(items)
+        }
+    }
+}
+
+/// Runs the party side of the protocol over the network.
+pub struct PartyRunner<'a> {
+    bp_gens: &'a BulletproofGens,
+    pc_gens: &'a PedersenGens,
+    v: u64,
+    v_blinding: Scalar,
+    n: usize,
+    timeout: Duration,
+}
+
+impl<'a> PartyRunner<'a> {
+    /// Creates a runner for a party proving that `v`, committed to with the
+    /// blinding factor `v_blinding`, is in the `n`-bit range. The runner waits
+    /// at most `timeout` for the dealer to open a session and for each of the
+    /// messages of the dealer.
+    pub fn new(
+        bp_gens: &'a BulletproofGens,
+        pc_gens: &'a PedersenGens,
+        v: u64,
+        v_blinding: Scalar,
+        n: usize,
+        timeout: Duration,
+    ) -> Self {
+        PartyRunner {
+            bp_gens,
+            pc_gens,
+            v,
+            v_blinding,
+            n,
+            timeout,
+        }
+    }
+
+    /// Runs a session with the dealer behind `connection`, and returns the
+    /// aggregated proof with the value commitments of all parties.
+    ///
+    /// Every session starts from a fresh [`Party`] with fresh blinding factors,
+    /// so after a failure the party can run again on the same connection, e.g.
+    /// when the dealer aborted the session because of another party.
+    pub async fn run<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        connection: &mut Connection<S>,
+    ) -> Result<(RangeProof, Vec<G1>), RunnerError> {
+        let (session, j) = self.recv_start(connection).await?;
+
+        let party = Party::new(self.bp_gens, self.pc_gens, self.v, self.v_blinding, self.n)?;
+        let (party, bit_commitment) = party.assign_position(j)?;
+        let V = bit_commitment.V_j;
+        self.send(connection, session, Message::BitCommitment(bit_commitment))
+            .await?;
+
+        let bit_challenge = match self.recv(connection, session).await? {
+            Message::BitChallenge(c) => c,
+            _ => return Err(RunnerError::MalformedDealerMessage),
+        };
+        let (party, poly_commitment) = party.apply_challenge(&bit_challenge);
+        self.send(connection, session, Message::PolyCommitment(poly_commitment))
+            .await?;
+
+        let poly_challenge = match self.recv(connection, session).await? {
+            Message::PolyChallenge(c) => c,
+            _ => return Err(RunnerError::MalformedDealerMessage),
+        };
+        let proof_share = party.apply_challenge(&poly_challenge)?;
+        self.send(connection, session, Message::ProofShare(proof_share))
+            .await?;
+
+        match self.recv(connection, session).await? {
+            Message::Proof {
+                proof,
+                value_commitments,
+            } if value_commitments.get(j) == Some(&V) => Ok((proof, value_commitments)),
+            _ => Err(RunnerError::MalformedDealerMessage),
+        }
+    }
+
+    /// Waits for the dealer to open a session, skipping what is left of
+    /// earlier sessions, and returns the session id and the position of
+    /// the party.
+    async fn recv_start<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        connection: &mut Connection<S>,
+    ) -> Result<(u64, usize), RunnerError> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let envelope = match timeout_at(deadline, connection.recv()).await {
+                Ok(Ok(envelope)) => envelope,
+                Ok(Err(_)) => return Err(RunnerError::MalformedDealerMessage),
+                Err(_) => return Err(RunnerError::DealerTimeout),
+            };
+            match envelope.message {
+                // The abort of a session this party gave up on.
+                Message::Abort => continue,
+                Message::Start { n, m, j } if n as usize == self.n && j < m => {
+                    return Ok((envelope.session, j as usize))
+                }
+                _ => return Err(RunnerError::MalformedDealerMessage),
+            }
+        }
+    }
+
+    async fn recv<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        connection: &mut Connection<S>,
+        session: u64,
+    ) -> Result<Message, RunnerError> {
+        match timeout(self.timeout, connection.recv()).await {
+            Ok(Ok(envelope)) if envelope.session == session => match envelope.message {
+                Message::Abort => Err(RunnerError::Aborted),
+                message => Ok(message),
+            },
+            Ok(_) => Err(RunnerError::MalformedDealerMessage),
+            Err(_) => Err(RunnerError::DealerTimeout),
+        }
+    }
+
+    async fn send<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        connection: &mut Connection<S>,
+        session: u64,
+        message: Message,
+    ) -> Result<(), RunnerError> {
+        match timeout(self.timeout, connection.send(&Envelope { session, message })).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(_)) => Err(RunnerError::MalformedDealerMessage),
+            Err(_) => Err(RunnerError::DealerTimeout),
+        }
+    }
+}
@@ -14,6 +14,9 @@ pub trait TranscriptProtocol {
     /// Append a domain separator for an `n`-bit, `m`-party range proof.
     fn rangeproof_domain_sep(&mut self, n: u64, m: u64);
 
+    /// Append a domain separator for a range proof of the interval `[min, max]`.
+    fn interval_domain_sep(&mut self, min: u64, max: u64);
+
     /// Append a domain separator for a length-`n` inner product proof.
     fn innerproduct_domain_sep(&mut self, n: u64);
 
@@ -51,6 +54,12 @@ impl TranscriptProtocol for Transcript {
         self.append_u64(b"m", m);
     }
 
+    fn interval_domain_sep(&mut self, min: u64, max: u64) {
+        self.append_message(b"dom-sep", b"interval v1");
+        self.append_u64(b"min", min);
+        self.append_u64(b"max", max);
+    }
+
     fn innerproduct_domain_sep(&mut self, n: u64) {
         self.append_message(b"dom-sep", b"ipp v1");
         self.append_u64(b"n", n);
@@ -5,9 +5,8 @@ extern crate alloc;
 
 use alloc::vec;
 use alloc::vec::Vec;
-use bn::{G1, Fr as Scalar, Group};
+use bn::{G1, Fr as Scalar};
 use clear_on_drop::clear::Clear;
-use sha2::Digest;
 use bn::arith::U256;
 use borsh::{BorshDeserialize, BorshSerialize};
 
@@ -63,9 +62,13 @@ impl Iterator for ScalarExp {
     }
 }
 
+/// Domain separation tag of `hash_to_g1`. The version must be bumped whenever the mapping
+/// changes, since points hashed under different versions are unrelated.
+pub const HASH_TO_G1_DST: &[u8] = b"BULLETPROOFS-V01-CS01-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+
+/// Hash `msg` to G1 without revealing the discrete log of the result.
 pub fn hash_to_g1(msg: &[u8]) -> G1 {
-    let hash = sha2::Sha256::digest(msg);
-    G1::one() * Scalar::new_mul_factor(U256::from_slice(&hash).unwrap())
+    G1::hash_to_curve(msg, HASH_TO_G1_DST)
 }
 
 pub fn alt_bn128_g1_sum(v: &[G1]) -> core::result::Result<G1, crate::alt_bn128::AltBn128Error> {
@@ -301,11 +304,26 @@ pub fn read32(data: &[u8]) -> [u8; 32] {
     buf32
 }
 
-/// Given `data` with `len >= 48`, return the first 48 bytes.
-pub fn read48(data: &[u8]) -> [u8; 48] {
-    let mut buf48 = [0u8; 48];
-    buf48[..].copy_from_slice(&data[..48]);
-    buf48
+/// Encodes a scalar as its canonical 32-byte big-endian representation,
+/// in the same byte order as the coordinates of compressed points.
+pub fn scalar_to_bytes(s: &Scalar) -> [u8; 32] {
+    let mut buf32 = [0u8; 32];
+    s.into_u256()
+        .to_big_endian(&mut buf32)
+        .expect("buffer is 32 bytes; qed");
+    buf32
+}
+
+/// Decodes a scalar from its canonical 32-byte big-endian representation.
+/// Returns `None` if the value is not below the group order.
+pub fn scalar_from_bytes(bytes: &[u8; 32]) -> Option<Scalar> {
+    U256::from_slice(bytes).ok().and_then(Scalar::new)
+}
+
+/// Given `data` with `len >= 33`, decode the compressed point in its first 33 bytes.
+/// Returns `None` if the encoding is invalid or the point is not on the curve.
+pub fn read_point(data: &[u8]) -> Option<G1> {
+    G1::from_compressed(&data[..33]).ok()
 }
 
 #[cfg(test)]
@@ -1,18 +1,23 @@
 #![allow(non_snake_case)]
 
 extern crate bulletproofs;
-extern crate curve25519_dalek;
+extern crate bn;
 extern crate merlin;
 extern crate rand;
 
 use bulletproofs::r1cs::*;
 use bulletproofs::{BulletproofGens, PedersenGens};
-use curve25519_dalek::ristretto::CompressedRistretto;
-use curve25519_dalek::scalar::Scalar;
+use bn::arith::U256;
+use bn::{Fr as Scalar, G1};
 use merlin::Transcript;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 
+/// The scalar `x`.
+fn scalar(x: u64) -> Scalar {
+    Scalar::new_mul_factor(U256::from(x))
+}
+
 // Shuffle gadget (documented in markdown file)
 
 /// A proof-of-shuffle.
@@ -72,11 +77,7 @@ impl ShuffleProof {
         input: &[Scalar],
         output: &[Scalar],
     ) -> Result<
-        (
-            ShuffleProof,
-            Vec<CompressedRistretto>,
-            Vec<CompressedRistretto>,
-        ),
+        (ShuffleProof, Vec<G1>, Vec<G1>),
         R1CSError,
     > {
         // Apply a domain separator with the shuffle parameters to the transcript
@@ -116,8 +117,8 @@ impl ShuffleProof {
         pc_gens: &'b PedersenGens,
         bp_gens: &'b BulletproofGens,
         transcript: &'a mut Transcript,
-        input_commitments: &Vec<CompressedRistretto>,
-        output_commitments: &Vec<CompressedRistretto>,
+        input_commitments: &Vec<G1>,
+        output_commitments: &Vec<G1>,
     ) -> Result<(), R1CSError> {
         // Apply a domain separator with the shuffle parameters to the transcript
         // XXX should this be part of the gadget?
@@ -156,7 +157,7 @@ fn kshuffle_helper(k: usize) {
         let mut rng = rand::thread_rng();
         let (min, max) = (0u64, std::u64::MAX);
         let input: Vec<Scalar> = (0..k)
-            .map(|_| Scalar::from(rng.gen_range(min..max)))
+            .map(|_| scalar(rng.gen_range(min..max)))
             .collect();
         let mut output = input.clone();
         output.shuffle(&mut rand::thread_rng());
@@ -248,7 +249,7 @@ fn example_gadget_proof(
     b2: u64,
     c1: u64,
     c2: u64,
-) -> Result<(R1CSProof, Vec<CompressedRistretto>), R1CSError> {
+) -> Result<(R1CSProof, Vec<G1>), R1CSError> {
     let mut transcript = Transcript::new(b"R1CSExampleGadget");
 
     // 1. Create a prover
@@ -257,7 +258,7 @@ fn example_gadget_proof(
     // 2. Commit high-level variables
     let (commitments, vars): (Vec<_>, Vec<_>) = [a1, a2, b1, b2, c1]
         .into_iter()
-        .map(|x| prover.commit(Scalar::from(*x), Scalar::random(&mut thread_rng())))
+        .map(|x| prover.commit(scalar(x), Scalar::random(&mut thread_rng())))
         .unzip();
 
     // 3. Build a CS
@@ -268,7 +269,7 @@ fn example_gadget_proof(
         vars[2].into(),
         vars[3].into(),
         vars[4].into(),
-        Scalar::from(c2).into(),
+        c2.into(),
     );
 
     // 4. Make a proof
@@ -283,7 +284,7 @@ fn example_gadget_verify(
     bp_gens: &BulletproofGens,
     c2: u64,
     proof: R1CSProof,
-    commitments: Vec<CompressedRistretto>,
+    commitments: Vec<G1>,
 ) -> Result<(), R1CSError> {
     let mut transcript = Transcript::new(b"R1CSExampleGadget");
 
@@ -301,7 +302,7 @@ fn example_gadget_verify(
         vars[2].into(),
         vars[3].into(),
         vars[4].into(),
-        Scalar::from(c2).into(),
+        c2.into(),
     );
 
     // 4. Verify the proof
@@ -378,7 +379,7 @@ pub fn range_proof<CS: ConstraintSystem>(
         // Create low-level variables and add them to constraints
         let (a, b, o) = cs.allocate_multiplier(v_assignment.map(|q| {
             let bit: u64 = (q >> i) & 1;
-            ((1 - bit).into(), bit.into())
+            (scalar(1 - bit), scalar(bit))
         }))?;
 
         // Enforce a * b = 0, so one of (a,b) is zero
@@ -413,9 +414,9 @@ fn range_proof_gadget() {
         let (min, max) = (0u64, ((1u128 << n) - 1) as u64);
         let values: Vec<u64> = (0..m).map(|_| rng.gen_range(min..max)).collect();
         for v in values {
-            assert!(range_proof_helper(v.into(), *n).is_ok());
+            assert!(range_proof_helper(v, *n).is_ok());
         }
-        assert!(range_proof_helper((max + 1).into(), *n).is_err());
+        assert!(range_proof_helper(max + 1, *n).is_err());
     }
 }
 
@@ -432,7 +433,7 @@ fn range_proof_helper(v_val: u64, n: usize) -> Result<(), R1CSError> {
 
         let mut prover = Prover::new(&pc_gens, &mut prover_transcript);
 
-        let (com, var) = prover.commit(v_val.into(), Scalar::random(&mut rng));
+        let (com, var) = prover.commit(scalar(v_val), Scalar::random(&mut rng));
         assert!(range_proof(&mut prover, var.into(), Some(v_val), n).is_ok());
 
         let proof = prover.prove(&bp_gens)?;
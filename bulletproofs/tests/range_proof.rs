@@ -2,82 +2,59 @@ use rand::SeedableRng;
 
 use rand_chacha::ChaChaRng;
 
-use group::ff::Field;
+use bn::{Fr as Scalar, G1};
 
-use blstrs::Scalar;
+use borsh::{BorshDeserialize, BorshSerialize};
 
 use merlin::Transcript;
 
-use bls_bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use bulletproofs::{BulletproofGens, PedersenGens, ProofError, RangeProof};
 
-use hex;
-
-/*
-// Tests that proofs generated with v1.0.0 continue to verify in later versions.
+// Tests that the encoding of range proofs stays stable, so that proofs
+// serialized by earlier versions continue to verify in later versions.
 #[test]
 fn deserialize_and_verify() {
     // proofs[i][j] has bitsize n = 8 << i, aggregation size m = 1 << j
     let proofs = [
         [
-            b"46b6ea8b6a9710c41c2622d4b353dbcf5f89afe8ed66c469f192bec19dc71d23c0442827f97fc9085a89caa87d294b0a21e7b8957732ec4951f6bf7d3aa2c66e7af3b7b956c7dcb3bed1223575a217a30642b603b6bf1d4138ed95e3458c524510b42c8d82958f40b447a84242b1ba1eeea54013f80bad643048eeb0b17c292a057cb6ae1c42338837c05eaa6336a17d60fa141204e015a1df15b28c1318c709d7eb35569cde89c0bf37eace54880a151498b38da54c6d739564f46f01b73601e518355ea06c9ef58a45fcb3baadbd1ac54e0838c471a6b91845f123d569fa0c46ef94471b7b826230e8576146beec08ac3e6683998815c576581f4c0e493433480f95f6495210636eaa2e32b577e1c363e35e522db85b18a56d57eb626f9e2b50578e0d7ee7b74b328e158b366bb9d117db725820a2fec3b1508212d75823345a801c0b602bfa05919d7e3bb8e71944587072badc363f334b08ba90d13e077ad24b82bacd51fc668d2b880daabd3b87e6bdc9584af66523026a30aadfc359283891bb65cca502f47421ffeee1fb5a5237bfa965b66a8b8ca5d6954f4f8222244c6a5340dc81e8d781d092cae2a763f185dd0b89965b1dd2506807b5d3e5a305fd9a68e60b91389dcffae6f85538713aa7ed272b8174e2f0b9730ebb6c464d06".to_vec(),
-            b"96cb7f843c95f494b5b97d74d29ad855eda25a9b09a51c04275d5f79b988055dee65c2accc2d77f281ff9e1933a470f40d467ae7a557e922a6d0cf07db09e3655a7d82cfe9262b7ad49953b765f0e85ff71730526d43b902b9e50e88f1b13a1ca0fed50530b4a22307a4eebf2d49127f1899a5b6b0b68f25d078972ec53a555fa371422ef112b34b08d45c37cca5bd6039f7644162e073918502a0585f9e3803e59f6e6b1646e38716a0704b8232f397c9ee6222a971dec8b8518b5dc6101308e188f22a47baf5139d069183a657287cabfe744e7f4871676d5b85321374710fd4f69cd96053c09ee529fe1e235765d3410593c7b8caff21c13d8efb175f540306bf4a841d05a2f78dae3e2382d4d0662f15f282b47a435a2b1d3f9bcaff0d14a4d44ba466ea0e63664656e715d60b42866626cd3a039d08c6428b3c4f60106610d97ead55e1af253353ddb313c65b4a6dd65ec4555c62dde57653db51db111ef42cfb0bf7621e14509634d3616ec9b46a12ab2b187668a6207a4838d04e0b01aca5f4002ee00a901319d876ab8ebb6619c81f2afc2016e48b7e1cf14577af1abccea0100f3ee10cbc1b2ea5f46e0a4a25519f5c58ef15c2c38549e9240a615fc87210c68e6128476cb02dd5ce2b8fdbd407f23013ed1ab3fa86ff441788b31ab5375e5442e825ecf5d7f3060e3b2bbf96074f5dc121ec6f3460d8d27f2e4101f5d910181c6012f6345da390055929f6c6ba243b6f8ef2a1a62c49e9ae036703".to_vec(),
-            b"ac9cc69473001eb3a3c794b293a7ebfb5677441aa1050bbefdb700cbd1e33b42422b547213f86fb2321c51dc0433fcc3114d406ab4c6df6fbdd854d878710f5e6c8a02a6231ac47d4c7bb01311fd98563285c7accdb63a01037bea326fa269094609d0552446cc593a141f1b735704009ec08eba147beff77c61c13d969ba443240e8c341e25ae508a1c6394d804baa70902ec15bd556a031a6470f7fba40f0842a77f1a070d5bd6ad0b4fb336924bc79b863eeb62baaa06684b4e78ab806a05d8372ee5951478fc673a8d6291727ee3af2154dad109cb4258b15ead6e34360840675442fd7d6fea8a54a787247244a1749a193a67592dd14abff0ad9ee9fd66f6e9762b69fda1e2dd59c786c5ed40e12be542b34a0a70a4a372e33eb6def048f82fc612fffb186f910e7436aee4c9a37065c1298cd20f5025b16273310e69259adda0d53703fafba056e5ad45f854bd10d904f38f5265794f7241fcaa77171e2ad8ac27c85a5c25439c3af100715d6626744b01064a4c69df0875ba25cb6418dc3e2f02a2c91bef7783dd10e898fc132957c0d50fad4095e16d136142bbd24886e9642297f93302cf1c13c4eb6e593f35896c3c52c208899901c654190f2274a8254bf2e3a412e20dabdd9cce8fa4e2cd9ff6c850c7513cb70dc8eebfddd222509717707674b2dc477adcdb1dd68b3a05a43db5f39a1cc41269210098eb8473089441253de2ddfcfbf6451300f3848e13cc4c09729780bddc524015f80aa171133dd05a64d53dc2d752d6b5892980cd772eb78c991a66222e7489f83dcb4c0d3d7951528ae33427d117232b8571641769d190e941a47b2fabafa74127ede40b".to_vec(),
-            b"debf1c3132cca627b5a254233a346397121c8316c24c36ad56c112faa556ba5b9e70c27ba6ebddd16fbade34093139cc2d110b92b07156e09afca4232aad8a53cc652db53258d4fc9470424162265ab473807807d563da8a312e360876948952b2b35314dafb99daaa5849eb1b2d4f6b9352c6c4d2c5bcf9486b7f741807b43bd3080f3cec4276bfe51be3c7a4830de67fab37ac05d9bf39211634afdb4aee00ae347d685f787ddd4fcdae659594a96e0b369350c048d323687108a87f4d800dc81635453e102239fc79f38e54163def5cb45b9654574450f663a86a23277d0d4e4e0a39541ae31d9ac9cc81b6f2152b1fa454f4cb7922e38d8a3f53449f9759ec0dbc37426515968f71b17f0737ccc0162fe9462f9ee3c9e060894ec7a4527faa58949f663d79a417d7bfa959122153a70f954dff65e1a647efb9883e6c946414e48fe0e9c5a48ad4387ea36e32634fcc4edb297626cd669467247b0784a92472ae47e4acd691e189d7b01eb34962381a8ff76893cf6bdd4c8f87c40b71515dd0654ba71b7dff63f5af7ce04dc57760089bb4ac2a0cbc636685018f770e3229065169f0b4e5a0f1480d4255bb71fc7bc0ee2b538b6247f97f2f73659362a7476883f480e5bff5ea2c503b7b5b870538139a2bf64db90549c97e9baf1ed5eb525ce2c3d6b232a642cfbe47fd4591b6a80b100c01f865924a8f9157a573fda428383beec5fb6e3396e898a5335d76333b2afcfe75df77039ea5f8af0e12f7ce0986c347530de5ecad84aa887fac03f11cc09f1a1b05b5b35a6a359915b98b986b6818f36b3f6054303976dc7dac6212a3ebafc5a67dd47623025426c865674b7b5cdabaccf1c528cfebf18cd0be121ea59b06ce42fcb6ae327f260ac5d7fadd0fac75a37634fc0d6a9217d3671d26e0973ee29d1cad27aa8b9c4a5759a5e1bb05".to_vec(),
+            "020cfbf0a0148f7194417118951d8799ed896fa68fd0ccc4420d5eeef4d465822a02049da25ee13999ba4206cfbce1ba195c312c8fb7cd4200ae5678fa84cecda6f203040ff654fc0ed4e061842ea1e9f36b4022b57ed77b9c9ee4b9c86d6cd271415602277bc9b3d2e1ba28c38af6fd49ff370d5ad0f623b1ee86cdf688a5a9df36548b1d0a9e3c3d553229ba10b20b39dacab1c1e54d5e4fef96f12b8765a71d897d4f1d017bdeae141f81e5487c8c63832b46d724e7b7981aea3f83c4b24369ce058a19d5170aa0dd9edf92b937729e1908ce096ebf880f44fd55240d030f432cac710313fd1048be4ffb2375b9eb1573a5926043b81ada010b675af2184044f469300c032602f0ea0fe094020268ef3b3bade594148ed77186812aa0e0597adff7e986a1030507561c170d954b3f1e6ec2be1144ecedc178ba87caa211a31b7591d17bbb990203428d167c7fd3ff4c69bc7ec9290050705c0d039ee15950b235acda783ede6b0214fefe1db460db966eaef80784b60c32ac212d8cf29a54d175731e313923efbb030d0146925d09b1c966124f12dfe4109993f2fc85e15d86b1a103b3e8dd6c834c159e6e9a9fa200bcb6e84cbe625430d81f4669b5f4e5b8981d533053f519ac4104c7b80eca56cd50b2cc6968ddf53ad4a9c17d6d1641a25bcc71b7acc7ff1026",
+            "0305feab7557ebd92dc681e42296459665227cffb4a04bffca0b08e60016b5ea19020a71623d790b1a86b31970f55c9fd72e1aec5018737057e7ffe2261010aaf362030fbe6ed00a90576745c63910db53df9b6edc9072e5007077411a23fecad4d33c021a7735353c215e1216d7df615fadbe63e6e68a9807f6536075b5f396c3165dc62572b3f08b19389925b7f55ff6d8d8fb99e91e2d24e912e8bf02df281225a0382ed2656ceee3201783be6e06d7ef2e47b10f6108c71e659363475951ca578665026625284faf89b4a4b270d00f5966bbba0163dc2996044f52f04a8345671039030dec1777b87e99e9df0a3e042c584a420a26f2d91134adeb9bff01e3033d6acc022beeab4d328a95fe21c8c29a425ff405768bc01ae42f5d94c6c9c159bf995982031ee48fd166c96e9b23d7de5e9113ea0578178236846135ac99a2ef44225d2f8b021a8cecc09011de6d16c78f26efd673a6c65f327c7095ad680ce4d61622153353030694f90b696522214a64877371f6d45627ae46da778723f25fc8e786211d45bc021fd859c69b7b0ead90af63e5fff5f62d724ddabedc76b99b6e6f4348aa45dc5202027c55a06fd33dfb2241ba0a685156a5a2c9e375ad0de16f58f2af5ed0c1d5f00222395ce54f18a604d4b539be8edc57e4741b49b1b2e7d994c13ddfb25a518b5b1016b7af425eade5181b73527c80168e586bce4a297a6a171c3c4495a162956b1b1021edd0b952b6abd23a689aeaeec4a5e8a88e5200fc2101d8e89581d2d10b",
+            "03210ca414d8a17fd511a7a85b37e2f97c903105d91764ec15169533cb990bc39d0225fcfae58715e61ddb22928c26d4da99e07e46c13d8fc98fc5dd83dfddaf251a031ded4f9ddca760409ac4c962578c2fad8d234c3bb6a9dbb564bf62245baddb61030c0b3e9db98cc31e0c12f06df0e02318f6f63fef52aac837ae9b7c7a1401bae32303b6392a957dc74d99e9e44b07c86f1bd1cbbadba75fe5efa7e3d8a4f4758e073d4374262528ff6157b75dcbaeb2a20b48d0a8196fccd39a35d1b945b0f4091ba101d1595a8cb1fcf7604e169bdf320946b1c6d0ffdf41fb8bd45bd0a6d1bb02228e0e16f1d5b9be013d780db01b1998eb2075a7f74c2e74e92050c38d04548b02285ec93e5cc572a3106fab3598ee170a212348d98264589f8afd24516a9b99080203562261404e844103ed64d7d6ea0b0b02f17c96fd293a4a5acd984fe9c487d9022075c897b00c4fc5937ed5388e87501c6d626835bc3a9db8f19d326bb148ea4f030ea88bc71077faccf9face4fc5f8f57c2d788c6d36ee1def501c11515acee1a9030c2e1f661dfe737247d2edaa74c7fb436c83e92cf0b7ca8876b5cc71219f3967020c450dc731ad5c8621cf894cbc7089a7d16a9debf5605cb6c1aa6912d7206a42021857c2ea996498477214e9256aa4b57fa4bc0ab5e267fc30062fb3a621ea4e90022a9be70d8d39e2959adbaeffab503ae0845badb00aaafe24b8e1454c81b84a16030c9399d7fcb083803297b17bece3cb5a7fe56ab159f85c7ad36542114e79632d2738107745e0607675bab48296a26e396b18ce101285d5214091bdf423862ecc22085d51d2d2f1b2a60f6dee5a6228016d6bc2cca5d1b42a706fcfa36ea2049f",
+            "02202c219080ad8b3caf1e3ba5a9b7681d35e08cb26fa94ed0922e841e497e44a9032f9e76290f77c3ae37aa195646a6c37ea68a2296371ee553f96ba4953309b73b032e6ddf14acab9467d6d7b8d287fa712d2a53353902df68206ae029756c7dfd430317a6422d748010a06f0c806a834b052f7b25e17673289dcddd1341b5b37e2f7a0f4c04566b3573413ade0e37a56e673d7dcfd29c34764aa925566752a620fb3f064e857e885345fc3c94184514063ed9d4b22723a75657652e478d61745f7bee09d12c007d2781e6d80d083829f280704a1bdded5ca1b72cbc3163d739f9041f0228bea799e07cb897837741d87d316b64031957c0687b075e6d3d92ba1ffd20f80229b0ab30a613ae9cfcd7e9c81bda83a537e4046af111c6fcfc4473111e6950720326670f2d8e971f9d0abddb12c50470bbf02a1c3b1d5600e6f76297fa83beb603030108d2a9521f1811f7c1e8a5a9b858d42ad677b2f0a9a2425057057b787ae3790200f166184bf9defa6d772e9f109fad9b5dfedde897765fb799f60457cd2a5489020f603fd889f7c3d0a7634ed086773ac170fa10ca7da3a81fd5ca9401e8d27542032a7a5eb8d6dbc1ee3a86490c40972ae5bf19e23401d7a6947634fc210015538a032b669c96096207b50b24e1f25db7634ca243bc253cfda5ded45a0e497f4afa6e03181b7dbdc537028b1d5e4adc9282b152214f0c3c5663c8543c6ae409d7ad052a0313c76f5cd17ad81161bde7b27c51a09f7b329dae484e15ce8ad4747efe0a20c00317c9de4db79401e6348e84ae6f2f59df68f5741d8c27b1a4016aee38e11398d803258145861f9a8488206262097f594ee2ae47c1d926a544dad0e06d774bdda3fd10043abad20af1e1afb840c8214a43687a095f11b4ddb93f63f84655c92da1b30903fc4d2b50c10f4e619bcb1159da506123d795a712580011e0e3a45c6fbfff",
         ],
         [
-            b"7ec9675098a56979e6c9633b901de996cf325ab4840f87b08a56177eae576d74ea735ed28829fa47147122d123d0c77312dab5681e9dc48cf791125a24c6d70ea8559bde2b16555ed5969a58d85dcf24e1d1763323e9c18d2a830c6fb2e8760476b1cc5c0fb360c207176eced0bf1b208ba1883bfc9db7fc2c212454576076296202fac695e164089c8bf430a760fb526630bd77f912129fe36fbfcfcad75d0fee46a98136d4413c15e8511b4728e9b1ca66e3a0034a4110d13a81856e75ca02eacfc8f49c4695ac7ef2309c88c8cfd960e32a01b6c0fac46db8f8a89f24d005bae3c5c6334e71e54c8af1dc1dd62fd11852370707b939f479bc4b26a342f20828172ec3d82ec90ea58050250e4f5303c0d87e5aed33f84586877d7c202736704ac29eb1a2f0ff98af3baee4c7a1a2ee6d8c495e5b584d3062d20b5e0394a740d6ad56a14d6a041408f9a3cfe711f131055b5f51f6b303b113d2df74d714006abc2b797586d3eded97ea9b78db94fee8cecea9d3d334b3ccd40ac916895bd35f8a3e6825445a3feaa9430a00c611535da2dcabdf3f686bd41e126ff5a18f7e301e060ac8753a6f09d95baec75d19b54444573cddf42787dfd08297d555daa50be4e0756f44dd7f1f3ee1ee280ddba95cec14ebba6043bd356fc29b6a2cde39021717795cc7fb8414894a6d3151e25a92c613756470e56753b365f8a8f40d2303da94a18594f20dabdf8f636e34b668f84d2856c850630c46a2d4462768606a09".to_vec(),
-            b"ec36d90690c31ce9cd3b7c755970ebfcfa2714093b15768b6b12c50fb74dee13c6deddfc11d632cdd46caabefb222a19e71b2dfd8f733641118fe36259afba120a019d2698fa20916bd0c48207353e6fa0ad81ed8048284628c793e48ac4e33eecbf2cc250e7e604e50cf02744d5aed9a8823520608e0a80a42cef3da67315622b723f695a73b3ba3371b47a56bc0f1bb65a3c999ef945bc1cd59d39921d2c0ca914607361592d1e42decdcc80ef05abc6680b163936d9f31e0b3a22c842000cdc4978020d2c57704b54efdbaa9bef53ff653b6393554d27c6d5aab50948e70fa2bad4abecf48efea51327ba6d03b24df7d703cfbb286414471fac6ea5e62d67684da7fbaf48b9ad0eeb28cf42a41c741b476136390e9a48e01c1f7220154149502b49ce7b09e3dce1a6027078efc7318288cd07f20aad0ded14b8c5b46b5f79ce60275d247aa33d0f66f501ec1e153b6c0d52380925e27d6ab503056976e444ba07707f7d86918fd8cd4540b51ae60b5d281d590c66f153bd65a13b531e940ef6218b80e2b8555cb8b26c63ae8ac676604699e38ad9db3d7f7441a0b4ecb8382840bceb7fc5185978bb9cc6cec3069963f20bfe37dca1f32c3e20ca95fce60b020e3bc3d8d356a378fd5e3e9f158aab3ad6c1f096ac1c60349bed2cc7ac0a7a4a5ca77e846d28941b794137c722093f5dabc12ea3a95aa1819c21550e497d381a5f2a04b87d83701677a9eba9a07398178e000cbbb3bcf3c8b4d5d38569ff782447595d846da95195de9a765a634c7d57ddc1738972e6c05340abf685228303f4eb9ce78966840e727a746483d31ee2012f9c88e1fc831a1f5746889dea240f".to_vec(),
-            b"0ae63b34b0129fc36d5d4214e888b1c69a9d67adda6f58f80f8de74f4e4bc6311af8cf6388fc73515a19d4abfcd91fe93e2323fb6e09a58a51a4d2c4c5eaed18fea816dc01275ef534169183aca5c237187499d8e688be92e1492812d2d0c118e2d36ac8c9f4e1b73ac0425ed84eb447a344f00ded4033a6f10f9bfacbbc5f5131ae699ccc90babcfea39c3142c2fdc7f14315959fd8ed79bdc4a957f9d79f09848272796643e5d142f1a6f6d445f1c936354525af0d3c6731ac1ea66c622d0a0ee123b4f2a5561de8e60269a15e50f3ba11820a71db34c9f3212556d2b64804cad3a0f2cbb3ba67fc802a298c6f78a9cefb9253ff8486072afdada6c97a0a2102985979cfb6f70e5acbfebcfc260d4258a4ea9c4ad4fdbca9d12bc1ffefd13774921fbacf2bb4826aabd52a16b4b6a795fb31d25a1bf9267108e10c2568c754dac540c71bbaf8039be555ec6a9c58efdce197bcc34930d93398da0814c4217ab43d3d7083e80983e675f4b5b474b58ecffff7bc23fa568a7f7ce53e60c9704ff6964476e3495b1c84e3791df5772c2be858707bef0839c3ebf7b6ff29715e25083ba6d6715b0d354b9022333eed15608e730f089c196aebaab5c039cb9de94e4ecf630621d44d586d9108a94d24ddd33bb92c882ddb1cd0c419c9408e73d06ccea91e12c4418f3d8f8c89b5631861f41dc328fd110ab23455b2265ffb122e36da1f7205cd0f7c01a0a946d50d09aff1caa31d7a06d433e0c79498aae4682c2996a96a3762ac2c3a514e290dee08969c27d8c003ab482f50da2d1e4ab6517c5fd6ea8caf2ebf2577e0ab49418ea22bd99b5293676c3daa9c1c98b38e705ccf612f6a372284fffc000fbff66601e14e57842efb755950ab661fffa2d11abe990429a6aed8d84e4cba63109a8f1f6ad137247d2b38934bb234b6217d2601f5110b".to_vec(),
-            b"46778375200c54d5310e77154cb25d655bd92e19e247bd709490fd057d3c3047faf72b6df04969a80cbf6abd9c0d7cb4eaee6d1bd9b58497eefdfedd6d9ba6670841cdea0f95f5720513f9d22e1a48c2a860b9673e7c4c6578d6a64418bcfe58bc731c96bc54eba127b8df4c86523bafcf2e78ea0644f5eca2bb6948752327532e27ee56f4ba480cbc9589456a1bb67a0b281785d7343cd1107eb3694dac81088667ff1dc2caa0a7f63a071571ffece9cdc36bb19672fbcbd95a818d872afd05abe6f936c6b9fac685d2966d5da3409aba0bb788f5a02dcb17235986c99dff049ce12d3958ee844555d207e78d0959b6c73bb61bff3116c6202095a711c5cc7b14d57b04ec80a95d39a0e58b3d010514e8bca3611c8cc4ac1be68a96ead0491ca8c086f8ba6a00ebb81f3e0a00f8594d1b1d6844dbc67d172b8c228a307b0256026514fe748b290c8119fda02b214baaa789014829c3077e9289e824f49d867f543f7476cd52cab01947507716ca08b56951bc78832eeb15fb8dcad9074e2645a6e41ed562d10c375a40a8635c64b769ace2c0219d1750614e462cdeb2e3d63d444aef6d0a97a43fed86a4626faf6ff2a7866064e764f2d843593a64c3b0141e021855f4e9d2f5e324074ff8b23be461a99c5e5c0934eb0846d2f99ed07a985f6c6b061191dd4046da6640816a25948a22342e7bf601160d6b91e3a78233f210f06654f3b1a80428aa52157060348b34ccde0039aa528ef362292d9ed295485cc8a4629d6235e27988566345f905014fb216264fe7c57d85a0e26fe5d876a9645ecdfc5b214bb0250f606ce7be72edbc551b89049e5a3891e014b7c9c959691cc2503ec53d175471ce0d98473d1b694561eedc8816dcc10bf75264ae08b6026b36a8d4b78cbd3934601fbee870ee46295be958eefb8fd5f3fc6a1b51aea42a1dc39c09560e2fee1c06a4e86fa11aaa4fa3902a567879ac4236ebf49d7b9a5800e274cd17ba1e98c61df9c87b4e60da414b4d259b848cec54f7ae47572fe48708".to_vec(),
+            "0323843e2770076c02e40bb49bf995451edbed8c05364d76618c91873beb8f20c0030fcd9355e4fb09872a86d26562380d9fba7d7a21a1cefdaf1c36fe053782ec46021015399d0eb26ad192160459233f5882211d6201d297d343b2289b177893c33c0219d1cb10410978295c3f7d7f95fa8f63498c72ec3af11016ae2a302fe8a741a21141b9989e102c0cf2cbbcd9d0a038ac588cc4370789b84ff0433b8a6993a4f62d070f28e9613ec7b2a73be5d54f2053d8a4f5b0e593332a978477d83c9506630ea917f645391e96f804a30de6e7d1da9ce3771f60d91d579dc6b2d651af1b8c02229799b7c0449a88d138813c2f2805b894376ef6ac2d87543c8756bc78fb4bd60214914059682c1fc831b0468d977d66bf3291d0f41673870a6581b9fc33ca2bbb032769abd70d4b356e9c96028862844821bbc0453a94963dbd036251316fd3f2df0318a072930ad90ca7db83de861c4bc609f0b09042ff5b442d913c1659df998523031bcee1d515dcbb390ed9d6f743668c5edcb42a805182be8435c5d6487a279055020cf453585b5d9e6dac71dff4938cce5e1b04d39ae73461241ee26c4ed9b9544c020f24e6d0d6b68db19bfe6e472e005fb47eac6c7d4535f712290ecee0ee7bde8102196591d3e02b4d2c7d84485dd504f005c372def5b9e02fd0c312093ffaffc8c3096afd1648518d29196e31838f4000a6589ab8f27902173b7ad0fba25744e4992712221b7f71b57552747575172c0a75d79573e76a81f4f7b15efb833504a5c3",
+            "020600610f33aec68afb5d86cc0cddb094b2c6e0d3b7dc7e5e91c0dcec2bc84bfd022cdd69e460b7503ac7a3b9a07d861df48d8bcd145bf2b8f413d61490cecf423c0201b005fc7d3b5e8daa43004d1983c92d1791c0740be8e051f8b65c9e00443d19030fecaa4f59d9c5cd9ad8555994eb1319be24c0ae0793f56f0c2c3ac4c8b343721c15f7a190db17406959674b6c8b2e4e16d36e183630f13d004abb6eec4fd0f3024c5910c368df23dda9ae567ca02757e7a016f968ce5b0f6432b23b2a33567a09d666a17b2002c0a57aafc9b249d3f31313880b62c2d0874d62440095839acd02055879b34229da5c3aa8f75371c56cc1f6161b90dbfb85a2b69ff6d07986432b030a1701755d1e2d1bbdd459f2727165546e91aa8350450fa5dd960eca8e5f024503189857858fe0d45a6e72960a2a94f9f024ee9cbeb7256b976f31de0f4808723d021150b8544bfe76b3e901866666b34af782acbfc72a7cf5b623b8ca0c563e230903032c25d9250f2533c82d34911b47be7761edbd153e8ec2e114c3631b6cbd528b02115c8b12a508aee968dcaaee4ec0f20dda0747dd205e260bebc50ab8cdd049540218a369470d530e4984bbe345ba430d29bbfdede7fd858d8c4425f59c034e4b950222215015a24a501ccc6fe43537c3dfb37c55e5b66cef36a8529104e9625f723b021e450b9c636d4262f7ce03d218f67f532e2f79b75de91e7a0a63686ed3e33f2c030588e56cce651973d497b0dc169894868012e1e8b3821d1c5f0f47c05045cb010cb2e90be49ef4aa7987475a3b4d8d8a1081d38ba40adbaa4404e0d9f1e925a01e070657003b263441651b91ec0bc6814cc56cf2ee248d12d0c7e8e67acea296",
+            "030226bd67fe6a0b1ed8c16786b7bc13f0109fc6afe01e38ab275c29aba46a708b0213835a5f8325b7734ce5520563575ae05b6fab7d1ade0183e74147723c3e93e902267f8c59fe56458eeeb960bd62e229cc5bed70214f6f9e11e77b15d54e3650b80312aab7b3acb4374e00ac1b32bb28e45e1f1dee7a89c506fd23d86c3dc55072ad1b11914d2679b5413f57f6ca28b18864e84cf9614bef81ba2995cc267347c74c27acf663924acce65bf3a3c71b4c163c956fc24e3c0494cd6a9be6882234a6c20cfbf51536e185e36ea9653d92f4162415ca8e61149ac5e2d62c18fd58cc8d71021624de335ccdba03f565ff0b61ea9c8176241f5a89855fe8f7ef364f574bca56031dbd037f15abbbedf91c388c07c1484ef303b625e168cdbe92af202e107ad227032e6b0f9221c2f02f1024b84e2577107731d056d0205e74998feca04790706546030be7f01daab66fd96cca4e842eae391018b188c173c44a673a89c31bf0467fcf0319a5c969845eafbbd4154920a685ea22ca98b29dd253a0bc7fffbf7b84368cc0033022227bd408ee6fecf63a4b24e839874f94db1abfb707d4d88c152800ba5159022a996bd9a1bafb833d26e0ad7e5bc941b0b34e376d40b33eeb7b8d420bf57997032c8647159b38dc86cdd7122d647cee32c0df1f3d81846729215da1aa7620829403026f14f26a40ea8e6a3a25dd4d33979d4560abe158efb0e4b4519b7f003f25690300ad380765ae97633c705b99bf342ce602d85f006a2233ea02f8705bc73dcbaf0216a49cfb2a5664fa3d445eb801ab89e32de15f571aa1c581a0045a9fbc791dc50308ce1818bd38e22a19faa4a4c6439cf31b55bb89816ec7a17aa0075b456bf03d2a3b0b7ddf07fbba6e51c8b55cbaddc3f27cb3dc301b1c63667388071bd9327521f8136266d2f89168bfba5f5a66b620c8e7a3770582b97bb0cb0a2ca15f58c6",
+            "0209cdf35fd00efc4eee28fa245d1997b5c85fe2fe352489e56aaff4432412061c0225c0f3fc618cfba6bdcf5997b8092ca821f94a48d03e94a521deebe23ff1fa5d021eaa7c4abe9cc96b9bf29f22854621d171c305efbe8cd929773e500db36f9723031fb76c2fa963985a1fe6872ef926b3fafb78ca002fce6a032f26cdb583ec65680ed1513bb6092fc99deacf6598d6d77b659480af106ecb1e2c1459d78e0c999928cbdaf3e8930d0e2bceb5d28fcb2e779678aa8be2aaf64328a27a4efd4c292e1572499505d0ebfa3a8744e4fc7e8c6a5e664baebf7a65144612d2101524ed22031be66634aaf98b400f9061e69c86d2cadd63f51f19d4ad606c2b97af7e62aa9c03191727f81d4913d5048bb1504e1ac3e45a1fab8788fad9ccf3bbf74ff8b2a141030bee34a4598515809f0dc034b59f5673e3a07af98492b7879184e857193a1833030036c8ef16deb55c94bcf38042041ce3397f746e15f8b8e9bf3ab2c9f65c48980219580025c603a2548c1d2dd2fd132649c971d9d4e97a805bbc0beb82511c1178030e793bd602e83dcc0830da7c18a06667e45f47692e2451dcf2c8dea0db7545db03155e160e400cd38b460e6a421993aa4cd29279b10436a1fef4dcee2ab5e0e41102041d92eda8cc35a4087e9519d33086d12702352e3228a81c327f654529fa1f1e030febc4a11c1976732431914e05ed60b799583f690ab0ff205ac65246fd639ea903219562e7e457694f2ddf09acf00ed2a21ffdebad89388f9c92cb674f00c3bc16030047422cb5703ddf2d151010a78a888fa92e011c4fa7cce891bad59f7d17b0ed0223e8d74b41320ff3597b1b1b650bc2cc6d62969d0b4587c0aa5bfacb7cf8fe83021f2bcc0751ceeb9a3057e9811b0637c68763cd9f520c6b8c4ab1d1b1cad3990b0228dd8e5369ecac58befe3a6b28adcc967c2640e95bcb0f09f46542686387be5e138286bc9c418b36707eceac471bac1d895ec4937683d0444caabb049006bc0f2013a038f891918b4b4b78b9d86890b65acd7413e40b40bee0918be77f77953c",
         ],
         [
-            b"9cdc1ed1e83ada128d182d30575bc437ecbeba58cf9faeed18fc2bc359c6d227a265cb521b3dbe2e289e3d68efbae1531708fedb6017d4fbbc9b747ee5a040565af225182509cbacee851cb3d2fdf8bf6601419b0ab262238ccd549f394b0a081e4bddc747ed1765a75db0ca54a05d8eb54fb87c7071849d248ca2df4c75ee50dd7558fd070b1ae72860b2d2c3e6966b4f0863d57ee93ebfc25116bf949c2b047e85e4b028ee18f0a43b8ad4f7c38f59ebe11a6289203387c01291bc32c99f01ab6fab5b6f970f0e9d01ca4f174e7d4fd5056e964811838831fefb6671cf5407624e47fc555120012295024d1db3972db8377587f8690cac55ef27967ae27310564cc99eb0bd2e935826d47275c7d4878f836c970dcba0c9468c82c7e84b9912580d16b60b0f5c67650ea9de49f18fe81bf4bd85dcc1a8f092c672baf8336335cc927722200f0e108c8179d01063543907d699b102370a93a14995d3ead9f46dbc133563e7b1a8ebe2b96781ec00ff4a22791ef0f4fe2508268159edd8e5b210304207771e1ac4cd69efc58bd638b039509f9e39e18ca5625fd6a91d496b467f702b2a82f46c9b42f9df3680eae5095a11dfa4724fc7b1cf8dd52ffdba97316ea4acf5617646abd8a6179af60b7e30301f8363b6641bf2a0346378e3655bf41d4c4b16dedfe7a6600062afacd2983875b5fb2341e85a0defa33161e5f93c9030461ccc252c804c531a070d7dd748f0086a67f5c85f9049d38409130282bab06c4442bbcc54e9f3b0c5e7f20e1421060a829aec404367e2836fdbbd07febbef0ae26153deaa93067d669dd0ed2af14d7c8a2c2f66e973660096420b7d5e570102".to_vec(),
-            b"d41df673b989900a92520255a001bb4a4d1457ecab5f9743a956c2fed93b6c517a761e2b266affa6fd86cd9d1823c9face4fe8a4cc70c3833f4fd87f415f0d11e86ce86e66656c6cf65e79e42b6c5bde347a88b77f24f4e59bd8ad4c34179a75f0c6f6c27eab1463f7d6a17c90c300bdd5433ea8afa0ed45e1e4ddfb2f1661746b24b395fd3250d3d2eecf04995c7d19fd1bec259502e5eb5da4ae1f387f8708f5780deba40aba64b6f99b8a9a1066e803671fd4fdc36f67243e28bc50c75d0993394321a82514791c929da1f30177a5a203f03028e5c2d0d1ca32a2d8de0f0e8405b523c90296a7a089fefebfabde4a0a499b543e399c239e47977e4317081054ed703b4102c1e6d20ce84c614ceabe862842a5b0c22209815deb8da6e6100c626ce0d51581443bf83d9bf67195f8f6420ac77884cd8ce018a6594b3777e92608bbd26a10fa4182bbdef249f619e7140f80cecd2478827479912478eeed1d48d8ffbcbc10fdbf46e50c282bac6e53a909c2947b8cfaea2bc1d532538f322473e6be6616dde8f410c4dc05a2c5e57b75a43b95265e78ee710ece5f9371d9eb3b1ccf0d135dee3a5f0d91064127164a7965a5873b64de599d296f2b469160ee51a2be9b94772bcf30d6297d75d279f30715629479785eae3a0ba2ece3c3abdc49a0f043f2577d711a3ea2bcae8c16bde426df6c5d5cee246951a955d708ba716f0e6d72c0efed05b7c2cf4f4fb7743b66913008d22b6fd2b9f671a9b2318f162928a6ae69e1367669dd3acc71ae54fafe39b36049cab12095e5d3d33300ea931568afb3977d0dfd8f807a7f1fa9145587dc6dc5b692b1558dfbe6454a17edce5a795493c3af7b34f2930b09aa16c3801ed671c4b8493b7576493cef0d09cfec0dad690cf5ac490c7b3b97db915253195a47e9e0a02eb168c9e10ea5cf30b6210b".to_vec(),
-            b"06ab929eacb52f35b0edd375c1e28c9f3366b363b1027123b92d8eb0e92d4b1eee687415b1a09eb55874c5a8149648361acb6f33f019684690a9fd40d69d3c5a562bc4f17c68a04cb46c2abbc5bfb9474e8535845a32dd1fde50cdf387c94b454ab96a379cbed05468ff7f98f85cec7947efceb75603d009f5a31937a8ed153ac4cb9a12ebc431a65301795b4e7060825bb33a451502a0f7edb232de205e590d817c780c841295860b6028e4bcf35e14db6bb674802c5b39f9bc99ebfad19205f4086b32b535caccd6bdfcc65cdb6a9240abba199e2ae2b8ebc7d9e9bfabea0d6ee4c49e3fde4da917b2b41f3ebf7727d0f29e31cc5d33ca9714f687da4c315a3617dc9b823b2210b8f9c703c0a81b5ebf6fd8e72ddd05cf66c728864c1ee946223f16528897de08346270a163a40e424b9693f55c3a36159614cc0e51ddb61e08c6075ef3c736ae7f070fee3c043bd296fda6a50b8de597b0e503bc1deaa95d0e8a4038116ef803aa40b955db4859744cc43923cdc9a90c233785932ecb0403c26c0a49527fd4ed8c9c4ca30cf230ed908926d7c35acfd9375e7824a8603b068e517e172a8300b77b27f1013ed286a23ce6d7afce5f6215a8f2b8c7c13d7b2fea527c688c46bcc6df5ade1f95e4f4f3772d6d5f77785eaa703a621ab05d194450a33f223fc8937d21c936f2d9e6f4ef58884e1ad3107c5c2e459fb182b6e7350466ceb8eef5286473629fb96df8390f75f18d58d657c5ddc7a8c8dea250c549b273bf416e287973db5dd1c1b1bcf2fe1dcf57f32ca5d040b3206e4141343737328e8a0887fa748d597973be204f96c9b395ac40e6d085eec62af494981a215a36b38a58c0d63d28e075c95d35a080fefe00287533e1c2cd31c750ebfdf6112c8c6fdba47cdfa5c3d65ef288f43bbca9b8e7c7ca581caad65c551342f8689766f377860a82b0cf62f3866d716fd74d366589ed02eb4c1ac2cda48f41f1c3240bb05c7d9d182a3a2d2686049758ebe45a153e137bad00a3ddd0351c0a862fac03".to_vec(),
-            b"3a1454aa90b807e350a7eed14159aef5b1658323a2fc19d4819397c0f0cb9b06ec4cfc8b4ba21f9c4ed7d54378bc6ea68638344179f50bc3f0d654183f6b3743d61a05ee13614428d048c34caed8e15c780443cf3c0bb69e4e720d8330ad47376ea003938954d0863f3984d1b277d5dde6f73b4fa9a002cf81f8ae0b6de4f66970d05dddf2cb972771f221a895b3228105bbd9279fbb82aa215253c144a5ac02ec36298cdf9278cefa0b69332adcde7d8a0b12f3b07c9628550f88a2e69fb200e34389f3712ecb2d32c0f0977a85a4f33d202edfdec7ba1c2d5016c88605380e3471b8b86982fb4719aa498641e3870a5689faf0b8cddeaf7976c2cda9e9095034c2cf3d1b5734beba84c7003309981e49ab7b544f8ec382b55ad188146d231fa6e125542e5fbb4c9b1219d9b90d8d3b16e30b74024ebbdb5c1b8ca8084c4f53e2076328268fd767684534f97b745734c901753ef3708d8e40bbfc7f669bff761c77f38dff0cb69654decb29a2e8e3d99d920ee03d0639ccba354682741ead7706d7cdef25cd3dd20ebc283fd09a6a0f1f1f41e1bd2a49458913b1ceb26cd768349581d6b0606aaf1acf1fda7836fb66ccebe8787ecd212b2a27e9044f78d70e108c89ad1d2d4953083d617c97bb47eb845775d494bbbbe998e12f56d4d03d1e668e466e962f6d9c6645dc6a8ea93639d8c5e963bb967b17dd0d34747c5e1d4dd463e201b994088fd7ddf6129a436459bcf41b14607242ed99fd537c69592d22d0d07555b8ccb480853ceecc02dd153c9c2ef0f2187811fc614cecd9c0586c5e187101f10aeb6288bd709e5728584c3ba1218592e5d0a88d942df801146b9d48ee4ca2b01381f5046b05eda95b2fcc1ee26cf97ee55bc1b49edef8d71e070e199cc3db42f295b905b08312ea944fd4702463cafbb4e133a193367e8b886aff4e0ead3ac0d722aed0624a45e9314efbc2619a476edee0cb4fc9470b20eaa9834f36681ca232fdb4dd046804a54e82e95c438e01f5f277941bd92bd5921f07934d23cf5ac900121fc8576723a2e753b90c0253f18621cab4cc9815a834340fcd0b1829918391cc28ff6d420739e1c3d7e943f20b87a6609f30354463d9ff46dc0c".to_vec(),
+            "031d92db9a7481d790f5c60189d6be5c6227d34df6155f221622f5642d2a244ff2020aa14eea2ea027d4afb8faa9ea4d76c6d1d13b718c842d9536e201bc89768a49032cb7e4fcd1cf41c24dafcf2765f588c1f5cb174c08dde28ccbebb0715b38404b030e5efacbb83c5c33284617e26cfd4f36a6927e2d411c2a73f308ad1e006d6575257a1e05a9fd2bf037c76e4dea264d89211061930cddd95e8ac6fd0b65ac9e702b77a8c61116352939a70edf6f8dc5017eb871f07cb62741fccd42852a848e681ec486ed2d2ef0b6f5b2827dfcbfbe05d631dcb9f6aa68d7d516624f985c60050218a68c46992d794bcc8fc246243a3ae40d8c0808c4a36cf11d91a7867be08c250213043c191942a51b1f8771913215e8b1e1e72c62cb5b02bdd875a810baab578c02132f2973623225cc36434cb7b22b9aea63d707df468dc70ab933ae97eb6f825c0208918cf528775f595f51a2d6be1ae3411b56ba6d878580e38ab4c98b96ad4a780203d2f9eee89dad6f54cd851e24beb3db60f984dbb6dbb946c1996bb40cab9826030a1f3a427452de257e9033249e2b035bb1168d117a8ccb38271e964446336d7c0210300fb9e4947cd85beeed791b80afbb752bce74c4700731c4b18eef144a27ee03097ad18382c44b3e3e81b8a42b5940db347638354524a38f647967e2a1807d870320334f25f1e9c2a980a2533f20d8aaf6a9ffeb111d76fb2531f74e88e0fc573c031e69ddcaec42cf2bb70a0a8d82f5ed21608db5ff06111613ab8be9637347d47914444f8dfe259d61101a864182f23bdd0c31ac4a857eee4d6eb23391b54cac8c09b9b5691008a021afa52e08bd5606d2d469c0ea37c3b854da2b9e25a142580e",
+            "030ade04b7824677308dd18c6b8122cae2dd7e26e9f4f54ca0c15b7985ad2556db0304c5b2356a9a845679a3d49c0d5d43bbef411210d16258c6f54f4701249428380223f0ca300e9220ca40d4da4e8390284b8397899ce73a2fcc9b04b51260d0e9cb0218a6aa4e518a54a7bc8e5032922b05aca645c74c69a265c7b85c26af587e292a1fa85e112fa066607bc378819832b856ad71435ad1ef0f5117adedf410b97de31eb5b9c89442a3e050730ed2b6d82f4dc001b5e15990b62443d22d2d64c3d4f9268cb9c8c379aa075a684e73aece13b42fdad78aeded9afd4879acf2c6b04c04022a67ed2596538b9393247b01e3582e362cf25126958d4f5bfcab6f79370c25e6030d74b79fbb22cd6b1925031b0bcabb4dfe27010da64e3d933ddc267395e80c71020ccc767e1b287274bd4070b62d969bb8db094d437b41b8c72cd2d72e20931f790320442dff38138a761c4a6f96ce86665569812d4b51723ee61724dc9d7f1adc58021b1ed2dd42f7b3d45570a34a7dd1ec87d750268c34abac99ca7480ada822e4c4020c75ff5328d7a13bda2d7bde8152bf8942f32ee9b960c24a48246c4f7793cb87031e6b4453085a2e8b0706f8e57a3974f7c07e25c8b20658c52c9da169466e0ae1030abfbf419db9cb00216550e89a5c74aec0627f5de0c051f120ca38638623310a022b2869878f93c33f5ff7467a36ed01b2e9f5cfda79cbcd4bcbe2ba4f816b62620206beea272123aa22115657917e3d17bcb28db0b04d9b20ab5aece833680c97c1020d8d5a713f9173cc21ee2d99f11c7f6d8ee50bf72f530ffd4781bd115ba9446d020e1ca9810ef994241810ff64b416634afbd2dcbcb1ffb11942a78f1a0bd362031873a12faa843e2ccb6206d4de04c540b4496cd88e99fb804b67907ddc5d455f2069c8ff89f6555c9d550d9b164871550b38bd48f27a01e348fa6924fe816157",
+            "0223bf35b8957701af08341f3cb9066d9355f6ec674edca2baf928f0fa7a44c83c032f20e3e7b6dc447e8e7efe637bd3210317b886e6d4bbe3814a27e8db20492505030a92b8aa861f50e960048ce07acf746a05e47d4f9ae30072aa5efd72c622d6a8030f48c3dcdba443c753afa9c35c2d2d7fe80dfa79e3415675f6cfb45f5eb475d208a8fc2f5a5599cd211e183c3c7095ed76ac14496588d57a29e5036e14db8bc42153927eedaf86d2ebb64d6141c82a11015b0934b45b1d020d25b86d348f9ebd2f704da43e54d4b266a2090d71afdb30b446148ecec1f0d860526ae6ccb002b70211e895a7cf3924569edef640f6edb259f4a881465208d6a66fc9f76a099c096f030f9012260ac890340cfdae845bd508e6a0706a9dba9447cb23a24b1953427a35032adac1d47f3be7d339916cd80904be905b6b5318097e3a5dce3a64384556ea2a021b94731a8bb4052033da41b520bdc9288efda7ffb62832d7b67bca43630dd34c021ac94cfe4b55aec7ffc0ee6e76755489541fb5f15fc732d377da6b7cbd71c90503177d75fc5f4776b77ac6eae9fc3cd660250975fd6204ce65a41f52deb5e093050305c591e752e0ef04981515c9f31b71db2ca7c7869e410c67263de1066c6af26c020cf1753ef4a80c10f81a367ec5a3f319626deb8d37e8f2a3372d4fd4491731e6020add77a81d2699b73af1a4ed4ac754492eb0960f9a0ad07352e103b1c0a8f000021f9e9445834b3e5fa3d4969ac17899857ecb33f2b6df9b1c7f9da226809d1429022d0932d1aa4ca499cf30cafe64ebce4ed02c9b508db06ddd35530e766581e51203172aefc0f0f5b2925c6d15e7bfb6d019afb7de43140ca9bc2575ce1e8ce888400212ce7eb55cf9c31087b623560a3f34918436f0ec896a1aab3a9a537e3b35d7a00222e8453d7bd79ce88f0ac240204364fcc5a892be3235d96f57d07898c011bbc91a0a25e1e3acd6e8c4cd7f4585d9aef15a444f1c3dea51f7e343660262bb7e74248ec2dccb4f16063a629b2a50a0e327224d674bb4e1b92fcc39db4c1da6f972",
+            "0222c2d34c8b3d8efccffc3420360dca68844b6d7c64d41012880a833d0be88638032f2baa0ebd2aa7034b3805332a94e04c250cb8bb4a9713e435245fd313773fce02258728e46319233ca64b83cd32ff4e8628c84cb7de48efbf9192456c431a5a7402165c2e6c110cf2c77bbc6e342a117a346b63778bff1873b034785685ebdf165c1c9ff65f94d59c8d128625725f62c8f4dfda9ea4b293fc45d73224c8b8da96b01ef3a4d178cffd4e3dd5c092e97f08d02e4ed9e9cf2c8f9e920a9fa44730333a190a2e5bb5aad3177e8c7bf717ef3bc4a22cc4a5a8ceaf1721464f4274c6fd40020564a066a1592a6fdd6282e97d1bf24802861391c489e3644a7d2d02248c54fb0227c5131423cac8f69ce0bff87f3d1786f9320f61673936e4a35b61b8b2bde5d6031d960f281091132d4f6c2676800f885b167107b13e289f161dad67feed773829031f7789ae1d606efbef9c6b2c26db44350cf0d3ea9c2d53851ec111f2ac7ca5ec031f754b817db9a8f928b08353ca47dfe3ec716de8fc391ae2a11f68e325a8819303225476516c8d24a57045d22deb38648a5dd90978730436dc67d4fb09aa762a86021dc70492873676141216e6fd6793c1923d62d11b53c9e925016a84d7fa431103031fe9b9dc793e74dc9dfbcf829cdeea171e1bd331d85434343ab202faae67a3960318c2e6530eff2cfa05f4f51b0431fc1b9cb4493bdd66903cf9a3dbf049f65ded0211c5b3c9e20a9012714367c819554926b9791db593f8cadfb3906c18fc293ae80202e39715767ac589f7bf96e9f3c346fb709e3f9037289f61782b627a98260417032e06b321f2ef549ebc0a5ec71dda6cda9ae139cfb8cbe61258195f4554c418610201abbc34f4f47a8d7f2354efc9e6d90a5e6b1faf19df37076d907f69e3c1622c0222a317b0243ce4468921b0d3a29110d03a3339daccc33c599379b76349c83a9a031ea9e4e9ad17e7362282aafc6bc29278cf982e1e8f17149bd15ce62c2763cf65030f235e9fed1b885f84a682cb8749b3bba9c155a7504e366f50bd82b2e4744b800a9c41b8459e41cb99c13fcfa7c8c91f6b76372a10b0b457d3f0166742cabe8817b2c196c2f21ac94126eb04e3ce7ab1c26e58c7723974be15d98582125ea927",
         ],
         [
-            b"9acbe03bdd0b3b663d4fe3f7a6ff57060042ea418aee184d23074ab616548a255c67c87d49d71829c29df85b0b13175469017f162ddd626abcdf49a79aa8ab5e00bb83bd1813739eb0813ea975f601adb82064cc7ac7ebf36874eee0c7a90e2e48529e7cb384ab4d36df9b3f8a66e953f0f2ed224fbbc6402cd4e56910e8a13c6ff62d272dce553acc040f40c94bbbb4ac827b91e35cb2c7db0a6cb2b8073302cce49751d2c97e89eb360d91d6ec6b6272344d7a1560a12c5f6bd9b129a9a00ceb8e1dadcd01d3cc4fe294036ac997a9fcee4617dd39480eefeb7be5c1e3bd057272f721bd11352798a8792ef7b41204ef2450bf7f353eb51525c4d3c7d7ec52dadd8b87614ca5e9b98a34e6fd79fcb35c40d0420bc378fe0bdf15c9630cc10264395b946ae0466b8d4dabf4a11c2bf630c72e469c4f347ff72c6ac7e5767c6e8679ac78a51e79de35c2d02da8dab7b7976e62e900355366a2574f008302df3692bde4df4bd157410f515864a3147eb4b28a9151f3600b710c47ff6d08b3561678c9d16cb27eb8e1a2909d80976a295286bc383de6816c283303d41ac3b68e54060f9568114ea89040a50c6e7b505d49359988a859e0c867e190d8e58aab9a052a9a3ca7d72d1ce088a8448812047adb70928c1bc7341ecbb514c074e23a440b62832883fa6db8d151cd265fd228bb2337f0eacd7d001482debd626f4fee815f3a79aa848e4468426598babc335d0875b435981b2348a3a28b70b536e3f04738fe0553207e1d5f1c0f97e3164d0fa3eb37ee198905f2e552ef3e6e3083b19002f866728d19ca3c918d18c5540a9c1f930767e63bf79d038087a9b92a01f94c245aafd531cd2d7bc20e2ac0ef9a30c5cfd82747afada0aea682ee103a88fd1101d9c97c5a2859e728c18c9bba4003d99fed92cf6b9242651d75348db6fa0dae03".to_vec(),
-            b"7ed1b8dde36bf733666dabb42f65de0a3c12fa02b2352e07358b2b69b89a3445482f33b6d3b964a9fa06e04263e25d9f9e74e3789abdd366b55544882996da1a0aeba5165d4b52e3da7d6526a390518e9f655fd0d6e20013f81522b3f02ea31c1a1d400d63b38af7feb1c017fc068a3e58f0e15f5f570ece29f004f420d7c556abbcc01498043c4ff66fc528c8d6edf5536739f08497b085c711614f90c6cb0a99f5107f862fdf7b1801a91da1b2aa9761d3de42d88d1187d2b580a4c1f7540423e35f5cd1255bbc7bfc2e8e0c9c8a778aebeea1c0b2d4b658653f1aefe3d207a84e421db6d1a6673df8432173874db876e11168a6ca90ab32350f667a26ae577640d0b43fa0fe3e2a9f20762d892fb3ec6a2ebe1a9768e61a902446f90bf17de893b31d9632afc319c206d7df551acdc3aa2cbd079b24c2f78f818e2b66b93fdc683723a55c6a40f7d65002333e66696d122f9b15a925cc17f9583440d7bd3ec8194da65cf2e91e1cd1fdb1f9c41f9305230dc423c96abe12d967ec582beb3322d94bd1b470c3c4a2e872e9be876b19c15fe66f9791e533913a684ab9b0084112b6b44453d3404c3f6ce7d037984353a1229dc731c085541b357500bc9cb85b5ebd423d136f99d8d9225d4b56a1d4f5f7dfd28a003c640544f5040182b54b0f0a43c78c910b8e2a80393add806d2cf13cf05a8995a2102f5dd6bee34ae01b28143097687ac3cd4a0cc4fb4068353ea6964440f04b7379e6da266d3305d4d74d6683fa2180d29cdbd09abe5c9f67fa29a4d9d6ab3d90683cb0269df3066f4f67d67fe5384f736a4bc70c6aa22f7dad32cff805888288eab9d733c97878766d62c0e34d85c25fd7b8e30f506e038193c5ce92794c2932f47a8994bdecd669327452a0f67024fb9f719a42c6c4864cfbed4780a740a2b2a96f8e48bfd34d8005675b0828f06c1b1e5411ad87583810a0593377114b10711214e63de2cf23d41b01337021cead358b7ef8472cd2c500756bd66cce0ab6256146d79111c4ecee5004".to_vec(),
-            b"f2947845b1d358095e22446e08c08dd4254dc6df6b107e65da8a22c744f2b23b2694658c5b3bac812e397fb9e51445064298e8498eef2f3a67940b0239fedf627a2b7a0f4834ac6c59979582b122b8ad11a42d9484228e13c8702af9833ad8261e02b62d6c63aa8638020d57823e70419dc9bcda09e698ab8a444c815d8038373691e67cb9e58d1951d6e40a711a83376ee6e03af83c3ab1dfb9bd69874ff00b384b4e8896aeb3f4443b95b6704281a19760f596b1063c328fe9168ae9169b048744f3905af3c67b6679e14d5294b6aa8c671edda152e4da70fa9571fa80660d6aecc9767fcb8ad80fd4957e01e831bda17ec76ceefbd9de562288c09c72486a3476c1eb86a63701811996a309015020ce408d9ed4ba7be0440297067813480aac45a9d214f3aeac5ca96463a96a3a15ac89b4e5f75dd0c1f12733809df11f73108d97bfbad6837a4bae88ba7487d13f728bab851331b64fac8bc6e742db1429f6e4819971979c139a208a0edd0c17f96afe664b4bcf39df5883cdaf7c464423aaec059d0b1599fd369a16ba72da2ca164c28c8bb313952a366d17ec241d5a6772f010c2e1fab05417c234f5ed4fa822c49c6c0f3da50092de007d5f5bd4825e7670bed3804d165abac1194ed8cfa00cfa251add7bc2a41cabf06b48de2875567251c31dd105829f0f3218b0a39f30bf8ff33060907ccd00cf68a1af1c920a696cbbf49fdbe59b52b1fb359338b007e7bcc6a4099da9a71d3d9331d5e5b8fc192a28083662c03f4234b2d3ece016c19fa40d8c6993ceb48a7225488e6f6d1904ec460b09d3b7bafc4aef80f206c98033d4127702ed1d3c14fd6175d0d484cb558852d38e4817e9ce4d45e776291c2c9d26217b1fb6cc5ae2acf2c8b6b3902a0b023b1ade6a6140f829c8fa6ec9772d2034f28f8b51a91daad5b00772d432ba27363a51fc73344457e8320388cfff8fb142cca55ae18f6ad157de19c5de75cb7230ace03eb3c0d23885a44820eda9cfae377ff318c3edd25c5fe10364882fc91d236c80ebe903f0dd986542762febb2f055a7c23b863d6064a191b33a3aee1c023920c044c0ddbc9e7d4c677b68d584d88d16cec062641e101385a2a95d196905".to_vec(),
-            b"9824fa7f8dc4e219fc9dbd1d3d8c0a30db28b98f6d57c9601a07541d5a6a423eb008f517eff83f041a65cc9c7795d77c3528d1424f77aee2c3bfe78e4b5a08705e6412c8c6f64527f33e6bb81b9d2c227798321d80ea4bec9cf91e50b7b1e301ce79cc816cce3fbe6e9bacec0127af5ff35c84ce9fb8997df913ab74b27a26278ce2bd133e687f691e9c97c065ddf051f13d54b6c4781eeba59bc0651802ac0b676a125d6c0d7c36bea5e430333c533bc9447f4823afb555154fbfae66ce460295585fe54971380d3d0ae80ef179f690600dfe383a267539153c270f54278004c6945290ee6b2b3b2e1ea1cef8c7f0e5334a1801d6f4be804689dae7f080cf5918d56abdff71fdb1918badd77f5c2953b1399d750e34b46cb16ae6eb0afffb1d5ad902e520294bc50691401637fea4563b3d800d2445142061d40586c6259e6de608b25f45ac84553c8ac6a35f9605ca84ddd8514217ba76a86800bc22090200e460a1e1f6f708135b2c673c913b732e895ce592627492e76831842fdbfdd73f38e949c6cb2201da4800a20588e7b6ebec720b899d63761540d461e02dd5bd484225da7c545578b9e091017e58d6a98d5d6b0331ac05378ed4719e9d2b8e31295af78863e169c83f50eb014bf1d041359ad3d2309504691e3a19ffed4c02251c30cd70a6e5e37dbf16414122958b1aa532a76fe9f03885a2caf1624aeaad916b627df349744ac4f7012c449b8498fd951c4fc5ebdaa6197be557e7d7278d216b922558c4f0fbaec68613c08b3e11f6dd9e1fc7ad88ff6f2c1ac8f6f733d0683e7e15959a5ce7ed95864df9451b3087be383e1d86d76c80193ce5d4b2af1dca740ed1531bdfbd09320d864981c7147b4e601c58fd8253698e303cb4ea694b7e060c589c8222d653f6c88fab9d7d1911b9942e607cfacabaf54bb3b3b9c499d5638a0d165cd3b49c7af74975ee8b570e46f4933e2f8ae5228e6324d08971be027a78ae9664e210f28b1a90165b92f0343a0d0837c0ba3b0cf508c26b4662b65970a2c170f0cbdf31c9c392ab7323620f36cc526c07d47ec489560ba2582d6b3038840ebf2b74ca356c81173302c38f40484f6b6b4bb0ca763b989ea0081d4e5f7ada8b4a5d989662f7d28009c13348e1a718cbe1ac054fe51295a91d779c5b880aaad97c2abcc5e05cfb9959570f350ba951a5ed6a37c079683c43582a5dc1fa0b".to_vec(),
+            "030e589cac6cc092d567473fbd455f3a9c7a044c6b6f521d36c2e0314619368bd7030d6b9edad98d4fcaa0b385195ff6100efbe581913a3bc3aeef961912737e716b030303855a9f0fc672dedd19eafb024212d8945d7a20413937c7b4c9ccbdde7fe4020f3532aba1f0f7a06218efe4df37aceb93f1efd761d28f85a43b154c59e16d580188185dc4d0e6ac50dd1b43dd7a617bce357c23f1459d2f8c10233a229791ac17a47752b0165c1bc89742a4805a9980c227dff1cf84902084b8c0f0c14ee15801ee374edfc2b135f38e1440cf11790e7465539a655f16a77b97b7804512209803223b265a4484aa61e3993b20f937f7119f82e7058aac84489a34fd30d4dcf0460226c0a02b506713df80c247a7c6efc3ba0ae2623de628ce883f66d4e64c483c190300f32180bffaa5e2d0b2f91abbdae2d73b046621c551ae0c39dced136779beb2021aca05791f5ea1783e0d8754f6ca4555c6370dae020583a6de4773fef302b1400302f933ebf2c152d1fdd13380ffbc01712e839d74afb87842bd398d32705bc27c031e350a0561d5e0633b8bc5d561b3a917f613a9377ae7b36befa8d5f9395d96ca020bbadfa34ae0d69a58a0f449cae1e3926cad789bacc211e71bc53dead7235eb80216b6c6fbf781f61be7bca578395843882c3af060f4c9ba649c68989367cead5a032d450c8f6c4822896ef818f81c7eabcd714d973908ceb3606392643ff953c5a8032f542825face3e55f22cb0cc9699842bb83d36fa6c6ec8db5375037609438fc0032d64a70eb29c11692a42cb01367ef8301eb8bfda12ba3ea8e259784a9858b0c103250311b34ab6024e18f0b676c7958364704f7dd88f5f49ca8742cac934d0f0641bda9377628b4bdf8c4b302fbd10db05fae1e09614783d0d4d329167299d994a1d09dda34d7ef1e66e2c8ddb3dbe0c47d343975e72c9ea0b5ac2108e3b745a04",
+            "022ebf3099c0ab935bde9a45af7a475f04a50837b406ad977b1a0ceb34c79393ff031500eb5fe1b24c4b982275e51f00236e66b385984d4ea469223f0d869a2bdefa0300b6e132b3e6efbc828ff2cdf63ea12843238e7aa4023b4ee84d345b3eb18c6b0313cef7288b9be20ba4a865955b1368269af32d3e7d158f7301e90e493636404711467a6cbf7660d6ffb4a4bb02cad6d04812b1d895a54d960e729c5cef2e40d3216b6cf9214561d61f994daf7bbed40e87674652ab7c7086e81505893bf36b0d2ae2bb404266014c30dc272030b5714fa11579086397393749def5de32c137c003224e54bd49a31316edec215d164e7ed7f487a4f011e25f4cd065373dbfdb43100307ade169e2340e4e974bbb475d513c0aaf091acd9140a98d4db67638a7efafcf0205993e9a0cfb520f2f3065ff33ff1a05eae3298eddbd14a2ebecbf6c4433d9140322464f7713a9a5a6dffae101b2de06af15df570b2b9efb69eb06aa5910886e25032612130fa8eeb0c64d6e8092eb8281a9ad34f8c1a884b2e061908a8cf5001a6c02215938eed547159e41f9533742e81a36de211c0bf96c9abf48ad12d96f59be8c031b72dc3e3a970dd0a97163e3a048ec73ad4d5f04cb8cbd206ddcb90ea1ee8fad020f1c649694b941511df88d178e12dd5ff92cb26a4222e872fa15f087b74ae67c0220c9ad89871ff8db52f982238d0dd1dea57142c87193c4ff4053be76b8d7ca7e021278459873e220a2f4d6e5bd79fb7aab6fb32808008c9448235caaacf68af56002071bb5ce5e24f80a476e60d7581544f1a1539ce0daf5e60139cbb3518320a64102074202474de178d78347b33511e8b525c165bfc3d347f4607c07094532049ae0032e1970bd34b5820fbba7f6d3214ad40c2942bc1a6299fd258a2821d394c9fd37022e7deb1e85457c0e94255877b23f77434f2376ed499a5060ff5b3c97fc49a0ee2e84e24d8a22ceea7a91dc8c0e3d74dc4b75b3f4708660a47446da9022d646cd094da6854fe5f89dc7a2942b53e65e4fe7b9fea722b116277cd2b09147782b8c",
+            "0228dcf42edba05e4d5380f797199256110d17feaef7d139d38870580b1b6b5641020113c799fe5d69c59f2c0af3936b1e792378551a44256fe130e9b5f22ae99a02031fa3b11ed76d9a9ba2075b87e258fe0d0fd73a7e9a046f919c8d258c95cae147031dbddb0b6c6d9da45c8f3b7845b9cd020d507f1657028318ab6ee7f110809f370d0d1fd279e1626fe8c0263c6e503f8aea449b178ec3f4ef9f62807a3a14085e1c3df4a4988f2e7dbad64ba81fbecf1707e193bdd11b57eaaa165f95f33f5cd402c79519e9202b6f23209293ac545a15a0077f9bfbef981f21ff9fe8b005a640020db9c2a49023abd47b403009018ba632fc1a31d785f5919023c4db1285793d590202c63a6b73b10eafc04c3e731a7a27bee5e260abb0f946792d37b3d6cf7d7687022ef6bf2cf167f0dc8ded483c9d355c87c9bf602ff86f14d36ee06945371729b9020d35b2cdbfe608d0b7d72d582b87ecf81677f0746110ffe8144d5d5f3f5a2b6b032c213f41f45b3dc7d783862ca4f5b344f49e75b37c60a312f5a6e3919fd367c80319bdeaa7604aefe7cc796ed75a9d437257b3aba23ea94cb101b5442894f37aa6020fb3185cbfe7d84cfdb93c2218a1cb2cf1fbf6433bb9573e74011cfa66d93c520322d6e391553f2b4ac90a2d7765b92e671e5b8974439b4c22a3d091b9e45ce35e0305be2b0af8fa893c63fd2d9d4e90e3c0f228dd93c0b0a321f7948cea7b479c7e0205a3bfd606f3f03512698e8a59fe3ec0a5daf7802ba5060d80abb3a9ee63fa9003099b8dcfe05a91aeb614c12bb45a6d739198ee21882342fd5ee953377ffba500020d37f3bf6ab566f2960e0a96d8ac6fce78cdfb57620975be03f70e0f9c541219032b7b8a437da4d02bdb9742a9980ae6692cf01560f081d7a967a465ef42765311020bee56647b84bc38332166180f951450292decde84871048ed4e38dd62189edb031b8293c88cea51b8f97e0fe801724c9e80c3b9919d5f22da087fba13ab1f66b303048e36fc32d467865ea80b27fd5ce8e3a4c7c8e19245d2316ff3953335b14ba32a869d5951eb9fea6ec4fabd79bea409d052fa6222296eee54a7ea31d3dbebfb103cd8238b0b6a5766ebe20f6df8d71a0cd644210bc9a6de8eae9e345912c1c8",
+            "0203cc0f3002e5e1646f6368a07c02fcee31c9b0863e4f1b6523f4f2c7fb917ccd0206b4853df3580ab448a3b7923fd2ba5703c9816728670aaba1d0106e88a51da80203f4ffd3135192334dcc384796e6bfc12c8d53a242dbab09fbee80b12c61d5e002165277eced8c1cad8fb8d24c204e67d8f22078248669873fdd2b25784df8261703accd496c04b6cf832142eef900c24a3f1873479cafb7de09eacf79092de08b30299ca95fdad2afbe30cb9b1312e2d59bd8f92f1be8e775cefc991241b8373b0d7acccbf10cb40030d45891852059604511fbd34a23ee2c82927b52e171980a02261428d7d8dffb05c35feb897b047eb0c95119ff59678ed077bcc29c27010454020fdb80b75a3b10015026d10b0162410cd2b7eeb40bab262f7bd158237d491e36031204e36f2991177189f37075a5932ed51688fec31292f32de281f8de73549798032ee01e06a32d774167a8916c6059cd3a82361f5de42f18409cb0b477e4bb0d7e03209113796ee1074449ab4165d280fd0807d917c8a41d662e61e6e3f361cc427603195b59473860bba6ae83519c978924aeda066e575378304186697e47d0b6a232022cd80c27f8ab0dacb3a76d091afcb501ca04c428bacaa7196165286e138e67de0208593595f91f6eddebc880447cf95921c7c86de5568cd3c2881bf0308aabb38f020460f7b1df06274391c0b61eb62b4ed33984c70afd631931a035b732fa71595e021b535c5f28f9464efe710fcfc183169a97b943e6c64d21bcbea69d2923c7f2340309288a62b5a7b79383c7c3059026881dd08012346efed4ad09a4282673fe4788031c1e98d69ae8faf7a5b2cb0404e4f866774892b93572694d67aae1d29dceba5d03085d3c07aaee9d79df6b2064e2569adf79023f88a9a637a2f07b97aca6e57966022155a0638c544ecd4ad532d30f4ce44f1f41dd267f4cae32476593c0191275da021112d7ce78150e8d354b77083b6aaaa21c0e44f99c8722f5f1ed5619b65b97dc021eb172b11827d0222d518c98b5557be38c0eb4fab846700d9d256a21f4b6efac021bc0be6621931d366d66b0f8b03ad0cdee023fe0c0f16d3ae6b944e5a96e51710328426c62ab86d666d95ef7c704f49885529deab544764a51de8bae202a7b2036128acece27f9b2266bec311b10df1ef3bc16bca2d662c37fae451ad54bdbeaae2d107781a1d82a0c9d75dbd5a3e2d28494ab621b4fb6742c5463b47e23c82e1c",
         ],
     ];
 
     let vc = [
-        CompressedRistretto::from_slice(
-            &hex::decode("90b0c2fe57934dff9f5396e135e7d72b82b3c5393e1843178918eb2cf28a5f3c")
-                .unwrap(),
-        ),
-        CompressedRistretto::from_slice(
-            &hex::decode("74256a3e2a7fe948210c4095195ae4db3e3498c6c5fddc2afb226c0f1e97e468")
-                .unwrap(),
-        ),
-        CompressedRistretto::from_slice(
-            &hex::decode("7e348def6d03dc7bcbe7e03736ca2898e2efa9f6ff8ae4ed1cb5252ec1744075")
-                .unwrap(),
-        ),
-        CompressedRistretto::from_slice(
-            &hex::decode("861859f5d4c14f5d6d7ad88dcf43c9a98064a7d8702ffc9bad9eba2ed766702a")
-                .unwrap(),
-        ),
-        CompressedRistretto::from_slice(
-            &hex::decode("4c09b1260c833fefe25b1c3d3becc80979beca5e864d57fcb410bb15c7ba5c14")
-                .unwrap(),
-        ),
-        CompressedRistretto::from_slice(
-            &hex::decode("08cf26bfdf2e6b731536f5e48b4c0ac7b5fc846d36aaa3fe0d28f07c207f0814")
-                .unwrap(),
-        ),
-        CompressedRistretto::from_slice(
-            &hex::decode("a6e2d1c2770333c9a8a5ac10d9eb28e8609d5954428261335b2fd6ff0e0e8d69")
-                .unwrap(),
-        ),
-        CompressedRistretto::from_slice(
-            &hex::decode("30beef3b58fd2c18dde771d5c77e32f8dc01361e284aef517bce54a5c74c4665")
-                .unwrap(),
-        ),
-    ];
+        "021fb1da4b4d71bee798fbd95c8ad3b03f9eca40b4723c445f2db4feb83ca18669",
+        "02302ecd349a0004baa2a6cfc7062009636072c459b21e44b9bb8ac2d26be508d9",
+        "030cd40dda01f4d8073c67f3c98c5f9fb4155b786787452e7cc12b26c80ab992e0",
+        "021ce4660f03ee2bd5d455af237cde65d3568f54f6dfe3a893805e965ab9f6f467",
+        "031fdb0b58cef2d63c338883c93831dd35d66543f983ffecb37ba1176114e6dc75",
+        "0215951dbd78f8c607ce5370c3daf3e523bf868042c1598a7d44cd1afaca86bb74",
+        "031825fbb02417386b0cf205ed37acbe079347b6257901eba88763ec47236fd1b5",
+        "0324914ba00673d65293247a825352f2194554982a709c3931b53548303dcbebfd",
+    ]
+    .iter()
+    .map(|vc| G1::from_compressed(&hex::decode(vc).unwrap()).unwrap())
+    .collect::<Vec<_>>();
 
     let pc_gens = PedersenGens::default();
     let bp_gens = BulletproofGens::new(64, 8);
@@ -85,17 +62,16 @@ fn deserialize_and_verify() {
     for i in 0..4 {
         for j in 0..4 {
             let (n, m) = (8 << i, 1 << j);
-            let proof = RangeProof::from_bytes(&hex::decode(&proofs[i][j]).unwrap())
+            let proof = RangeProof::from_bytes(&hex::decode(proofs[i][j]).unwrap())
                 .expect("Rangeproof deserialization failed");
             let mut transcript = Transcript::new(b"Deserialize-And-Verify Test");
             assert_eq!(
-                proof.verify_multiple(&bp_gens, &pc_gens, &mut transcript, &vc[0..m], n,),
+                proof.verify_multiple(&bp_gens, &pc_gens, &mut transcript, &vc[0..m], n),
                 Ok(())
             );
         }
     }
 }
-*/
 
 // This function generates test vectors and dumps them to stdout.
 // It can be run by uncommenting the #[test] annotation.
@@ -140,3 +116,315 @@ fn generate_test_vectors() {
 
     panic!();
 }
+
+fn create_proof(n: usize, m: usize) -> (RangeProof, Vec<G1>) {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(n, m.next_power_of_two());
+    let mut rng = rand::thread_rng();
+
+    let values = (0..m as u64).collect::<Vec<_>>();
+    let blindings = (0..m).map(|_| Scalar::random(&mut rng)).collect::<Vec<_>>();
+
+    let mut transcript = Transcript::new(b"Serialization Test");
+    RangeProof::prove_multiple(&bp_gens, &pc_gens, &mut transcript, &values, &blindings, n)
+        .unwrap()
+}
+
+#[test]
+fn serialization_roundtrip() {
+    let pc_gens = PedersenGens::default();
+
+    for (n, m) in [(8, 1), (32, 2), (64, 4)] {
+        let bp_gens = BulletproofGens::new(n, m);
+        let (proof, value_commitments) = create_proof(n, m);
+
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), proof.serialized_size());
+
+        let decoded = RangeProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(decoded.to_bytes(), bytes);
+
+        let mut transcript = Transcript::new(b"Serialization Test");
+        assert!(decoded
+            .verify_multiple(&bp_gens, &pc_gens, &mut transcript, &value_commitments, n)
+            .is_ok());
+    }
+}
+
+#[test]
+fn serde_roundtrip() {
+    let (proof, _) = create_proof(32, 1);
+
+    let bytes = bincode::serialize(&proof).unwrap();
+    let decoded: RangeProof = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(decoded, proof);
+
+    let mut corrupted = bytes.clone();
+    // skip the length prefix of bincode
+    corrupted[8] = 0xff;
+    assert!(bincode::deserialize::<RangeProof>(&corrupted).is_err());
+}
+
+#[test]
+fn borsh_roundtrip() {
+    let (proof, _) = create_proof(32, 1);
+
+    // Borsh uses the compact encoding, with a 4-byte length prefix.
+    let bytes = proof.try_to_vec().unwrap();
+    assert_eq!(bytes.len(), 4 + proof.serialized_size());
+    assert_eq!(bytes[4..], proof.to_bytes()[..]);
+    assert_eq!(RangeProof::try_from_slice(&bytes).unwrap(), proof);
+
+    let mut corrupted = bytes.clone();
+    corrupted[4] = 0xff;
+    assert!(RangeProof::try_from_slice(&corrupted).is_err());
+}
+
+#[test]
+fn from_bytes_rejects_malformed_proofs() {
+    let (proof, _) = create_proof(32, 1);
+    let bytes = proof.to_bytes();
+
+    // truncated, or with a trailing byte
+    assert_eq!(
+        RangeProof::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ProofError::FormatError)
+    );
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert_eq!(RangeProof::from_bytes(&longer), Err(ProofError::FormatError));
+    assert_eq!(RangeProof::from_bytes(&[]), Err(ProofError::FormatError));
+
+    // a point with an invalid prefix
+    let mut invalid = bytes.clone();
+    invalid[0] = 4;
+    assert_eq!(RangeProof::from_bytes(&invalid), Err(ProofError::FormatError));
+
+    // a point that is not on the curve: x = 0 has no y on BN254
+    let mut invalid = bytes.clone();
+    invalid[1..33].copy_from_slice(&[0u8; 32]);
+    assert_eq!(RangeProof::from_bytes(&invalid), Err(ProofError::FormatError));
+
+    // t_x not below the group order
+    let mut invalid = bytes.clone();
+    invalid[4 * 33..4 * 33 + 32].copy_from_slice(&[0xff; 32]);
+    assert_eq!(RangeProof::from_bytes(&invalid), Err(ProofError::FormatError));
+
+    // b of the inner-product proof not below the group order
+    let mut invalid = bytes;
+    let len = invalid.len();
+    invalid[len - 32..].copy_from_slice(&[0xff; 32]);
+    assert_eq!(RangeProof::from_bytes(&invalid), Err(ProofError::FormatError));
+}
+
+#[test]
+fn batch_verify_proofs_of_different_sizes() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(64, 8);
+
+    let sizes = [(8, 1), (64, 2), (16, 8), (32, 4), (64, 1)];
+    let proofs = sizes
+        .iter()
+        .map(|&(n, m)| create_proof(n, m))
+        .collect::<Vec<_>>();
+    let mut transcripts = sizes
+        .iter()
+        .map(|_| Transcript::new(b"Serialization Test"))
+        .collect::<Vec<_>>();
+
+    let mut batch = proofs
+        .iter()
+        .zip(transcripts.iter_mut())
+        .zip(sizes.iter())
+        .map(|(((proof, vc), transcript), &(n, _))| (proof, transcript, &vc[..], n))
+        .collect::<Vec<_>>();
+    assert!(RangeProof::batch_verify(&bp_gens, &pc_gens, &mut batch).is_ok());
+
+    // an empty batch is trivially valid
+    assert!(RangeProof::batch_verify(&bp_gens, &pc_gens, &mut []).is_ok());
+}
+
+#[test]
+fn batch_verify_rejects_an_invalid_proof() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(64, 4);
+
+    let (proof1, vc1) = create_proof(32, 2);
+    let (proof2, vc2) = create_proof(64, 4);
+    let (proof3, mut vc3) = create_proof(16, 1);
+
+    let batch_verify = |vc3: &[G1], n3: usize| {
+        let mut transcripts = [
+            Transcript::new(b"Serialization Test"),
+            Transcript::new(b"Serialization Test"),
+            Transcript::new(b"Serialization Test"),
+        ];
+        let [t1, t2, t3] = &mut transcripts;
+        RangeProof::batch_verify(
+            &bp_gens,
+            &pc_gens,
+            &mut [(&proof1, t1, &vc1, 32), (&proof2, t2, &vc2, 64), (&proof3, t3, vc3, n3)],
+        )
+    };
+    assert!(batch_verify(&vc3, 16).is_ok());
+
+    // the last proof is for another bitsize
+    assert_eq!(batch_verify(&vc3, 32), Err(ProofError::VerificationError));
+    assert_eq!(batch_verify(&vc3, 12), Err(ProofError::InvalidBitsize));
+
+    // the last proof is for another value commitment
+    vc3[0] = vc3[0] + pc_gens.B;
+    assert_eq!(batch_verify(&vc3, 16), Err(ProofError::VerificationError));
+}
+
+#[test]
+fn aggregation_sizes_that_are_not_powers_of_two() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(32, 8);
+
+    for m in [3, 5, 6, 7] {
+        let (proof, vc) = create_proof(32, m);
+        assert_eq!(vc.len(), m);
+
+        let mut transcript = Transcript::new(b"Serialization Test");
+        assert!(proof
+            .verify_multiple(&bp_gens, &pc_gens, &mut transcript, &vc, 32)
+            .is_ok());
+
+        // The padding is not part of the statement: dropping a value commitment fails.
+        let mut transcript = Transcript::new(b"Serialization Test");
+        assert!(proof
+            .verify_multiple(&bp_gens, &pc_gens, &mut transcript, &vc[..m - 1], 32)
+            .is_err());
+    }
+
+    let mut transcript = Transcript::new(b"Serialization Test");
+    let (proof, _) = create_proof(32, 3);
+    assert_eq!(
+        proof.verify_multiple(&bp_gens, &pc_gens, &mut transcript, &[], 32),
+        Err(ProofError::InvalidAggregation)
+    );
+}
+
+#[test]
+fn aggregated_proofs_of_three_parties_over_mpc() {
+    use bulletproofs::range_proof_mpc::{dealer::Dealer, party::Party, MPCError};
+
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(16, 4);
+    let mut rng = rand::thread_rng();
+
+    let values = [7u64, 300, 65535];
+    let prove = |bad_party: Option<usize>| {
+        let mut transcript = Transcript::new(b"MPC Test");
+        let dealer = Dealer::new(&bp_gens, &pc_gens, &mut transcript, 16, 3).unwrap();
+
+        let blindings = values
+            .iter()
+            .map(|_| Scalar::random(&mut rand::thread_rng()))
+            .collect::<Vec<_>>();
+        let vc = values
+            .iter()
+            .zip(blindings.iter())
+            .map(|(&v, &v_blinding)| pc_gens.commit(scalar(v), v_blinding))
+            .collect::<Vec<_>>();
+
+        let (parties, bit_commitments): (Vec<_>, Vec<_>) = values
+            .iter()
+            .zip(blindings)
+            .enumerate()
+            .map(|(j, (&v, v_blinding))| {
+                let party = Party::new(&bp_gens, &pc_gens, v, v_blinding, 16).unwrap();
+                party.assign_position(j).unwrap()
+            })
+            .unzip();
+
+        let (dealer, bit_challenge) = dealer.receive_bit_commitments(bit_commitments).unwrap();
+        let (parties, poly_commitments): (Vec<_>, Vec<_>) = parties
+            .into_iter()
+            .map(|p| p.apply_challenge(&bit_challenge))
+            .unzip();
+        let (dealer, poly_challenge) = dealer.receive_poly_commitments(poly_commitments).unwrap();
+
+        let mut shares = parties
+            .into_iter()
+            .map(|p| p.apply_challenge(&poly_challenge).unwrap())
+            .collect::<Vec<_>>();
+        if let Some(j) = bad_party {
+            // a share computed for another challenge
+            shares[j] = shares[(j + 1) % 3].clone();
+        }
+
+        dealer.receive_shares(&shares).map(|proof| (proof, vc))
+    };
+
+    let (proof, vc) = prove(None).unwrap();
+    let mut transcript = Transcript::new(b"MPC Test");
+    assert!(proof
+        .verify_multiple_with_rng(&bp_gens, &pc_gens, &mut transcript, &vc, 16, &mut rng)
+        .is_ok());
+
+    assert_eq!(
+        prove(Some(1)).unwrap_err(),
+        ProofError::ProvingError(MPCError::MalformedProofShares { bad_shares: vec![1] })
+    );
+}
+
+#[test]
+fn interval_proofs() {
+    let pc_gens = PedersenGens::default();
+    let bp_gens = BulletproofGens::new(64, 2);
+    let blinding = Scalar::random(&mut rand::thread_rng());
+
+    let intervals = [(0, 0), (5, 10_000), (1_000, 1_255), (0, u64::MAX), (u64::MAX - 3, u64::MAX)];
+    for &(min, max) in intervals.iter() {
+        for &v in [min, max, min + (max - min) / 2].iter() {
+            let mut transcript = Transcript::new(b"Interval Test");
+            let (proof, V) = RangeProof::prove_in_range(
+                &bp_gens, &pc_gens, &mut transcript, v, &blinding, min, max,
+            )
+            .unwrap();
+            assert_eq!(V, pc_gens.commit(scalar(v), blinding));
+
+            let mut transcript = Transcript::new(b"Interval Test");
+            assert!(proof
+                .verify_in_range(&bp_gens, &pc_gens, &mut transcript, &V, min, max)
+                .is_ok());
+        }
+    }
+
+    // a cap that is not a power of two
+    let mut transcript = Transcript::new(b"Interval Test");
+    let (proof, V) =
+        RangeProof::prove_in_range(&bp_gens, &pc_gens, &mut transcript, 10_000, &blinding, 5, 10_000)
+            .unwrap();
+    for (min, max) in [(5, 9_999), (10_001, 20_000), (6, 10_000)] {
+        let mut transcript = Transcript::new(b"Interval Test");
+        assert!(proof
+            .verify_in_range(&bp_gens, &pc_gens, &mut transcript, &V, min, max)
+            .is_err());
+    }
+    let mut transcript = Transcript::new(b"Interval Test");
+    assert_eq!(
+        proof.verify_in_range(&bp_gens, &pc_gens, &mut transcript, &V, 10, 5),
+        Err(ProofError::InvalidRange)
+    );
+
+    // a value outside the interval
+    let mut transcript = Transcript::new(b"Interval Test");
+    assert_eq!(
+        RangeProof::prove_in_range(&bp_gens, &pc_gens, &mut transcript, 10_001, &blinding, 5, 10_000)
+            .unwrap_err(),
+        ProofError::InvalidRange
+    );
+    assert_eq!(
+        RangeProof::prove_in_range(&bp_gens, &pc_gens, &mut transcript, 4, &blinding, 5, 10_000)
+            .unwrap_err(),
+        ProofError::InvalidRange
+    );
+}
+
+fn scalar(x: u64) -> Scalar {
+    Scalar::new_mul_factor(bn::arith::U256::from(x))
+}
@@ -0,0 +1,275 @@
+use std::time::Duration;
+
+use bn::{Fr as Scalar, G1};
+use merlin::Transcript;
+use tokio::io::{duplex, DuplexStream};
+use tokio::net::{TcpListener, TcpStream};
+
+use bulletproofs::range_proof_mpc::runner::{
+    Connection, DealerRunner, Envelope, Message, PartyRunner,
+};
+use bulletproofs::range_proof_mpc::{MPCError, RunnerError};
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn gens() -> (&'static BulletproofGens, &'static PedersenGens) {
+    let bp_gens = Box::leak(Box::new(BulletproofGens::new(32, 4)));
+    let pc_gens = Box::leak(Box::new(PedersenGens::default()));
+    (bp_gens, pc_gens)
+}
+
+/// Connects a dealer with `m` parties over in-memory streams.
+fn connect(m: usize) -> (Vec<Connection<DuplexStream>>, Vec<Connection<DuplexStream>>) {
+    (0..m)
+        .map(|_| {
+            let (dealer, party) = duplex(1 << 16);
+            (Connection::new(dealer), Connection::new(party))
+        })
+        .unzip()
+}
+
+/// The result of a party, with its connection to run it again.
+type PartyHandle = tokio::task::JoinHandle<(
+    Result<(RangeProof, Vec<G1>), RunnerError>,
+    Connection<DuplexStream>,
+)>;
+
+/// Runs a party proving `v` in the background, which returns its result.
+fn spawn_party(
+    bp_gens: &'static BulletproofGens,
+    pc_gens: &'static PedersenGens,
+    v: u64,
+    n: usize,
+    mut connection: Connection<DuplexStream>,
+) -> PartyHandle {
+    tokio::spawn(async move {
+        let blinding = Scalar::random(&mut rand::thread_rng());
+        let party = PartyRunner::new(bp_gens, pc_gens, v, blinding, n, TIMEOUT);
+        let result = party.run(&mut connection).await;
+        (result, connection)
+    })
+}
+
+fn verify(proof: &RangeProof, value_commitments: &[G1], n: usize) -> bool {
+    let (bp_gens, pc_gens) = gens();
+    let mut transcript = Transcript::new(b"MPC Runner Test");
+    proof
+        .verify_multiple(bp_gens, pc_gens, &mut transcript, value_commitments, n)
+        .is_ok()
+}
+
+#[tokio::test]
+async fn three_parties_in_memory() {
+    let (bp_gens, pc_gens) = gens();
+    let (mut connections, party_connections) = connect(3);
+
+    let parties = [5u64, 1 << 20, u32::MAX as u64]
+        .iter()
+        .zip(party_connections)
+        .map(|(&v, connection)| spawn_party(bp_gens, pc_gens, v, 32, connection))
+        .collect::<Vec<_>>();
+
+    let dealer = DealerRunner::new(bp_gens, pc_gens, 32, TIMEOUT);
+    let mut transcript = Transcript::new(b"MPC Runner Test");
+    let (proof, value_commitments) = dealer.run(&mut transcript, &mut connections).await.unwrap();
+    assert!(verify(&proof, &value_commitments, 32));
+
+    // Every party gets the same proof and commitments.
+    for party in parties {
+        let (result, _) = party.await.unwrap();
+        assert_eq!(result.unwrap(), (proof.clone(), value_commitments.clone()));
+    }
+}
+
+#[tokio::test]
+async fn two_parties_over_tcp() {
+    let (bp_gens, pc_gens) = gens();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let parties = [42u64, 4242]
+        .iter()
+        .map(|&v| {
+            tokio::spawn(async move {
+                let stream = TcpStream::connect(address).await.unwrap();
+                let mut connection = Connection::new(stream);
+                let blinding = Scalar::random(&mut rand::thread_rng());
+                PartyRunner::new(bp_gens, pc_gens, v, blinding, 16, TIMEOUT)
+                    .run(&mut connection)
+                    .await
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut connections = Vec::new();
+    for _ in 0..2 {
+        let (stream, _) = listener.accept().await.unwrap();
+        connections.push(Connection::new(stream));
+    }
+
+    let dealer = DealerRunner::new(bp_gens, pc_gens, 16, TIMEOUT);
+    let mut transcript = Transcript::new(b"MPC Runner Test");
+    let (proof, value_commitments) = dealer.run(&mut transcript, &mut connections).await.unwrap();
+    // The order of the connections is the order of the positions.
+    assert!(verify(&proof, &value_commitments, 16));
+
+    for party in parties {
+        assert!(party.await.unwrap().is_ok());
+    }
+}
+
+#[tokio::test]
+async fn identify_a_misbehaving_party_and_restart() {
+    let (bp_gens, pc_gens) = gens();
+    let (mut connections, mut party_connections) = connect(3);
+
+    // The second party claims a value that does not fit in 8 bits,
+    // so its proof share does not match its value commitment.
+    let honest = party_connections.remove(0);
+    let last = party_connections.pop().unwrap();
+    let parties = vec![
+        spawn_party(bp_gens, pc_gens, 1, 8, honest),
+        spawn_party(bp_gens, pc_gens, 300, 8, party_connections.pop().unwrap()),
+        spawn_party(bp_gens, pc_gens, 255, 8, last),
+    ];
+
+    let dealer = DealerRunner::new(bp_gens, pc_gens, 8, TIMEOUT);
+    let mut transcript = Transcript::new(b"MPC Runner Test");
+    let err = dealer
+        .run(&mut transcript, &mut connections)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        RunnerError::MPC(MPCError::MalformedProofShares { bad_shares: vec![1] })
+    );
+    assert_eq!(err.bad_parties(), &[1]);
+
+    // Every party learns that the session was aborted.
+    let mut party_connections = Vec::new();
+    for party in parties {
+        let (result, connection) = party.await.unwrap();
+        assert_eq!(result.unwrap_err(), RunnerError::Aborted);
+        party_connections.push(connection);
+    }
+
+    // Restart without the misbehaving party, on the same connections
+    // and with the same transcript, which the failure left untouched.
+    connections.remove(1);
+    party_connections.remove(1);
+    let parties = [1u64, 255]
+        .iter()
+        .zip(party_connections)
+        .map(|(&v, connection)| spawn_party(bp_gens, pc_gens, v, 8, connection))
+        .collect::<Vec<_>>();
+
+    let (proof, value_commitments) = dealer.run(&mut transcript, &mut connections).await.unwrap();
+    assert!(verify(&proof, &value_commitments, 8));
+    for party in parties {
+        assert!(party.await.unwrap().0.is_ok());
+    }
+}
+
+#[tokio::test]
+async fn parties_that_time_out_or_send_garbage() {
+    let (bp_gens, pc_gens) = gens();
+    let (mut connections, mut party_connections) = connect(3);
+
+    let mut silent = party_connections.pop().unwrap();
+    let mut confused = party_connections.pop().unwrap();
+    let honest = spawn_party(bp_gens, pc_gens, 7, 8, party_connections.pop().unwrap());
+
+    // A party that answers the start of the session with a message of another session.
+    let confused = tokio::spawn(async move {
+        let start = confused.recv().await.unwrap();
+        let message = Envelope {
+            session: start.session.wrapping_add(1),
+            message: Message::Abort,
+        };
+        confused.send(&message).await.unwrap();
+        confused
+    });
+
+    let dealer = DealerRunner::new(bp_gens, pc_gens, 8, Duration::from_millis(200));
+    let mut transcript = Transcript::new(b"MPC Runner Test");
+    assert_eq!(
+        dealer.run(&mut transcript, &mut connections).await.unwrap_err(),
+        RunnerError::MalformedMessages { parties: vec![1] }
+    );
+    assert_eq!(
+        honest.await.unwrap().0.unwrap_err(),
+        RunnerError::Aborted
+    );
+    confused.await.unwrap();
+
+    // Without the confused party, the silent party is to blame.
+    let mut connections = vec![connections.pop().unwrap()];
+    assert_eq!(
+        dealer.run(&mut transcript, &mut connections).await.unwrap_err(),
+        RunnerError::PartyTimeout { parties: vec![0] }
+    );
+
+    // The silent party was sent the start and the abort of both sessions.
+    for j in [2, 0] {
+        assert!(matches!(silent.recv().await.unwrap().message, Message::Start { j: k, .. } if k == j));
+        assert!(matches!(silent.recv().await.unwrap().message, Message::Abort));
+    }
+}
+
+#[tokio::test]
+async fn party_gives_up_on_a_silent_dealer() {
+    let (bp_gens, pc_gens) = gens();
+    let (_connections, mut party_connections) = connect(1);
+
+    let blinding = Scalar::random(&mut rand::thread_rng());
+    let party = PartyRunner::new(bp_gens, pc_gens, 7, blinding, 8, Duration::from_millis(100));
+    assert_eq!(
+        party.run(&mut party_connections[0]).await.unwrap_err(),
+        RunnerError::DealerTimeout
+    );
+}
+
+#[test]
+fn envelope_roundtrip_and_malformed_envelopes() {
+    let (bp_gens, pc_gens) = gens();
+    let mut transcript = Transcript::new(b"MPC Runner Test");
+    let blinding = Scalar::random(&mut rand::thread_rng());
+    let (proof, vc) = RangeProof::prove_single(bp_gens, pc_gens, &mut transcript, 3, &blinding, 8).unwrap();
+
+    let envelope = Envelope {
+        session: 0x0123_4567_89ab_cdef,
+        message: Message::Proof {
+            value_commitments: vec![vc, vc],
+            proof: proof.clone(),
+        },
+    };
+    let bytes = envelope.to_bytes();
+    match Envelope::from_bytes(&bytes).unwrap() {
+        Envelope {
+            session: 0x0123_4567_89ab_cdef,
+            message: Message::Proof {
+                value_commitments,
+                proof: decoded,
+            },
+        } => {
+            assert_eq!(value_commitments, vec![vc, vc]);
+            assert_eq!(decoded, proof);
+        }
+        other => panic!("unexpected envelope {:?}", other),
+    }
+
+    // truncated, with a trailing byte, or with an unknown kind of message
+    assert!(Envelope::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert!(Envelope::from_bytes(&longer).is_err());
+    let mut unknown = bytes.clone();
+    unknown[8] = 8;
+    assert!(Envelope::from_bytes(&unknown).is_err());
+
+    // a value commitment that is not a point
+    let mut invalid = bytes;
+    invalid[13] = 4;
+    assert!(Envelope::from_bytes(&invalid).is_err());
+}
@@ -124,8 +124,19 @@ pub fn run() -> sc_cli::Result<()> {
 				// which sub-commands it wants to support.
 				// In the case of substrate-contract-node we only support benchmark for pallet
 				match cmd {
-					BenchmarkCmd::Pallet(_) | BenchmarkCmd::Storage(_) =>
-						Err("Runtime Benchmarking not supported.")?,
+					BenchmarkCmd::Pallet(cmd) => {
+						if !cfg!(feature = "runtime-benchmarks") {
+							return Err(
+								"Runtime benchmarking wasn't enabled when building the node. \
+							You can enable it with `--features runtime-benchmarks`."
+									.into(),
+							)
+						}
+
+						// The executor registers the BN254 host functions the runtime verifies proofs with.
+						cmd.run::<Block, service::ExecutorDispatch>(config)
+					},
+					BenchmarkCmd::Storage(_) => Err("Storage benchmarking not supported.")?,
 					BenchmarkCmd::Block(cmd) => {
 						let PartialComponents { client, .. } = service::new_partial(&config)?;
 						cmd.run(client)
@@ -11,8 +11,15 @@ use std::sync::Arc;
 pub struct ExecutorDispatch;
 
 impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
-	/// We only use the default Substrate host functions as runtime benchmarks are not supported.
-	type ExtendHostFunctions = ();
+	/// The runtime verifies faceless proofs with the BN254 host functions, and runtime benchmarks
+	/// additionally need the benchmarking host functions.
+	#[cfg(feature = "runtime-benchmarks")]
+	type ExtendHostFunctions = (
+		frame_benchmarking::benchmarking::HostFunctions,
+		pallet_faceless_host::bn254::HostFunctions,
+	);
+	#[cfg(not(feature = "runtime-benchmarks"))]
+	type ExtendHostFunctions = pallet_faceless_host::bn254::HostFunctions;
 
 	fn dispatch(method: &str, data: &[u8]) -> Option<Vec<u8>> {
 		faceless_node_runtime::api::dispatch(method, data)
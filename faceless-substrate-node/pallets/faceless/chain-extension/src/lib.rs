@@ -0,0 +1,167 @@
+//! Chain extension of the faceless pallet, which lets ink! contracts read the encrypted balances
+//! of accounts, verify burn and transfer proofs, and transfer confidential funds with a proof that
+//! the pallet checks against the chain state, e.g. to build escrows or exchanges over hidden amounts.
+//!
+//! The extension is registered under the id `1`, so a contract calls a function with the id
+//! `(1 << 16) | func_id`. Inputs and outputs are SCALE encoded; statements and proofs
+//! are base64 encoded Borsh, exactly as they are passed to the extrinsics of the pallet.
+//!
+//! | `func_id` | input                                                                                               | output                     |
+//! |-----------|-----------------------------------------------------------------------------------------------------|----------------------------|
+//! | 1         | `PublicKeyId`                                                                                       | `Option<EncryptedBalance>` |
+//! | 2         | `PublicKeyId`                                                                                       | `Option<EncryptedBalance>` |
+//! | 3         | `(PublicKeyId, AccountId, u32, Vec<u8>, Vec<u8>)` account, destination, amount, statement and proof | -                          |
+//! | 4         | `(PublicKeyId, PublicKeyId, Vec<u8>, Vec<u8>)` sender, receiver, statement and proof                | -                          |
+//! | 5         | `(PublicKeyId, PublicKeyId, Vec<u8>, Vec<u8>)`                                                      | -                          |
+//!
+//! Every function returns a [`StatusCode`]. A confidential transfer that fails reverts the call
+//! of the contract with the error of the pallet instead.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::RuntimeDebug;
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RegisteredChainExtension, RetVal, SysConfig,
+};
+use pallet_faceless::{PublicKeyId, VerificationError, WeightInfo};
+use scale_info::TypeInfo;
+use sp_core::crypto::UncheckedFrom;
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+type Faceless<T> = pallet_faceless::Pallet<T>;
+
+/// The functions of the extension.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[repr(u16)]
+pub enum FuncId {
+	/// The spendable balance of an account, as `FacelessApi::balance` returns it.
+	Balance = 1,
+	/// The pending balance of an account, as `FacelessApi::pending` returns it.
+	Pending = 2,
+	/// Verify a burn statement and proof, see `Pallet::check_burn`.
+	VerifyBurn = 3,
+	/// Verify a transfer statement and proof, see `Pallet::check_transfer`.
+	VerifyTransfer = 4,
+	/// Transfer confidential funds on behalf of the contract, see `Pallet::do_transfer`.
+	Transfer = 5,
+}
+
+impl TryFrom<u16> for FuncId {
+	type Error = DispatchError;
+
+	fn try_from(func_id: u16) -> Result<Self, Self::Error> {
+		match func_id {
+			1 => Ok(Self::Balance),
+			2 => Ok(Self::Pending),
+			3 => Ok(Self::VerifyBurn),
+			4 => Ok(Self::VerifyTransfer),
+			5 => Ok(Self::Transfer),
+			_ => Err(DispatchError::Other("Unimplemented func_id")),
+		}
+	}
+}
+
+/// The status a function of the extension returns to the contract.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug)]
+#[repr(u32)]
+pub enum StatusCode {
+	Success = 0,
+	/// The account is not registered.
+	AccountNotRegistered = 1,
+	/// The statement is not base64 encoded Borsh.
+	InvalidStatement = 2,
+	/// The proof is not base64 encoded Borsh.
+	InvalidProof = 3,
+	/// The proof does not verify against the statement rebuilt from the chain state.
+	Rejected = 4,
+	/// The nonce of the proof has already been used in this epoch.
+	NonceAlreadyUsed = 5,
+}
+
+impl From<VerificationError> for StatusCode {
+	fn from(error: VerificationError) -> Self {
+		match error {
+			VerificationError::InvalidStatement => Self::InvalidStatement,
+			VerificationError::InvalidProof => Self::InvalidProof,
+			VerificationError::AccountNotRegistered => Self::AccountNotRegistered,
+			VerificationError::NonceAlreadyUsed => Self::NonceAlreadyUsed,
+			VerificationError::Rejected => Self::Rejected,
+		}
+	}
+}
+
+/// The chain extension of the faceless pallet, see the crate documentation.
+#[derive(Default)]
+pub struct FacelessExtension;
+
+impl<T> ChainExtension<T> for FacelessExtension
+where
+	T: pallet_contracts::Config + pallet_faceless::Config,
+{
+	fn call<E>(&mut self, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+	where
+		E: Ext<T = T>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		let func_id = FuncId::try_from(env.func_id())?;
+		let mut env = env.buf_in_buf_out();
+
+		let status = match func_id {
+			FuncId::Balance | FuncId::Pending => {
+				env.charge_weight(<T as pallet_faceless::Config>::WeightInfo::balance())?;
+				let pk_id: PublicKeyId = env.read_as()?;
+
+				match Faceless::<T>::account_state(&pk_id) {
+					Some((balance, pending)) => {
+						let balance = if func_id == FuncId::Balance { Some(balance) } else { pending };
+						env.write(&balance.encode(), false, None)?;
+						StatusCode::Success
+					},
+					None => StatusCode::AccountNotRegistered,
+				}
+			},
+			FuncId::VerifyBurn => {
+				env.charge_weight(<T as pallet_faceless::Config>::WeightInfo::verify_burn())?;
+				let (pk_id, destination, amount, statement, proof): (PublicKeyId, T::AccountId, u32, Vec<u8>, Vec<u8>) =
+					env.read_as_unbounded(env.in_len())?;
+
+				match Faceless::<T>::check_burn(&pk_id, &destination, amount, &statement, &proof) {
+					Ok(()) => StatusCode::Success,
+					Err(error) => error.into(),
+				}
+			},
+			FuncId::VerifyTransfer => {
+				env.charge_weight(<T as pallet_faceless::Config>::WeightInfo::verify_transfer())?;
+				let (pk_id1, pk_id2, statement, proof): (PublicKeyId, PublicKeyId, Vec<u8>, Vec<u8>) =
+					env.read_as_unbounded(env.in_len())?;
+
+				match Faceless::<T>::check_transfer(&pk_id1, &pk_id2, &statement, &proof) {
+					Ok(()) => StatusCode::Success,
+					Err(error) => error.into(),
+				}
+			},
+			FuncId::Transfer => {
+				env.charge_weight(<T as pallet_faceless::Config>::WeightInfo::transfer())?;
+				let (pk_id1, pk_id2, statement, proof): (PublicKeyId, PublicKeyId, Vec<u8>, Vec<u8>) =
+					env.read_as_unbounded(env.in_len())?;
+
+				// The contract is the sender of the transfer. As for every transfer, the proof shows
+				// knowledge of the identity key of the account the funds are spent from.
+				let sender = env.ext().address().clone();
+				Faceless::<T>::do_transfer(sender, pk_id1, pk_id2, &statement, &proof)?;
+				StatusCode::Success
+			},
+		};
+
+		Ok(RetVal::Converging(status as u32))
+	}
+}
+
+impl<T> RegisteredChainExtension<T> for FacelessExtension
+where
+	T: pallet_contracts::Config + pallet_faceless::Config,
+{
+	const ID: u16 = 1;
+}
@@ -0,0 +1,83 @@
+//! Host functions for the BN254 operations that dominate the verification of faceless proofs:
+//! multi-pairings, multiexps in G1 and G2 and exponentiations in Gt.
+//!
+//! The runtime calls [`enable`] before it verifies a proof, which makes `bn` run these operations
+//! through the host functions, so the verifiers of `aibe` and the range proofs of `bulletproofs`
+//! run as native code. The node has to register [`bn254::HostFunctions`] with its executor.
+//!
+//! A node that does not register them cannot execute a runtime that uses them: the executor stubs
+//! the missing imports, and the stubs trap on the first call, so such a node stops importing blocks
+//! once the runtime is upgraded to one that verifies proofs through them. Every node of a chain has
+//! to be upgraded to a binary that registers the host functions before the runtime upgrade is
+//! enacted; see the README of the node.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bn::backend::Backend;
+use bn::{Fr, Gt, G1, G2};
+use borsh::{BorshDeserialize, BorshSerialize};
+use sp_runtime_interface::runtime_interface;
+use sp_std::vec::Vec;
+
+/// BN254 operations run natively with `bn`.
+///
+/// Operands and results are Borsh encoded, with points in Jacobian coordinates, so that neither
+/// side spends time on decompressing them. `None` is returned for operands that do not decode.
+#[runtime_interface]
+pub trait Bn254 {
+	/// The product of the pairings of all pairs `(p, q)`, with a single final exponentiation.
+	fn pairing_product(pairs: &[u8]) -> Option<Vec<u8>> {
+		let pairs = Vec::<(G1, G2)>::try_from_slice(pairs).ok()?;
+		bn::backend::native::pairing_batch(&pairs).try_to_vec().ok()
+	}
+
+	/// `sum(p * s)` over the items `(p, s)` in G1.
+	fn g1_multiexp(items: &[u8]) -> Option<Vec<u8>> {
+		let items = Vec::<(G1, Fr)>::try_from_slice(items).ok()?;
+		bn::backend::native::g1_multiexp(&items).try_to_vec().ok()
+	}
+
+	/// `sum(q * s)` over the items `(q, s)` in G2.
+	fn g2_multiexp(items: &[u8]) -> Option<Vec<u8>> {
+		let items = Vec::<(G2, Fr)>::try_from_slice(items).ok()?;
+		bn::backend::native::g2_multiexp(&items).try_to_vec().ok()
+	}
+
+	/// `a^s` for the operand `(a, s)` in Gt.
+	fn gt_pow(operand: &[u8]) -> Option<Vec<u8>> {
+		let (a, s) = <(Gt, Fr)>::try_from_slice(operand).ok()?;
+		bn::backend::native::gt_pow(a, s).try_to_vec().ok()
+	}
+}
+
+/// The `bn` backend that runs every operation through the host functions.
+pub static HOST: Backend = Backend {
+	g1_multiexp: |items| call(bn254::g1_multiexp, items, || bn::backend::native::g1_multiexp(items)),
+	g2_multiexp: |items| call(bn254::g2_multiexp, items, || bn::backend::native::g2_multiexp(items)),
+	gt_pow: |a, s| call(bn254::gt_pow, &(a, s), || bn::backend::native::gt_pow(a, s)),
+	pairing_batch: |pairs| call(bn254::pairing_product, pairs, || bn::backend::native::pairing_batch(pairs)),
+};
+
+/// Run the BN254 operations of the current runtime call through the host functions.
+pub fn enable() {
+	bn::backend::set_backend(&HOST);
+}
+
+/// Run the BN254 operations of the current runtime call inside the runtime again.
+pub fn disable() {
+	bn::backend::reset_backend();
+}
+
+/// Run an operation on the host, or inside the runtime with `native` if the host returns no result
+/// or one that does not decode. The result is never made up, since a stand-in like the identity
+/// could satisfy a verification equation, so a host that fails this way only costs time. A host
+/// that does not provide the host functions at all is not covered: the call traps before it
+/// returns, see the crate documentation.
+fn call<A: BorshSerialize + ?Sized, R: BorshDeserialize>(f: fn(&[u8]) -> Option<Vec<u8>>, operands: &A, native: impl FnOnce() -> R) -> R {
+	operands
+		.try_to_vec()
+		.ok()
+		.and_then(|operands| f(&operands))
+		.and_then(|result| R::try_from_slice(&result).ok())
+		.unwrap_or_else(native)
+}
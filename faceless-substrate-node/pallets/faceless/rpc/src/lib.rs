@@ -0,0 +1,232 @@
+//! RPC interface of the faceless pallet.
+//!
+//! The `faceless_*` methods expose the [`FacelessApi`] runtime API. Group elements are returned as
+//! hex encoded compressed points, the same encodings that statements are built from, and
+//! statements and proofs are passed as the base64 encoded Borsh strings that the extrinsics take.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_faceless_runtime_api::{EncryptedBalance, PublicKeyId, VerificationError};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_faceless_runtime_api::FacelessApi as FacelessRuntimeApi;
+
+#[cfg(test)]
+mod tests;
+
+/// An encrypted balance `(c1, c2)` together with the epoch it is valid in.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Balance {
+	/// The compressed G1 element `c1`.
+	pub c1: Bytes,
+	/// The compressed Gt element `c2`.
+	pub c2: Bytes,
+	/// The epoch that proofs against this balance must be made for.
+	pub epoch: u64,
+}
+
+impl Balance {
+	fn new(balance: EncryptedBalance, epoch: u64) -> Self {
+		Self { c1: balance.c1.to_vec().into(), c2: balance.c2.to_vec().into(), epoch }
+	}
+}
+
+/// Why a burn or transfer proof was rejected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationFailure {
+	/// The statement is not base64 encoded Borsh.
+	InvalidStatement,
+	/// The proof is not base64 encoded Borsh.
+	InvalidProof,
+	/// An account of the statement is not registered.
+	AccountNotRegistered,
+	/// The nonce of the proof has already been used in this epoch.
+	NonceAlreadyUsed,
+	/// The proof does not verify against the statement rebuilt from the chain state.
+	Rejected,
+}
+
+impl From<VerificationError> for VerificationFailure {
+	fn from(error: VerificationError) -> Self {
+		match error {
+			VerificationError::InvalidStatement => Self::InvalidStatement,
+			VerificationError::InvalidProof => Self::InvalidProof,
+			VerificationError::AccountNotRegistered => Self::AccountNotRegistered,
+			VerificationError::NonceAlreadyUsed => Self::NonceAlreadyUsed,
+			VerificationError::Rejected => Self::Rejected,
+		}
+	}
+}
+
+/// The result of a dry-run verification of a burn or transfer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Verification {
+	/// Whether the proof verifies against the chain state.
+	pub valid: bool,
+	/// Why the proof was rejected, if it was.
+	pub failure: Option<VerificationFailure>,
+}
+
+impl From<Result<(), VerificationError>> for Verification {
+	fn from(result: Result<(), VerificationError>) -> Self {
+		Self { valid: result.is_ok(), failure: result.err().map(Into::into) }
+	}
+}
+
+#[rpc(client, server)]
+pub trait FacelessApi<BlockHash, AccountId> {
+	/// The spendable balance of an account, or `null` if it is not registered.
+	#[method(name = "faceless_getBalance")]
+	fn balance(&self, pk_id: Bytes, at: Option<BlockHash>) -> RpcResult<Option<Balance>>;
+
+	/// The funds of an account that stay pending for the rest of the epoch, or `null` if there
+	/// are none.
+	#[method(name = "faceless_getPending")]
+	fn pending(&self, pk_id: Bytes, at: Option<BlockHash>) -> RpcResult<Option<Balance>>;
+
+	#[method(name = "faceless_isRegistered")]
+	fn is_registered(&self, pk_id: Bytes, at: Option<BlockHash>) -> RpcResult<bool>;
+
+	/// Verify a burn statement and proof for withdrawing `amount` from `pk_id` to `destination`
+	/// against the state of the block, without submitting them.
+	#[method(name = "faceless_verifyBurn")]
+	fn verify_burn(
+		&self,
+		pk_id: Bytes,
+		destination: AccountId,
+		amount: u32,
+		statement: String,
+		proof: String,
+		at: Option<BlockHash>,
+	) -> RpcResult<Verification>;
+
+	/// Verify a transfer statement and proof from `pk_id1` to `pk_id2` against the state of the
+	/// block, without submitting them.
+	#[method(name = "faceless_verifyTransfer")]
+	fn verify_transfer(&self, pk_id1: Bytes, pk_id2: Bytes, statement: String, proof: String, at: Option<BlockHash>) -> RpcResult<Verification>;
+}
+
+/// Error codes of the faceless RPC methods.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+	/// The public key is not 192 bytes long.
+	InvalidPublicKey,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::InvalidPublicKey => 2,
+		}
+	}
+}
+
+/// Implements the `faceless_*` methods on top of the runtime API of a client.
+pub struct Faceless<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> Faceless<C, Block> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> Faceless<C, Block>
+where
+	Block: BlockT,
+	C: HeaderBackend<Block>,
+{
+	fn at(&self, at: Option<Block::Hash>) -> BlockId<Block> {
+		BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash))
+	}
+}
+
+fn public_key(pk_id: Bytes) -> RpcResult<PublicKeyId> {
+	let pk_id = <[u8; 192]>::try_from(pk_id.0).map_err(|pk_id| {
+		CallError::Custom(ErrorObject::owned(
+			Error::InvalidPublicKey.into(),
+			"Public key must be a compressed Gt element of 192 bytes.",
+			Some(format!("got {} bytes", pk_id.len())),
+		))
+	})?;
+	Ok(PublicKeyId(pk_id))
+}
+
+fn runtime_error(e: impl ToString) -> CallError {
+	CallError::Custom(ErrorObject::owned(Error::RuntimeError.into(), "Unable to query the faceless pallet.", Some(e.to_string())))
+}
+
+impl<C, Block, AccountId> FacelessApiServer<<Block as BlockT>::Hash, AccountId> for Faceless<C, Block>
+where
+	Block: BlockT,
+	AccountId: Codec,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: FacelessRuntimeApi<Block, AccountId>,
+{
+	fn balance(&self, pk_id: Bytes, at: Option<Block::Hash>) -> RpcResult<Option<Balance>> {
+		let api = self.client.runtime_api();
+		let at = self.at(at);
+
+		let balance = api.balance(&at, public_key(pk_id)?).map_err(runtime_error)?;
+		let epoch = api.epoch(&at).map_err(runtime_error)?;
+		Ok(balance.map(|balance| Balance::new(balance, epoch)))
+	}
+
+	fn pending(&self, pk_id: Bytes, at: Option<Block::Hash>) -> RpcResult<Option<Balance>> {
+		let api = self.client.runtime_api();
+		let at = self.at(at);
+
+		let pending = api.pending(&at, public_key(pk_id)?).map_err(runtime_error)?;
+		let epoch = api.epoch(&at).map_err(runtime_error)?;
+		Ok(pending.map(|pending| Balance::new(pending, epoch)))
+	}
+
+	fn is_registered(&self, pk_id: Bytes, at: Option<Block::Hash>) -> RpcResult<bool> {
+		let api = self.client.runtime_api();
+
+		Ok(api.is_registered(&self.at(at), public_key(pk_id)?).map_err(runtime_error)?)
+	}
+
+	fn verify_burn(
+		&self,
+		pk_id: Bytes,
+		destination: AccountId,
+		amount: u32,
+		statement: String,
+		proof: String,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Verification> {
+		let api = self.client.runtime_api();
+
+		let result = api
+			.verify_burn(&self.at(at), public_key(pk_id)?, destination, amount, statement.into_bytes(), proof.into_bytes())
+			.map_err(runtime_error)?;
+		Ok(result.into())
+	}
+
+	fn verify_transfer(&self, pk_id1: Bytes, pk_id2: Bytes, statement: String, proof: String, at: Option<Block::Hash>) -> RpcResult<Verification> {
+		let api = self.client.runtime_api();
+
+		let result = api
+			.verify_transfer(&self.at(at), public_key(pk_id1)?, public_key(pk_id2)?, statement.into_bytes(), proof.into_bytes())
+			.map_err(runtime_error)?;
+		Ok(result.into())
+	}
+}
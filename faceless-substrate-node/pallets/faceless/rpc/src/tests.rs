@@ -0,0 +1,176 @@
+use super::*;
+use std::sync::RwLock;
+
+use sp_blockchain::{BlockStatus, Info};
+use sp_core::H256;
+use sp_runtime::{generic, traits::BlakeTwo256, OpaqueExtrinsic};
+
+type Block = generic::Block<generic::Header<u64, BlakeTwo256>, OpaqueExtrinsic>;
+type AccountId = u64;
+
+/// The spendable balances of the registered accounts, as the runtime sees them at the best block.
+///
+/// The runtime is mocked, so proofs are mocked too: a statement names the balance it was made
+/// against, and the proof verifies if that is still the spendable balance of the sender.
+#[derive(Clone, Default)]
+struct MockRuntime {
+	accounts: Vec<(PublicKeyId, EncryptedBalance)>,
+}
+
+impl MockRuntime {
+	fn spendable(&self, pk_id: &PublicKeyId) -> Option<EncryptedBalance> {
+		self.accounts.iter().find(|(account, _)| account == pk_id).map(|(_, balance)| *balance)
+	}
+
+	fn verify(&self, pk_id: &PublicKeyId, statement: &[u8]) -> Result<(), VerificationError> {
+		let balance = self.spendable(pk_id).ok_or(VerificationError::AccountNotRegistered)?;
+		let made_against = statement.first().ok_or(VerificationError::InvalidStatement)?;
+		if *made_against == balance.c1[0] {
+			Ok(())
+		} else {
+			Err(VerificationError::Rejected)
+		}
+	}
+}
+
+sp_api::mock_impl_runtime_apis! {
+	impl FacelessRuntimeApi<Block, AccountId> for MockRuntime {
+		fn balance(&self, pk_id: PublicKeyId) -> Option<EncryptedBalance> {
+			self.spendable(&pk_id)
+		}
+
+		fn pending(_pk_id: PublicKeyId) -> Option<EncryptedBalance> {
+			None
+		}
+
+		fn is_registered(&self, pk_id: PublicKeyId) -> bool {
+			self.spendable(&pk_id).is_some()
+		}
+
+		fn epoch() -> u64 {
+			0
+		}
+
+		fn verify_burn(&self, pk_id: PublicKeyId, _destination: AccountId, _amount: u32, statement: Vec<u8>, _proof: Vec<u8>) -> Result<(), VerificationError> {
+			self.verify(&pk_id, &statement)
+		}
+
+		fn verify_transfer(&self, pk_id1: PublicKeyId, pk_id2: PublicKeyId, statement: Vec<u8>, _proof: Vec<u8>) -> Result<(), VerificationError> {
+			self.spendable(&pk_id2).ok_or(VerificationError::AccountNotRegistered)?;
+			self.verify(&pk_id1, &statement)
+		}
+	}
+}
+
+/// A client with a single block, whose state can be changed.
+#[derive(Default)]
+struct TestClient {
+	runtime: RwLock<MockRuntime>,
+}
+
+impl ProvideRuntimeApi<Block> for TestClient {
+	type Api = MockRuntime;
+
+	fn runtime_api<'a>(&'a self) -> sp_api::ApiRef<'a, Self::Api> {
+		self.runtime.read().unwrap().clone().into()
+	}
+}
+
+impl HeaderBackend<Block> for TestClient {
+	fn header(&self, _id: BlockId<Block>) -> sp_blockchain::Result<Option<<Block as BlockT>::Header>> {
+		Ok(None)
+	}
+
+	fn info(&self) -> Info<Block> {
+		Info {
+			best_hash: H256::zero(),
+			best_number: 0,
+			genesis_hash: H256::zero(),
+			finalized_hash: H256::zero(),
+			finalized_number: 0,
+			finalized_state: None,
+			number_leaves: 1,
+			block_gap: None,
+		}
+	}
+
+	fn status(&self, _id: BlockId<Block>) -> sp_blockchain::Result<BlockStatus> {
+		Ok(BlockStatus::InChain)
+	}
+
+	fn number(&self, _hash: H256) -> sp_blockchain::Result<Option<u64>> {
+		Ok(Some(0))
+	}
+
+	fn hash(&self, _number: u64) -> sp_blockchain::Result<Option<H256>> {
+		Ok(Some(H256::zero()))
+	}
+}
+
+/// The encrypted balance with the given tag, which a mocked statement names.
+fn balance(tag: u8) -> EncryptedBalance {
+	EncryptedBalance { c1: [tag; 33], c2: [tag; 192] }
+}
+
+fn setup() -> (Faceless<TestClient, Block>, Arc<TestClient>) {
+	let client = Arc::new(TestClient::default());
+	client.runtime.write().unwrap().accounts = vec![(PublicKeyId([1; 192]), balance(1)), (PublicKeyId([2; 192]), balance(2))];
+	(Faceless::new(client.clone()), client)
+}
+
+fn verify_transfer(rpc: &Faceless<TestClient, Block>, statement: &str) -> Verification {
+	FacelessApiServer::<H256, AccountId>::verify_transfer(
+		rpc,
+		vec![1; 192].into(),
+		vec![2; 192].into(),
+		statement.into(),
+		"proof".into(),
+		None,
+	)
+	.unwrap()
+}
+
+#[test]
+fn verify_transfer_against_the_balance_of_the_sender() {
+	let (rpc, _) = setup();
+
+	// The statement was made against the balance tagged 1.
+	assert_eq!(verify_transfer(&rpc, "\u{1}"), Verification { valid: true, failure: None });
+	assert_eq!(
+		verify_transfer(&rpc, ""),
+		Verification { valid: false, failure: Some(VerificationFailure::InvalidStatement) }
+	);
+}
+
+#[test]
+fn verify_transfer_rejects_a_stale_balance() {
+	let (rpc, client) = setup();
+	assert!(verify_transfer(&rpc, "\u{1}").valid);
+
+	// The balance of the sender changes before the proof is submitted, e.g. by rolling over an
+	// incoming transfer, so the proof no longer verifies against the chain state.
+	client.runtime.write().unwrap().accounts[0].1 = balance(3);
+	assert_eq!(
+		verify_transfer(&rpc, "\u{1}"),
+		Verification { valid: false, failure: Some(VerificationFailure::Rejected) }
+	);
+}
+
+#[test]
+fn verify_burn_checks_the_account() {
+	let (rpc, _) = setup();
+	let verify_burn = |pk_id: Vec<u8>| {
+		FacelessApiServer::<H256, AccountId>::verify_burn(&rpc, pk_id.into(), 7, 25, "\u{1}".into(), "proof".into(), None)
+	};
+
+	assert_eq!(
+		verify_burn(vec![1; 192]).unwrap(),
+		Verification { valid: true, failure: None }
+	);
+	assert_eq!(
+		verify_burn(vec![4; 192]).unwrap(),
+		Verification { valid: false, failure: Some(VerificationFailure::AccountNotRegistered) }
+	);
+	// Public keys of the wrong length are rejected by the RPC itself.
+	assert!(verify_burn(vec![1; 191]).is_err());
+}
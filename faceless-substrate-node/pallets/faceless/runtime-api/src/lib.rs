@@ -0,0 +1,34 @@
+//! Runtime API of the faceless pallet, which lets clients read the encrypted balances of accounts
+//! and verify proofs against the chain state without submitting an extrinsic.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+pub use pallet_faceless::{EncryptedBalance, PublicKeyId, VerificationError};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait FacelessApi<AccountId> where AccountId: Codec {
+		/// The spendable balance of an account, including pending funds that the next action of
+		/// the account in the current epoch rolls over, or `None` if it is not registered.
+		fn balance(pk_id: PublicKeyId) -> Option<EncryptedBalance>;
+
+		/// The funds of an account that stay pending for the rest of the current epoch.
+		fn pending(pk_id: PublicKeyId) -> Option<EncryptedBalance>;
+
+		/// Whether an account is registered.
+		fn is_registered(pk_id: PublicKeyId) -> bool;
+
+		/// The epoch of the block the API is called at. Proofs verified against the state of that
+		/// block must be made for it, which is not the epoch of the next block at an epoch boundary.
+		fn epoch() -> u64;
+
+		/// Verify a base64 encoded burn statement and full proof for withdrawing `amount` from
+		/// `pk_id` to `destination` against the chain state, as `withdraw` does.
+		fn verify_burn(pk_id: PublicKeyId, destination: AccountId, amount: u32, statement: Vec<u8>, proof: Vec<u8>) -> Result<(), VerificationError>;
+
+		/// Verify a base64 encoded transfer statement and full proof from `pk_id1` to `pk_id2`
+		/// against the chain state, as `transfer` does.
+		fn verify_transfer(pk_id1: PublicKeyId, pk_id2: PublicKeyId, statement: Vec<u8>, proof: Vec<u8>) -> Result<(), VerificationError>;
+	}
+}
@@ -1,19 +1,464 @@
 //! Benchmarking setup for pallet-faceless
+//!
+//! Every benchmark submits a real proof, generated during the setup of the benchmark. Accounts hold
+//! part of their balance as pending balance in an epoch in which they have not been rolled over
+//! yet, so every extrinsic takes its most expensive path.
 
 use super::*;
 
 #[allow(unused)]
 use crate::Pallet as Faceless;
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use alloc::{format, string::String};
+use aibe::bf_ibe::{BFIbe, CipherText, Fr, G1, G2, Gt, Group, MasterPublicKey, MasterSecretKey, IdSecretKey, pairing};
+use aibe::traits::IdentityBasedEncryption;
+use aibe::utils::{u64_to_scalar, hash_to_g2, pedersen_commitment};
+use aibe::zk::nonce;
+use aibe::zk::burn::{BurnStatement, BurnWitness, BurnProver, BurnVerifier, FullBurnProof};
+use aibe::zk::register::{RegisterStatement, RegisterWitness, RegisterProver};
+use aibe::zk::transfer::{TransferStatement, TransferWitness, TransferProver, TransferVerifier, FullTransferProof};
+use aibe::zk::multi_transfer::{MultiTransferStatement, MultiTransferWitness, MultiTransferProver};
+use aibe::zk::ring_transfer::{RingTransferStatement, RingTransferWitness, RingTransferProver};
+use borsh::de::BorshDeserialize;
+use borsh::ser::BorshSerialize;
+use bulletproofs::PedersenGens;
+use codec::Encode;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::{Currency, Hooks};
+use frame_support::sp_runtime::traits::{AccountIdConversion, UniqueSaturatedInto, Zero};
+use frame_support::BoundedVec;
 use frame_system::RawOrigin;
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::SeedableRng;
+use sp_std::{vec, vec::Vec};
+
+/// The encrypted balance of every account set up by a benchmark.
+const BALANCE: u64 = 60;
+/// The part of `BALANCE` that is still pending.
+const PENDING: u64 = 10;
+
+type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The keys of an identity, each under its own master key.
+struct Keys {
+	id: String,
+	msk: MasterSecretKey,
+	mpk: MasterPublicKey,
+	sk: IdSecretKey,
+	pk_id: Gt,
+}
+
+impl Keys {
+	fn new(ibe: &mut BFIbe<ChaCha20Rng>, id: String) -> Self {
+		let (msk, mpk) = ibe.generate_key();
+		let sk = ibe.extract(&id, &msk);
+		let pk_id = BFIbe::<ChaCha20Rng>::pk_id(&mpk, &id);
+		Keys { id, msk, mpk, sk, pk_id }
+	}
+}
+
+fn encode<V: BorshSerialize>(x: &V) -> Vec<u8> {
+	base64::encode(x.try_to_vec().unwrap()).into_bytes()
+}
+
+fn decode<V: BorshDeserialize>(input: &[u8]) -> V {
+	V::try_from_slice(&base64::decode(input).unwrap()).unwrap()
+}
+
+fn rng() -> ChaCha20Rng {
+	ChaCha20Rng::seed_from_u64(42)
+}
+
+/// Move to the first block of the next epoch, so that accounts have to be rolled over.
+fn start_epoch<T: Config>() {
+	frame_system::Pallet::<T>::set_block_number(T::EpochLength::get());
+}
+
+/// The epoch of the current block, as the pallet stores nonces under it.
+fn current_epoch<T: Config>() -> T::BlockNumber {
+	Faceless::<T>::epoch_index().unique_saturated_into()
+}
+
+/// Set up and fund the pallet account, as the genesis config does.
+fn fund_faceless_account<T: Config>() {
+	let faceless_account: T::AccountId = PALLET_ID.into_account_truncating();
+	T::Currency::make_free_balance_be(&faceless_account, T::Currency::minimum_balance() + 1_000_000u32.into());
+	FacelessAccount::<T>::put(faceless_account);
+}
+
+/// Register an account with an encrypted balance of `BALANCE`, of which `PENDING` is still pending,
+/// and return the balance the account holds after it is rolled over.
+fn register_account<T: Config>(ibe: &mut BFIbe<ChaCha20Rng>, keys: &Keys) -> CipherText {
+	let balance = ibe.encrypt(&u64_to_scalar(BALANCE), &keys.id, &keys.mpk);
+	let pending = ibe.encrypt(&u64_to_scalar(PENDING), &keys.id, &keys.mpk);
+	let spendable = (balance.0 - pending.0, balance.1 * pending.1.inverse().unwrap());
+
+	let pk_id = PublicKeyId::from_gt(&keys.pk_id);
+	Accounts::<T>::insert(pk_id, EncryptedBalance::from_cipher(&spendable));
+	Pending::<T>::insert(pk_id, EncryptedBalance::from_cipher(&pending));
+	balance
+}
+
+/// A statement and proof for transferring 40 from `sender` to `receiver`, both registered.
+fn transfer_proof<T: Config>(ibe: &mut BFIbe<ChaCha20Rng>, sender: &Keys, receiver: &Keys) -> (TransferStatement, Vec<u8>) {
+	let mut rng = rng();
+	let c_balance = register_account::<T>(ibe, sender);
+	register_account::<T>(ibe, receiver);
+
+	let b_star = u64_to_scalar(40);
+	let b_prime = u64_to_scalar(BALANCE - 40);
+	let ((c_transfer, c_transfer_bar), (h_id, h_id_bar), r) =
+		ibe.encrypt_correlated_internal(&b_star, (sender.id.as_str(), receiver.id.as_str()), (&sender.mpk, &receiver.mpk));
+
+	let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng);
+	let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+	let epoch = Faceless::<T>::epoch_index();
+	let statement = TransferStatement {
+		y: sender.mpk,
+		y_bar: receiver.mpk,
+		pk_id_bar: receiver.pk_id,
+		c1: c_transfer.0,
+		c2: c_transfer.1,
+		c2_bar: c_transfer_bar.1,
+		c1_tilde: c_balance.0 - c_transfer.0,
+		c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
+		c_b_star,
+		c_b_prime,
+		epoch,
+		u: nonce(&sender.sk, epoch),
+	};
+	let witness = TransferWitness {
+		r,
+		s: sender.msk,
+		r_star,
+		r_prime,
+		b_star,
+		b_prime,
+		h_id,
+		h_id_bar,
+		sk_id: sender.sk,
+	};
+
+	let proof = TransferProver::new(rng).generate_full_proof(statement.clone(), witness).unwrap();
+	(statement, encode(&proof))
+}
+
+/// A registered account together with a statement and proof for withdrawing 25 from it to `destination`.
+fn burn_proof<T: Config>(destination: &T::AccountId) -> (PublicKeyId, BurnStatement, Vec<u8>) {
+	let mut rng = rng();
+	let mut ibe = BFIbe::new(rng.clone());
+	let keys = Keys::new(&mut ibe, "faceless".into());
+	let balance = register_account::<T>(&mut ibe, &keys);
+
+	let remaining = (balance.0, balance.1 * pairing(G1::one(), G2::one()).pow(-u64_to_scalar(25)));
+	let r_b = Fr::random(&mut rng);
+	let epoch = Faceless::<T>::epoch_index();
+	let statement = BurnStatement {
+		y: keys.mpk,
+		c1_id: remaining.0,
+		c2_id: remaining.1,
+		c_b: PedersenGens::default().commit(u64_to_scalar(BALANCE - 25), r_b),
+		epoch,
+		u: nonce(&keys.sk, epoch),
+	};
+	let witness = BurnWitness {
+		b: u64_to_scalar(BALANCE - 25),
+		s: keys.msk,
+		r_b,
+		h_id: hash_to_g2(keys.id.as_bytes()),
+		sk_id: keys.sk,
+	};
+
+	let proof = BurnProver::new(rng).generate_full_proof(statement.clone(), witness, &destination.encode()).unwrap();
+	(PublicKeyId::from_gt(&keys.pk_id), statement, encode(&proof))
+}
 
 benchmarks! {
-	do_something {
-		let s in 0 .. 100;
+	register {
+		let caller: T::AccountId = whitelisted_caller();
+		let mut ibe = BFIbe::new(rng());
+		let keys = Keys::new(&mut ibe, "faceless".into());
+
+		let statement = RegisterStatement {
+			y: keys.mpk,
+			pk_id: keys.pk_id,
+		};
+		let witness = RegisterWitness {
+			h_id: hash_to_g2(keys.id.as_bytes()),
+			sk_id: keys.sk,
+		};
+		let proof = RegisterProver::new(rng()).generate_proof(statement.clone(), witness, &caller.encode());
+		let pk_id = PublicKeyId::from_gt(&keys.pk_id);
+	}: _(RawOrigin::Signed(caller), pk_id, encode(&statement), encode(&proof))
+	verify {
+		assert!(Accounts::<T>::contains_key(pk_id));
+	}
+
+	deposit {
+		let caller: T::AccountId = whitelisted_caller();
+		start_epoch::<T>();
+		fund_faceless_account::<T>();
+		T::Currency::make_free_balance_be(&caller, T::Currency::minimum_balance() + 1_000_000u32.into());
+
+		let mut ibe = BFIbe::new(rng());
+		let keys = Keys::new(&mut ibe, "faceless".into());
+		register_account::<T>(&mut ibe, &keys);
+		let pk_id = PublicKeyId::from_gt(&keys.pk_id);
+	}: _(RawOrigin::Signed(caller), pk_id, 100)
+	verify {
+		assert!(Pending::<T>::contains_key(pk_id));
+	}
+
+	withdraw {
 		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller), s)
+		start_epoch::<T>();
+		fund_faceless_account::<T>();
+		let destination: T::AccountId = account("destination", 0, 0);
+		T::Currency::make_free_balance_be(&destination, T::Currency::minimum_balance());
+
+		let (pk_id, statement, proof) = burn_proof::<T>(&destination);
+	}: _(RawOrigin::Signed(caller), pk_id, destination.clone(), 25, encode(&statement), proof)
+	verify {
+		let withdrawn: BalanceOf<T> = 25u32.into();
+		assert_eq!(T::Currency::free_balance(&destination), T::Currency::minimum_balance() + withdrawn);
+	}
+
+	transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		start_epoch::<T>();
+
+		let mut ibe = BFIbe::new(rng());
+		let sender = Keys::new(&mut ibe, "sender".into());
+		let receiver = Keys::new(&mut ibe, "receiver".into());
+		let (statement, proof) = transfer_proof::<T>(&mut ibe, &sender, &receiver);
+		let pk_id1 = PublicKeyId::from_gt(&sender.pk_id);
+		let pk_id2 = PublicKeyId::from_gt(&receiver.pk_id);
+	}: _(RawOrigin::Signed(caller), pk_id1, pk_id2, encode(&statement), proof)
 	verify {
-		assert_eq!(Something::<T>::get(), Some(s));
+		assert!(Nonces::<T>::contains_key(current_epoch::<T>(), Nonce::from_gt(&statement.u)));
+	}
+
+	transfer_batch {
+		let n in 1 .. T::MaxBatchSize::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		start_epoch::<T>();
+
+		let mut ibe = BFIbe::new(rng());
+		let mut transfers = Vec::new();
+		for i in 0 .. n {
+			let sender = Keys::new(&mut ibe, format!("sender{}", i));
+			let receiver = Keys::new(&mut ibe, format!("receiver{}", i));
+			let (statement, proof) = transfer_proof::<T>(&mut ibe, &sender, &receiver);
+			transfers.push((PublicKeyId::from_gt(&sender.pk_id), PublicKeyId::from_gt(&receiver.pk_id), encode(&statement), proof));
+		}
+		let transfers = BoundedVec::try_from(transfers).unwrap();
+	}: _(RawOrigin::Signed(caller), transfers)
+	verify {
+		assert_eq!(Nonces::<T>::iter_prefix(current_epoch::<T>()).count(), n as usize);
+	}
+
+	multi_transfer {
+		let n in 1 .. T::MaxRecipients::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		start_epoch::<T>();
+
+		let mut rng = rng();
+		let mut ibe = BFIbe::new(rng.clone());
+		let sender = Keys::new(&mut ibe, "sender".into());
+		let c_balance = register_account::<T>(&mut ibe, &sender);
+		let receivers = (0 .. n).map(|i| Keys::new(&mut ibe, format!("receiver{}", i))).collect::<Vec<_>>();
+		for receiver in receivers.iter() {
+			register_account::<T>(&mut ibe, receiver);
+		}
+
+		let b_stars = vec![u64_to_scalar(1); n as usize];
+		let b_prime = u64_to_scalar(BALANCE - n as u64);
+		let recipients = receivers.iter().map(|receiver| (receiver.id.as_str(), &receiver.mpk)).collect::<Vec<_>>();
+		let ((c_transfer, c_outputs), (h_id, h_id_bars), r) =
+			ibe.encrypt_multi_internal(&b_stars, (sender.id.as_str(), &sender.mpk), &recipients);
+
+		let (r_stars, c_b_stars) = b_stars.iter().map(|b_star| pedersen_commitment(*b_star, &mut rng)).unzip();
+		let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+		let epoch = Faceless::<T>::epoch_index();
+		let statement = MultiTransferStatement {
+			y: sender.mpk,
+			y_bars: receivers.iter().map(|receiver| receiver.mpk).collect(),
+			pk_id_bars: receivers.iter().map(|receiver| receiver.pk_id).collect(),
+			c1: c_transfer.0,
+			c2: c_transfer.1,
+			c2_bars: c_outputs.iter().map(|c| c.1).collect(),
+			c1_tilde: c_balance.0 - c_transfer.0,
+			c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
+			c_b_stars,
+			c_b_prime,
+			epoch,
+			u: nonce(&sender.sk, epoch),
+		};
+		let witness = MultiTransferWitness {
+			r,
+			s: sender.msk,
+			r_stars,
+			r_prime,
+			b_stars,
+			b_prime,
+			h_id,
+			h_id_bars,
+			sk_id: sender.sk,
+		};
+
+		let proof = MultiTransferProver::new(rng).generate_full_proof(statement.clone(), witness).unwrap();
+		let pk_id = PublicKeyId::from_gt(&sender.pk_id);
+		let recipients = BoundedVec::try_from(receivers.iter().map(|receiver| PublicKeyId::from_gt(&receiver.pk_id)).collect::<Vec<_>>()).unwrap();
+	}: _(RawOrigin::Signed(caller), pk_id, recipients, encode(&statement), encode(&proof))
+	verify {
+		assert!(Nonces::<T>::contains_key(current_epoch::<T>(), Nonce::from_gt(&statement.u)));
+	}
+
+	ring_transfer {
+		let n in 2 .. T::MaxRingSize::get();
+
+		let caller: T::AccountId = whitelisted_caller();
+		start_epoch::<T>();
+
+		let mut rng = rng();
+		let mut ibe = BFIbe::new(rng.clone());
+		let members = (0 .. n).map(|i| Keys::new(&mut ibe, format!("member{}", i))).collect::<Vec<_>>();
+		let balances = members.iter().map(|member| register_account::<T>(&mut ibe, member)).collect::<Vec<_>>();
+		let pk_ids = members.iter().map(|member| member.pk_id).collect::<Vec<_>>();
+
+		let b_star = u64_to_scalar(40);
+		let b_prime = u64_to_scalar(BALANCE - 40);
+		let receiver = n as usize - 1;
+		let ((c1, c2s), r) = ibe.encrypt_ring_internal(&b_star, &pk_ids, 0, receiver);
+
+		let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng);
+		let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+		let epoch = Faceless::<T>::epoch_index();
+		let statement = RingTransferStatement {
+			pk_ids,
+			balances,
+			c1,
+			c2s,
+			c_b_star,
+			c_b_prime,
+			epoch,
+			u: nonce(&members[0].sk, epoch),
+		};
+		let witness = RingTransferWitness {
+			sender: 0,
+			receiver: receiver as u32,
+			r,
+			r_star,
+			r_prime,
+			b_star,
+			b_prime,
+			sk_id: members[0].sk,
+		};
+
+		let proof = RingTransferProver::new(rng).generate_full_proof(statement.clone(), witness).unwrap();
+		let ring = BoundedVec::try_from(members.iter().map(|member| PublicKeyId::from_gt(&member.pk_id)).collect::<Vec<_>>()).unwrap();
+	}: _(RawOrigin::Signed(caller), ring, encode(&statement), encode(&proof))
+	verify {
+		assert!(Nonces::<T>::contains_key(current_epoch::<T>(), Nonce::from_gt(&statement.u)));
+	}
+
+	verify_burn {
+		let caller: T::AccountId = whitelisted_caller();
+		start_epoch::<T>();
+		let destination: T::AccountId = account("destination", 0, 0);
+		let (pk_id, statement, proof) = burn_proof::<T>(&destination);
+	}: _(RawOrigin::Signed(caller), pk_id, destination, 25, encode(&statement), proof)
+
+	verify_transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		start_epoch::<T>();
+
+		let mut ibe = BFIbe::new(rng());
+		let sender = Keys::new(&mut ibe, "sender".into());
+		let receiver = Keys::new(&mut ibe, "receiver".into());
+		let (statement, proof) = transfer_proof::<T>(&mut ibe, &sender, &receiver);
+		let pk_id1 = PublicKeyId::from_gt(&sender.pk_id);
+		let pk_id2 = PublicKeyId::from_gt(&receiver.pk_id);
+	}: _(RawOrigin::Signed(caller), pk_id1, pk_id2, encode(&statement), proof)
+
+	// The spendable balance of an account as the chain extension reads it, which adds the pending
+	// balance of an account that has not been rolled over yet.
+	balance {
+		start_epoch::<T>();
+		let mut ibe = BFIbe::new(rng());
+		let keys = Keys::new(&mut ibe, "faceless".into());
+		register_account::<T>(&mut ibe, &keys);
+		let pk_id = PublicKeyId::from_gt(&keys.pk_id);
+	}: {
+		Faceless::<T>::account_state(&pk_id).unwrap();
+	}
+	verify {
+		assert!(Pending::<T>::contains_key(pk_id));
+	}
+
+	on_initialize {
+		let n in 0 .. T::MaxNoncesPruned::get();
+		for i in 0..n {
+			let mut nonce = [0; 192];
+			nonce[..4].copy_from_slice(&i.to_le_bytes());
+			Nonces::<T>::insert(T::BlockNumber::zero(), Nonce(nonce), ());
+		}
+		let block = T::EpochLength::get();
+	}: {
+		Faceless::<T>::on_initialize(block);
+	}
+	verify {
+		assert_eq!(Nonces::<T>::iter_prefix(T::BlockNumber::zero()).count(), 0);
+	}
+
+	// The verification of a proof with the BN254 operations run inside the runtime and as host
+	// functions. Not used for the weights, only to compare the two.
+	#[extra]
+	verify_burn_in_runtime {
+		let destination: T::AccountId = account("destination", 0, 0);
+		let (_, statement, proof) = burn_proof::<T>(&destination);
+		let proof: FullBurnProof = decode(&proof);
+		pallet_faceless_host::disable();
+	}: {
+		assert!(BurnVerifier::verify_full_proof(statement, proof, &destination.encode()).is_ok());
+	}
+
+	#[extra]
+	verify_burn_on_host {
+		let destination: T::AccountId = account("destination", 0, 0);
+		let (_, statement, proof) = burn_proof::<T>(&destination);
+		let proof: FullBurnProof = decode(&proof);
+		pallet_faceless_host::enable();
+	}: {
+		assert!(BurnVerifier::verify_full_proof(statement, proof, &destination.encode()).is_ok());
+	}
+
+	#[extra]
+	verify_transfer_in_runtime {
+		let mut ibe = BFIbe::new(rng());
+		let sender = Keys::new(&mut ibe, "sender".into());
+		let receiver = Keys::new(&mut ibe, "receiver".into());
+		let (statement, proof) = transfer_proof::<T>(&mut ibe, &sender, &receiver);
+		let proof: FullTransferProof = decode(&proof);
+		pallet_faceless_host::disable();
+	}: {
+		assert!(TransferVerifier::verify_full_proof(statement, proof).is_ok());
+	}
+
+	#[extra]
+	verify_transfer_on_host {
+		let mut ibe = BFIbe::new(rng());
+		let sender = Keys::new(&mut ibe, "sender".into());
+		let receiver = Keys::new(&mut ibe, "receiver".into());
+		let (statement, proof) = transfer_proof::<T>(&mut ibe, &sender, &receiver);
+		let proof: FullTransferProof = decode(&proof);
+		pallet_faceless_host::enable();
+	}: {
+		assert!(TransferVerifier::verify_full_proof(statement, proof).is_ok());
 	}
 
 	impl_benchmark_test_suite!(Faceless, crate::mock::new_test_ext(), crate::mock::Test);
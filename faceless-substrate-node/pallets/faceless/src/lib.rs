@@ -5,10 +5,25 @@
 /// relevant zero-knowledge proofs:
 /// 1. Verification of burn proof
 /// 2. Verification of transfer proof
+/// 3. Verification of registration proof
+/// 4. Verification of ring transfer proof, which hides the sender and the receiver of a transfer
+///    among a ring of registered accounts
+///
+/// Incoming funds are kept in a pending balance and only become spendable at the first action of
+/// an account in a later epoch, so proofs are always made against a balance that stays the same
+/// for the rest of the epoch and cannot be invalidated by incoming deposits or transfers.
+/// Every burn and transfer proof carries the nonce of the sender in the current epoch, so an
+/// account can spend at most once per epoch and a proof cannot be replayed.
+///
+/// Every call enables the host functions of `pallet_faceless_host` first, so that the pairings and
+/// multiexps of proof verification run as native code instead of inside the Wasm runtime.
 
 extern crate alloc;
 pub use pallet::*;
 
+mod types;
+pub use types::{EncryptedBalance, Nonce, PublicKeyId, VerificationError};
+
 #[cfg(test)]
 mod mock;
 
@@ -18,21 +33,30 @@ mod tests;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+pub mod migrations;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
 #[frame_support::pallet]
 pub mod pallet {
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 	use frame_support::traits::{Currency, ExistenceRequirement};
 	use frame_support::PalletId;
-	use frame_support::sp_runtime::traits::{AccountIdConversion, Zero};
+	use frame_support::sp_runtime::traits::{AccountIdConversion, CheckedDiv, One, Saturating, UniqueSaturatedInto};
     use sp_std::vec::Vec;
-    use aibe::zk::burn::{BurnStatement, BurnProof, BurnVerifier};
-    use aibe::zk::transfer::{TransferStatement, TransferProof, TransferVerifier};
+    use aibe::zk::burn::{BurnStatement, FullBurnProof, BurnVerifier};
+    use aibe::zk::register::{RegisterStatement, RegisterProof, RegisterVerifier};
+    use aibe::zk::transfer::{TransferStatement, FullTransferProof, TransferVerifier};
+    use aibe::zk::multi_transfer::{MultiTransferStatement, FullMultiTransferProof, MultiTransferVerifier};
+    use aibe::zk::ring_transfer::{RingTransferStatement, FullRingTransferProof, RingTransferVerifier};
 	use aibe::bf_ibe::{BFIbe, CipherText, PlainData, MasterSecretKey, MasterPublicKey, IdSecretKey, G1, G2, Gt, pairing, Group};
 	use aibe::utils::{u64_to_scalar};
     use borsh::de::BorshDeserialize;
-	use borsh::ser::BorshSerialize;
 	use rand_chacha::ChaCha20Rng;
+	use crate::types::{EncryptedBalance, Nonce, PublicKeyId, VerificationError};
+	use crate::weights::WeightInfo;
 
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
@@ -41,21 +65,47 @@ pub mod pallet {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type Currency: Currency<Self::AccountId>;
+		/// The number of blocks in an epoch. Pending balances are rolled over once per epoch.
+		#[pallet::constant]
+		type EpochLength: Get<Self::BlockNumber>;
+		/// The maximum number of recipients of a multi-recipient transfer.
+		#[pallet::constant]
+		type MaxRecipients: Get<u32>;
+		/// The maximum number of members of the ring of an anonymous transfer. Proofs grow
+		/// quadratically with the size of the ring.
+		#[pallet::constant]
+		type MaxRingSize: Get<u32>;
+		/// The maximum number of transfers verified together by `transfer_batch`.
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+		/// The maximum number of nonces of past epochs removed at the start of a block.
+		#[pallet::constant]
+		type MaxNoncesPruned: Get<u32>;
+		/// Weight information for the extrinsics of this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 	type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
 
+	/// The version of the storage layout, see `migrations`.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
-    // 'without_storage_info' is needed for storing variable-length Vec<u8> data in the StorageMap (Proofs below).
-    #[pallet::without_storage_info]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	// The pallet's runtime storage items.
 	// https://docs.substrate.io/v3/runtime/storage
+	/// The nonces used by burn and transfer proofs, by epoch. Nonces of past epochs are pruned at
+	/// the start of the following blocks, since proofs of past epochs are no longer accepted.
 	#[pallet::storage]
-    pub(super) type Proofs<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, (T::AccountId, T::BlockNumber)>; 
+	pub(super) type Nonces<T: Config> = StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Blake2_128Concat, Nonce, ()>;
+
+	/// The oldest epoch whose nonces may not have been pruned yet.
+	#[pallet::storage]
+	pub(super) type NoncesToPrune<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
 
 	// #[pallet::storage]
 	// #[pallet::getter(fn get_balance)]
@@ -63,7 +113,16 @@ pub mod pallet {
 
 	#[pallet::storage]
 	#[pallet::getter(fn get_balance)]
-	pub(super) type Accounts<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, Vec<u8>>;
+	pub(super) type Accounts<T: Config> = StorageMap<_, Blake2_128Concat, PublicKeyId, EncryptedBalance>;
+
+	/// Funds received by an account that are not spendable until the account is rolled over.
+	#[pallet::storage]
+	#[pallet::getter(fn get_pending)]
+	pub(super) type Pending<T: Config> = StorageMap<_, Blake2_128Concat, PublicKeyId, EncryptedBalance>;
+
+	/// The last epoch in which the pending balance of an account was rolled over.
+	#[pallet::storage]
+	pub(super) type LastRollOver<T: Config> = StorageMap<_, Blake2_128Concat, PublicKeyId, T::BlockNumber, ValueQuery>;
 
 	#[pallet::storage]
 	// This getter `get_faceless_account` is only for use inside the Substrate node. Externally, like in Polkadot-JS,
@@ -78,7 +137,7 @@ pub mod pallet {
 	pub enum Event<T: Config> {
         BurnVerificationSuccess(T::AccountId, Vec<u8>),
         TransferVerificationSuccess(T::AccountId, Vec<u8>),
-		RegisterSuccess(T::AccountId, Vec<u8>),
+		RegisterSuccess(T::AccountId, PublicKeyId),
 		DepositSuccess(T::AccountId, u32),
 		WithdrawSuccess(T::AccountId, u32),
 		TransferSuccess(T::AccountId),
@@ -89,7 +148,26 @@ pub mod pallet {
 	pub enum Error<T> {
         BurnVerificationFailure,
         TransferVerificationFailure,
+		RegisterVerificationFailure,
+		AccountAlreadyRegistered,
 		AccountNotRegistered,
+		SelfTransfer,
+		/// The nonce of the proof has already been used in this epoch.
+		NonceAlreadyUsed,
+		/// A recipient appears more than once in a multi-recipient transfer.
+		DuplicateRecipient,
+		/// An account appears more than once in the ring of an anonymous transfer.
+		DuplicateRingMember,
+		/// The public key is not a valid element of Gt.
+		InvalidPublicKey,
+		/// The stored encrypted balance could not be decoded.
+		InvalidBalance,
+		/// The statement could not be decoded.
+		InvalidStatement,
+		/// The proof could not be decoded.
+		InvalidProof,
+		/// The pallet account has not been set up at genesis.
+		FacelessAccountNotSet,
 	}
 
 	pub const PALLET_ID: PalletId = PalletId(*b"faceless");
@@ -120,145 +198,478 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(block: T::BlockNumber) -> Weight {
+			let epoch = NoncesToPrune::<T>::get();
+			if epoch >= Self::epoch(block) {
+				return T::WeightInfo::on_initialize(0);
+			}
+
+			// The nonces of past epochs can no longer be replayed. At most `MaxNoncesPruned` of them
+			// are removed per block; the removals are committed at the end of the block, so the next
+			// block continues from the start of the remaining nonces without a cursor.
+			let removed = Nonces::<T>::clear_prefix(epoch, T::MaxNoncesPruned::get(), None);
+			if removed.maybe_cursor.is_none() {
+				NoncesToPrune::<T>::put(epoch.saturating_add(One::one()));
+			}
+			T::WeightInfo::on_initialize(removed.unique)
+		}
+	}
+
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
 	// These functions materialize as "extrinsics", which are often compared to transactions.
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 
-		#[pallet::weight(1_000)]
-		pub fn register(origin: OriginFor<T>, pk_id: Vec<u8>) -> DispatchResult {
+        /// A dispatchable that takes a registration statement and proof as inputs, verifies that the signer
+        /// knows the identity key of `pk_id`, and only then creates an account with a zero balance for it.
+        /// The proof is bound to the signer, so it cannot be replayed by another account.
+		#[pallet::weight(T::WeightInfo::register())]
+		pub fn register(origin: OriginFor<T>, pk_id: PublicKeyId, statement: Vec<u8>, proof: Vec<u8>) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			pallet_faceless_host::enable();
+			ensure!(!Accounts::<T>::contains_key(pk_id), Error::<T>::AccountAlreadyRegistered);
 			
 			// Create encryption of 0
 			let ct_0 = G1::one();
-			let ct_1 = Gt::try_from_slice(base64::decode(pk_id.as_slice()).unwrap().as_slice()).unwrap();
-			let zero_ct = base64::encode((ct_0, ct_1).try_to_vec().unwrap()).into_bytes();
+			let ct_1 = pk_id.to_gt().ok_or(Error::<T>::InvalidPublicKey)?;
 
-			Accounts::<T>::insert::<Vec<u8>, Vec<u8>>(pk_id.clone(), zero_ct);
+			let mut rs: RegisterStatement = decode_base64(&statement).ok_or(Error::<T>::InvalidStatement)?;
+			let rp: RegisterProof = decode_base64(&proof).ok_or(Error::<T>::InvalidProof)?;
+			rs.pk_id = ct_1;
+			RegisterVerifier::verify_proof(rs, rp, &sender.encode()).map_err(|_| Error::<T>::RegisterVerificationFailure)?;
+
+			Accounts::<T>::insert(pk_id, EncryptedBalance::from_cipher(&(ct_0, ct_1)));
+			LastRollOver::<T>::insert(pk_id, Self::current_epoch());
 
 			Self::deposit_event(Event::RegisterSuccess(sender, pk_id));
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
-		pub fn deposit(origin: OriginFor<T>, pk_id: Vec<u8>, amount: u32) -> DispatchResult {
+		#[pallet::weight(T::WeightInfo::deposit())]
+		pub fn deposit(origin: OriginFor<T>, pk_id: PublicKeyId, amount: u32) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			pallet_faceless_host::enable();
+			let faceless_account = Self::get_faceless_account().ok_or(Error::<T>::FacelessAccountNotSet)?;
 
-			// Current encrypted balance
-			let balance = Self::get_balance(&pk_id).ok_or(Error::<T>::AccountNotRegistered)?;
-			let mut balance = CipherText::try_from_slice(base64::decode(balance.as_slice()).unwrap().as_slice()).unwrap();
+			Self::roll_over(&pk_id)?;
 
-			// Add the encryption of amount to current encrypted balance
+			// Add the encryption of amount to the pending balance
 			let addend = pairing(G1::one(), G2::one()).pow(u64_to_scalar(amount as u64));
-			balance.1 = balance.1 * addend;
-			let balance = base64::encode(balance.try_to_vec().unwrap()).into_bytes();
-			Accounts::<T>::insert(pk_id, balance);
+			Self::add_pending(&pk_id, &(G1::zero(), addend))?;
 
 			// Transfer sender's native tokens to pallet's account
 			let value: BalanceOf<T> = From::<u32>::from(amount);
-			T::Currency::transfer(&sender, &Self::get_faceless_account().unwrap(), value, ExistenceRequirement::AllowDeath)?;
+			T::Currency::transfer(&sender, &faceless_account, value, ExistenceRequirement::AllowDeath)?;
 
 			Self::deposit_event(Event::DepositSuccess(sender, amount));
 
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
-		pub fn withdraw(origin: OriginFor<T>, pk_id: Vec<u8>, destination: T::AccountId, amount: u32) -> DispatchResult {
+        /// A dispatchable that takes an anonymous transfer statement and proof over a ring of registered accounts,
+        /// verifies the proof against the current encrypted balances of the ring, and credits every member of the
+        /// ring with its ciphertext. The proof does not tell which member is the sender and which the receiver.
+        ///
+        /// All updates go to the pending balances, so the sender's debit only becomes effective when it is rolled
+        /// over; the nonce of the proof keeps the sender from spending again in the meantime.
+		#[pallet::weight(T::WeightInfo::ring_transfer(ring.len() as u32))]
+		pub fn ring_transfer(origin: OriginFor<T>, ring: BoundedVec<PublicKeyId, T::MaxRingSize>, statement: Vec<u8>, proof: Vec<u8>) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
+			pallet_faceless_host::enable();
+			for (i, member) in ring.iter().enumerate() {
+				ensure!(!ring[..i].contains(member), Error::<T>::DuplicateRingMember);
+			}
 
-			// Current encrypted balance
-			let balance = Self::get_balance(&pk_id).ok_or(Error::<T>::AccountNotRegistered)?;
-			let mut balance = CipherText::try_from_slice(base64::decode(balance.as_slice()).unwrap().as_slice()).unwrap();
+			let mut ts: RingTransferStatement = decode_base64(&statement).ok_or(Error::<T>::InvalidStatement)?;
+			let tp: FullRingTransferProof = decode_base64(&proof).ok_or(Error::<T>::InvalidProof)?;
+			ensure!(ts.ring_size() == Some(ring.len()), Error::<T>::InvalidStatement);
 
-			// Substract the encryption of amount from current encrypted balance
-			let deduction = pairing(G1::one(), G2::one()).pow(-u64_to_scalar(amount as u64));
-			balance.1 = balance.1 * deduction;
-			let balance = base64::encode(balance.try_to_vec().unwrap()).into_bytes();
-			Accounts::<T>::insert(pk_id, balance);
+			// The ring and its balances are always taken from storage, so the proof cannot be made
+			// against a balance that a member does not actually hold.
+			for (i, member) in ring.iter().enumerate() {
+				Self::roll_over(member)?;
+				ts.pk_ids[i] = member.to_gt().ok_or(Error::<T>::InvalidPublicKey)?;
+				ts.balances[i] = Self::balance(member)?;
+			}
+			let epoch = Self::current_epoch();
+			ts.epoch = epoch.unique_saturated_into();
+			let nonce = Nonce::from_gt(&ts.u);
+			ensure!(!Nonces::<T>::contains_key(epoch, nonce), Error::<T>::NonceAlreadyUsed);
+
+			RingTransferVerifier::verify_full_proof(ts.clone(), tp).map_err(|_| Error::<T>::TransferVerificationFailure)?;
+
+			Nonces::<T>::insert(epoch, nonce, ());
+			for (member, c2) in ring.iter().zip(ts.c2s.iter()) {
+				Self::add_pending(member, &(ts.c1, *c2))?;
+			}
+
+			Self::deposit_event(Event::TransferSuccess(sender));
+
+			Ok(())
+		}
+
+        /// A dispatchable that takes a burn statement and a burn proof as inputs, verifies that the signer
+        /// can decrypt the encrypted balance of `pk_id` and that the balance minus `amount` is a non-negative
+        /// value in range, and only then releases `amount` native tokens to `destination`. The proof is bound
+        /// to `destination`, so a copy of the transaction cannot redirect the payout to another account.
+		#[pallet::weight(T::WeightInfo::withdraw())]
+		pub fn withdraw(origin: OriginFor<T>, pk_id: PublicKeyId, destination: T::AccountId, amount: u32, statement: Vec<u8>, proof: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			pallet_faceless_host::enable();
+			let faceless_account = Self::get_faceless_account().ok_or(Error::<T>::FacelessAccountNotSet)?;
+
+			let bs = Self::verified_burn(&pk_id, &destination, amount, &statement, &proof)?;
+
+			// The proof is checked without writing to storage, so the account is only rolled over now.
+			// The remaining balance it was checked against already includes the pending funds.
+			Self::roll_over(&pk_id)?;
+			Nonces::<T>::insert(Self::current_epoch(), Nonce::from_gt(&bs.u), ());
+			Accounts::<T>::insert(pk_id, EncryptedBalance::from_cipher(&(bs.c1_id, bs.c2_id)));
 
 			// Transfer tokens from pallet's account to sender
 			let value: BalanceOf<T> = From::<u32>::from(amount);			
-			T::Currency::transfer(&Self::get_faceless_account().unwrap(), &destination, value, ExistenceRequirement::AllowDeath)?;
+			T::Currency::transfer(&faceless_account, &destination, value, ExistenceRequirement::AllowDeath)?;
 
 			Self::deposit_event(Event::WithdrawSuccess(sender, amount));
 
 			Ok(())
 		}
 
-		#[pallet::weight(1_000)]
-		pub fn transfer(origin: OriginFor<T>, pk_id1: Vec<u8>, pk_id2: Vec<u8>, enc_amount1: Vec<u8>, enc_amount2: Vec<u8>) -> DispatchResult {
+        /// A dispatchable that takes a transfer statement and a transfer proof as inputs, verifies the proof
+        /// against the sender's current encrypted balance, and only then updates the encrypted balances of
+        /// both the sender and the receiver.
+		#[pallet::weight(T::WeightInfo::transfer())]
+		pub fn transfer(origin: OriginFor<T>, pk_id1: PublicKeyId, pk_id2: PublicKeyId, statement: Vec<u8>, proof: Vec<u8>) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			let enc_amount1 = CipherText::try_from_slice(base64::decode(enc_amount1.as_slice()).unwrap().as_slice()).unwrap();
-			let enc_amount2 = CipherText::try_from_slice(base64::decode(enc_amount2.as_slice()).unwrap().as_slice()).unwrap();
+			Self::do_transfer(sender, pk_id1, pk_id2, &statement, &proof)
+		}
 
-			let balance1 = Self::get_balance(&pk_id1).ok_or(Error::<T>::AccountNotRegistered)?;
-			let mut balance1 = CipherText::try_from_slice(base64::decode(balance1.as_slice()).unwrap().as_slice()).unwrap();
-			balance1 = BFIbe::<ChaCha20Rng>::add_ciphers(&balance1, &enc_amount1);
-			let balance1 = base64::encode(balance1.try_to_vec().unwrap()).into_bytes();
+        /// A dispatchable that takes a batch of transfers, each given as `(pk_id1, pk_id2, statement, proof)` like
+        /// the arguments of `transfer`, verifies all proofs at once, and only then applies every transfer. The whole
+        /// batch fails if any of its proofs does not verify.
+		#[pallet::weight(T::WeightInfo::transfer_batch(transfers.len() as u32))]
+		pub fn transfer_batch(origin: OriginFor<T>, transfers: BoundedVec<(PublicKeyId, PublicKeyId, Vec<u8>, Vec<u8>), T::MaxBatchSize>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			pallet_faceless_host::enable();
+			let epoch = Self::current_epoch();
 
-			let balance2 = Self::get_balance(&pk_id2).ok_or(Error::<T>::AccountNotRegistered)?;
-			let mut balance2 = CipherText::try_from_slice(base64::decode(balance2.as_slice()).unwrap().as_slice()).unwrap();
-			balance2 = BFIbe::<ChaCha20Rng>::add_ciphers(&balance2, &enc_amount2);
-			let balance2 = base64::encode(balance2.try_to_vec().unwrap()).into_bytes();
+			let mut instances = Vec::new();
+			let mut nonces = Vec::new();
+			for (pk_id1, pk_id2, statement, proof) in transfers.iter() {
+				ensure!(pk_id1 != pk_id2, Error::<T>::SelfTransfer);
+
+				let ts = Self::transfer_statement(pk_id1, pk_id2, statement)?;
+				let tp: FullTransferProof = decode_base64(proof).ok_or(Error::<T>::InvalidProof)?;
+				let nonce = Nonce::from_gt(&ts.u);
+				ensure!(!Nonces::<T>::contains_key(epoch, nonce) && !nonces.contains(&nonce), Error::<T>::NonceAlreadyUsed);
+
+				nonces.push(nonce);
+				instances.push((ts, tp));
+			}
+
+			TransferVerifier::verify_batch(&instances).map_err(|_| Error::<T>::TransferVerificationFailure)?;
+
+			for ((pk_id1, pk_id2, _, _), (ts, _)) in transfers.iter().zip(instances.iter()) {
+				Self::roll_over(pk_id1)?;
+				Self::roll_over(pk_id2)?;
+				Nonces::<T>::insert(epoch, Nonce::from_gt(&ts.u), ());
+				Accounts::<T>::insert(pk_id1, EncryptedBalance::from_cipher(&(ts.c1_tilde, ts.c2_tilde)));
+				Self::add_pending(pk_id2, &(ts.c1, ts.c2_bar))?;
+			}
+
+			Self::deposit_event(Event::TransferSuccess(sender));
+
+			Ok(())
+		}
+
+        /// A dispatchable that takes a multi-recipient transfer statement and proof as inputs, verifies the
+        /// proof against the sender's current encrypted balance, and only then deducts the sum of the outputs
+        /// from the sender and credits every recipient with its output, all in one transaction.
+		#[pallet::weight(T::WeightInfo::multi_transfer(recipients.len() as u32))]
+		pub fn multi_transfer(origin: OriginFor<T>, pk_id: PublicKeyId, recipients: BoundedVec<PublicKeyId, T::MaxRecipients>, statement: Vec<u8>, proof: Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			pallet_faceless_host::enable();
+			for (i, recipient) in recipients.iter().enumerate() {
+				ensure!(*recipient != pk_id, Error::<T>::SelfTransfer);
+				ensure!(!recipients[..i].contains(recipient), Error::<T>::DuplicateRecipient);
+			}
+
+			let mut ts: MultiTransferStatement = decode_base64(&statement).ok_or(Error::<T>::InvalidStatement)?;
+			let tp: FullMultiTransferProof = decode_base64(&proof).ok_or(Error::<T>::InvalidProof)?;
+			ensure!(ts.outputs() == Some(recipients.len()), Error::<T>::InvalidStatement);
+
+			Self::roll_over(&pk_id)?;
+			for recipient in recipients.iter() {
+				Self::roll_over(recipient)?;
+			}
+			let balance = Self::balance(&pk_id)?;
+
+			// The recipients of the proof are always `recipients`, and the remaining balance of the
+			// sender is always rebuilt from the on-chain ciphertext, so the proof cannot credit other
+			// accounts or be made against a balance the sender does not actually hold.
+			ts.pk_id_bars = recipients.iter()
+				.map(|recipient| recipient.to_gt().ok_or(Error::<T>::InvalidPublicKey))
+				.collect::<Result<Vec<_>, _>>()?;
+			ts.c1_tilde = balance.0 - ts.c1;
+			ts.c2_tilde = balance.1 * ts.c2.inverse().ok_or(Error::<T>::TransferVerificationFailure)?;
+			let epoch = Self::current_epoch();
+			ts.epoch = epoch.unique_saturated_into();
+			let nonce = Nonce::from_gt(&ts.u);
+			ensure!(!Nonces::<T>::contains_key(epoch, nonce), Error::<T>::NonceAlreadyUsed);
+
+			MultiTransferVerifier::verify_full_proof(ts.clone(), tp).map_err(|_| Error::<T>::TransferVerificationFailure)?;
+
+			Nonces::<T>::insert(epoch, nonce, ());
+			Accounts::<T>::insert(pk_id, EncryptedBalance::from_cipher(&(ts.c1_tilde, ts.c2_tilde)));
+			for (recipient, c2_bar) in recipients.iter().zip(ts.c2_bars.iter()) {
+				Self::add_pending(recipient, &(ts.c1, *c2_bar))?;
+			}
 
-			Accounts::<T>::insert(pk_id1, balance1);
-			Accounts::<T>::insert(pk_id2, balance2);
-			
 			Self::deposit_event(Event::TransferSuccess(sender));
 
 			Ok(())
 		}
 
-        /// A dispatchable that takes a burn statement and a burn proof as inputs, verifies the proof, and 
-        /// emits an event that denotes the verification status.
-		#[pallet::weight(1_000)]
-		pub fn verify_burn(origin: OriginFor<T>, statement: Vec<u8>, proof: Vec<u8>) -> DispatchResult {
+        /// A dispatchable that takes a burn statement and a burn proof for withdrawing `amount` from `pk_id` to
+        /// `destination` as inputs, verifies the proof against the chain state as `withdraw` does, and emits an
+        /// event that denotes the verification status. Nothing else is written to storage.
+		#[pallet::weight(T::WeightInfo::verify_burn())]
+		pub fn verify_burn(origin: OriginFor<T>, pk_id: PublicKeyId, destination: T::AccountId, amount: u32, statement: Vec<u8>, proof: Vec<u8>) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			// https://docs.substrate.io/v3/runtime/origins
 			let sender = ensure_signed(origin)?;
+			pallet_faceless_host::enable();
 
-            let bs = BurnStatement::try_from_slice(base64::decode(statement.as_slice()).unwrap().as_slice()).unwrap();
-            let bp = BurnProof::try_from_slice(base64::decode(proof.as_slice()).unwrap().as_slice()).unwrap();
-
-            let result = BurnVerifier::verify_proof(bs, bp);
-
-            match result {
-                Ok(()) => {
-                    Self::deposit_event(Event::BurnVerificationSuccess(sender, proof));
-                    Ok(())
-                },
-                Err(_) => {
-                    Err(Error::<T>::BurnVerificationFailure.into())
-                }
-            }
+			Self::verified_burn(&pk_id, &destination, amount, &statement, &proof)?;
+			Self::deposit_event(Event::BurnVerificationSuccess(sender, proof));
+			Ok(())
 		}
 
-        /// A dispatchable that takes a transfer statement and a transfer proof as inputs, verifies the proof, and 
-        /// emits an event that denotes the verification status.
-		#[pallet::weight(10_000)]
-		pub fn verify_transfer(origin: OriginFor<T>, statement: Vec<u8>, proof: Vec<u8>) -> DispatchResult {
+        /// A dispatchable that takes a transfer statement and a transfer proof from `pk_id1` to `pk_id2` as inputs,
+        /// verifies the proof against the chain state as `transfer` does, and emits an event that denotes the
+        /// verification status. Nothing else is written to storage.
+		#[pallet::weight(T::WeightInfo::verify_transfer())]
+		pub fn verify_transfer(origin: OriginFor<T>, pk_id1: PublicKeyId, pk_id2: PublicKeyId, statement: Vec<u8>, proof: Vec<u8>) -> DispatchResult {
             // Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			// https://docs.substrate.io/v3/runtime/origins
 			let sender = ensure_signed(origin)?;
+			pallet_faceless_host::enable();
 
-            let bs = TransferStatement::try_from_slice(base64::decode(statement.as_slice()).unwrap().as_slice()).unwrap();
-            let bp = TransferProof::try_from_slice(base64::decode(proof.as_slice()).unwrap().as_slice()).unwrap();
+			Self::verified_transfer(&pk_id1, &pk_id2, &statement, &proof)?;
+			Self::deposit_event(Event::TransferVerificationSuccess(sender, proof));
+			Ok(())
+		}
+	}
 
-            let result = TransferVerifier::verify_proof(bs, bp);
+	impl<T: Config> Pallet<T> {
+		/// Whether an account is registered.
+		pub fn is_registered(pk_id: &PublicKeyId) -> bool {
+			Accounts::<T>::contains_key(pk_id)
+		}
 
-            match result {
-                Ok(()) => {
-                    Self::deposit_event(Event::TransferVerificationSuccess(sender, proof));
-                    Ok(())
-                },
-                Err(_) => {
-                    Err(Error::<T>::TransferVerificationFailure.into())
-                }
-            }
+		/// The spendable and pending balance of a registered account, as the next action of the
+		/// account in the current epoch sees them: if the account has not been rolled over in this
+		/// epoch yet, its pending balance is already added to its spendable balance. Nothing is
+		/// written to storage.
+		pub fn account_state(pk_id: &PublicKeyId) -> Option<(EncryptedBalance, Option<EncryptedBalance>)> {
+			let balance = Self::get_balance(pk_id)?;
+			let pending = Self::get_pending(pk_id);
+			if LastRollOver::<T>::get(pk_id) >= Self::current_epoch() {
+				return Some((balance, pending));
+			}
+
+			match pending {
+				Some(pending) => {
+					let balance = BFIbe::<ChaCha20Rng>::add_ciphers(&balance.to_cipher()?, &pending.to_cipher()?);
+					Some((EncryptedBalance::from_cipher(&balance), None))
+				},
+				None => Some((balance, None)),
+			}
+		}
+
+		/// The epoch that burn and transfer statements submitted in the current block must be made for.
+		pub fn epoch_index() -> u64 {
+			Self::current_epoch().unique_saturated_into()
+		}
+
+		/// Verify a base64 encoded burn statement and proof for withdrawing `amount` from `pk_id` to
+		/// `destination` against the current chain state, as `withdraw` does, without writing to storage.
+		pub fn check_burn(pk_id: &PublicKeyId, destination: &T::AccountId, amount: u32, statement: &[u8], proof: &[u8]) -> Result<(), VerificationError> {
+			pallet_faceless_host::enable();
+			Self::verified_burn(pk_id, destination, amount, statement, proof)?;
+			Ok(())
+		}
+
+		/// Verify a base64 encoded transfer statement and proof from `pk_id1` to `pk_id2` against the
+		/// current chain state, as `transfer` does, without writing to storage.
+		pub fn check_transfer(pk_id1: &PublicKeyId, pk_id2: &PublicKeyId, statement: &[u8], proof: &[u8]) -> Result<(), VerificationError> {
+			pallet_faceless_host::enable();
+			Self::verified_transfer(pk_id1, pk_id2, statement, proof)?;
+			Ok(())
+		}
+
+		/// Transfer confidential funds from `pk_id1` to `pk_id2` on behalf of `sender`, as `transfer`
+		/// does. Contracts call this through the chain extension of `pallet_faceless_chain_extension`.
+		pub fn do_transfer(sender: T::AccountId, pk_id1: PublicKeyId, pk_id2: PublicKeyId, statement: &[u8], proof: &[u8]) -> DispatchResult {
+			pallet_faceless_host::enable();
+			let ts = Self::verified_transfer(&pk_id1, &pk_id2, statement, proof)?;
+
+			// As for a withdrawal, both accounts are only rolled over once the proof is accepted.
+			Self::roll_over(&pk_id1)?;
+			Self::roll_over(&pk_id2)?;
+			Nonces::<T>::insert(Self::current_epoch(), Nonce::from_gt(&ts.u), ());
+			Accounts::<T>::insert(pk_id1, EncryptedBalance::from_cipher(&(ts.c1_tilde, ts.c2_tilde)));
+			Self::add_pending(&pk_id2, &(ts.c1, ts.c2_bar))?;
+
+			Self::deposit_event(Event::TransferSuccess(sender));
+
+			Ok(())
+		}
+
+		/// Decode a burn statement and proof for withdrawing `amount` from `pk_id` to `destination`,
+		/// bind the statement to the chain state and verify the proof, without writing to storage.
+		/// Returns the statement as it was verified.
+		fn verified_burn(pk_id: &PublicKeyId, destination: &T::AccountId, amount: u32, statement: &[u8], proof: &[u8]) -> Result<BurnStatement, Error<T>> {
+			let bs = Self::burn_statement(pk_id, amount, statement)?;
+			let bp: FullBurnProof = decode_base64(proof).ok_or(Error::<T>::InvalidProof)?;
+			ensure!(!Nonces::<T>::contains_key(Self::current_epoch(), Nonce::from_gt(&bs.u)), Error::<T>::NonceAlreadyUsed);
+
+			BurnVerifier::verify_full_proof(bs.clone(), bp, &destination.encode()).map_err(|_| Error::<T>::BurnVerificationFailure)?;
+			Ok(bs)
+		}
+
+		/// Decode a transfer statement and proof from `pk_id1` to `pk_id2`, bind the statement to the
+		/// chain state and verify the proof, without writing to storage. Returns the statement as it
+		/// was verified.
+		fn verified_transfer(pk_id1: &PublicKeyId, pk_id2: &PublicKeyId, statement: &[u8], proof: &[u8]) -> Result<TransferStatement, Error<T>> {
+			ensure!(pk_id1 != pk_id2, Error::<T>::SelfTransfer);
+
+			let ts = Self::transfer_statement(pk_id1, pk_id2, statement)?;
+			let tp: FullTransferProof = decode_base64(proof).ok_or(Error::<T>::InvalidProof)?;
+			ensure!(!Nonces::<T>::contains_key(Self::current_epoch(), Nonce::from_gt(&ts.u)), Error::<T>::NonceAlreadyUsed);
+
+			TransferVerifier::verify_full_proof(ts.clone(), tp).map_err(|_| Error::<T>::TransferVerificationFailure)?;
+			Ok(ts)
+		}
+
+		/// The current encrypted balance of a registered account.
+		fn balance(pk_id: &PublicKeyId) -> Result<CipherText, Error<T>> {
+			let balance = Self::get_balance(pk_id).ok_or(Error::<T>::AccountNotRegistered)?;
+			balance.to_cipher().ok_or(Error::<T>::InvalidBalance)
+		}
+
+		/// The balance the next action of a registered account in the current epoch spends from: if
+		/// the account has not been rolled over in this epoch yet, its pending balance is already
+		/// added. Nothing is written to storage.
+		fn spendable_balance(pk_id: &PublicKeyId) -> Result<CipherText, Error<T>> {
+			let balance = Self::balance(pk_id)?;
+			if LastRollOver::<T>::get(pk_id) >= Self::current_epoch() {
+				return Ok(balance);
+			}
+
+			match Self::get_pending(pk_id) {
+				Some(pending) => {
+					let pending = pending.to_cipher().ok_or(Error::<T>::InvalidBalance)?;
+					Ok(BFIbe::<ChaCha20Rng>::add_ciphers(&balance, &pending))
+				},
+				None => Ok(balance),
+			}
+		}
+
+		/// Decode a burn statement for withdrawing `amount` from `pk_id` and bind it to the chain
+		/// state: the remaining balance is always rebuilt from the spendable balance of the account
+		/// minus the encryption of `amount`, so the proof cannot be made against a balance the
+		/// account does not actually hold.
+		fn burn_statement(pk_id: &PublicKeyId, amount: u32, statement: &[u8]) -> Result<BurnStatement, Error<T>> {
+			let mut bs: BurnStatement = decode_base64(statement).ok_or(Error::<T>::InvalidStatement)?;
+			let balance = Self::spendable_balance(pk_id)?;
+
+			let deduction = pairing(G1::one(), G2::one()).pow(-u64_to_scalar(amount as u64));
+			bs.c1_id = balance.0;
+			bs.c2_id = balance.1 * deduction;
+			bs.epoch = Self::current_epoch().unique_saturated_into();
+			Ok(bs)
+		}
+
+		/// Decode a transfer statement from `pk_id1` to `pk_id2` and bind it to the chain state: the
+		/// receiver of the proof is always `pk_id2`, and the remaining balance of the sender is always
+		/// rebuilt from its spendable balance, so the proof cannot credit another account or be made
+		/// against a balance the sender does not actually hold. Nothing is written to storage.
+		fn transfer_statement(pk_id1: &PublicKeyId, pk_id2: &PublicKeyId, statement: &[u8]) -> Result<TransferStatement, Error<T>> {
+			let mut ts: TransferStatement = decode_base64(statement).ok_or(Error::<T>::InvalidStatement)?;
+
+			let balance1 = Self::spendable_balance(pk_id1)?;
+			ensure!(Self::is_registered(pk_id2), Error::<T>::AccountNotRegistered);
+
+			ts.pk_id_bar = pk_id2.to_gt().ok_or(Error::<T>::InvalidPublicKey)?;
+
+			ts.c1_tilde = balance1.0 - ts.c1;
+			ts.c2_tilde = balance1.1 * ts.c2.inverse().ok_or(Error::<T>::TransferVerificationFailure)?;
+			ts.epoch = Self::current_epoch().unique_saturated_into();
+			Ok(ts)
+		}
+
+		/// The epoch of a block.
+		fn epoch(block: T::BlockNumber) -> T::BlockNumber {
+			block.checked_div(&T::EpochLength::get()).unwrap_or(block)
+		}
+
+		/// The epoch of the current block.
+		pub(crate) fn current_epoch() -> T::BlockNumber {
+			Self::epoch(frame_system::Pallet::<T>::block_number())
 		}
+
+		/// Roll the pending balance of a registered account into its spendable balance, if this is
+		/// the first action of the account in the current epoch.
+		fn roll_over(pk_id: &PublicKeyId) -> Result<(), Error<T>> {
+			let balance = Self::balance(pk_id)?;
+			let epoch = Self::current_epoch();
+			if LastRollOver::<T>::get(pk_id) >= epoch {
+				return Ok(());
+			}
+
+			if let Some(pending) = Pending::<T>::take(pk_id) {
+				let pending = pending.to_cipher().ok_or(Error::<T>::InvalidBalance)?;
+				let balance = BFIbe::<ChaCha20Rng>::add_ciphers(&balance, &pending);
+				Accounts::<T>::insert(pk_id, EncryptedBalance::from_cipher(&balance));
+			}
+			LastRollOver::<T>::insert(pk_id, epoch);
+			Ok(())
+		}
+
+		/// Add an incoming ciphertext to the pending balance of an account.
+		fn add_pending(pk_id: &PublicKeyId, cipher: &CipherText) -> Result<(), Error<T>> {
+			let pending = match Self::get_pending(pk_id) {
+				Some(pending) => {
+					let pending = pending.to_cipher().ok_or(Error::<T>::InvalidBalance)?;
+					BFIbe::<ChaCha20Rng>::add_ciphers(&pending, cipher)
+				},
+				None => *cipher,
+			};
+			Pending::<T>::insert(pk_id, EncryptedBalance::from_cipher(&pending));
+			Ok(())
+		}
+	}
+
+	impl<T> From<Error<T>> for VerificationError {
+		fn from(error: Error<T>) -> Self {
+			match error {
+				Error::InvalidStatement => Self::InvalidStatement,
+				Error::InvalidProof => Self::InvalidProof,
+				Error::AccountNotRegistered => Self::AccountNotRegistered,
+				Error::NonceAlreadyUsed => Self::NonceAlreadyUsed,
+				_ => Self::Rejected,
+			}
+		}
+	}
+
+	/// Decode a base64 encoded Borsh value passed to an extrinsic.
+	fn decode_base64<V: BorshDeserialize>(input: &[u8]) -> Option<V> {
+		V::try_from_slice(&base64::decode(input).ok()?).ok()
 	}
 }
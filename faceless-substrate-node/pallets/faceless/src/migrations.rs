@@ -0,0 +1,99 @@
+//! Storage migrations of the faceless pallet.
+
+use super::*;
+use aibe::bf_ibe::{CipherText, Gt};
+use borsh::BorshDeserialize;
+use frame_support::{
+	pallet_prelude::*,
+	storage::migration::clear_storage_prefix,
+	traits::{GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+};
+use sp_std::vec::Vec;
+
+/// Version 1 stores accounts under their typed public keys, see `types.rs`.
+pub mod v1 {
+	use super::*;
+
+	/// The storage of version 0.
+	mod v0 {
+		use super::*;
+
+		/// Base64 encoded Borsh public keys `Gt`, mapped to base64 encoded Borsh balances `(G1, Gt)`.
+		/// Both are uncompressed.
+		#[frame_support::storage_alias]
+		pub type Accounts<T: Config> = StorageMap<Pallet<T>, Blake2_128Concat, Vec<u8>, Vec<u8>>;
+	}
+
+	/// Decode an account of version 0.
+	fn translate(pk_id: &[u8], balance: &[u8]) -> Option<(PublicKeyId, EncryptedBalance)> {
+		let pk_id = Gt::try_from_slice(&base64::decode(pk_id).ok()?).ok()?;
+		let balance = CipherText::try_from_slice(&base64::decode(balance).ok()?).ok()?;
+		Some((PublicKeyId::from_gt(&pk_id), EncryptedBalance::from_cipher(&balance)))
+	}
+
+	/// Translates the accounts of version 0 to the typed `Accounts` of version 1 and clears the
+	/// `Proofs` of version 0, which recorded verified proofs and are no longer stored.
+	///
+	/// Version 0 credited transfers directly to the balance of the receiver, so no account has a
+	/// pending balance, and every account is rolled over in the epoch of the upgrade, as if it had
+	/// just registered. Version 0 only stored accounts it could decode, so every account is
+	/// translated; `pre_upgrade` checks this before the upgrade.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 1 {
+				return T::DbWeight::get().reads(1);
+			}
+
+			let pallet = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+			let proofs = clear_storage_prefix(pallet, b"Proofs", b"", None, None).unique as u64;
+
+			// The accounts of both versions are under the same prefix, so the accounts of version 0
+			// are all removed before those of version 1 are inserted.
+			let accounts = v0::Accounts::<T>::drain().collect::<Vec<_>>();
+			let epoch = Pallet::<T>::current_epoch();
+			let mut translated = 0;
+			for (pk_id, balance) in accounts.iter() {
+				if let Some((pk_id, balance)) = translate(pk_id, balance) {
+					Accounts::<T>::insert(pk_id, balance);
+					LastRollOver::<T>::insert(pk_id, epoch);
+					translated += 1;
+				}
+			}
+			StorageVersion::new(1).put::<Pallet<T>>();
+
+			let removed = proofs + accounts.len() as u64;
+			T::DbWeight::get().reads_writes(removed + 1, removed + 2 * translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			if Pallet::<T>::on_chain_storage_version() >= 1 {
+				return Ok(None::<u32>.encode());
+			}
+
+			let accounts = v0::Accounts::<T>::iter().collect::<Vec<_>>();
+			ensure!(
+				accounts.iter().all(|(pk_id, balance)| translate(pk_id, balance).is_some()),
+				"an account of version 0 cannot be decoded"
+			);
+			Ok(Some(accounts.len() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			ensure!(Pallet::<T>::on_chain_storage_version() == 1, "the faceless pallet was not migrated to version 1");
+
+			let accounts = Option::<u32>::decode(&mut &state[..]).map_err(|_| "invalid state of pre_upgrade")?;
+			if let Some(accounts) = accounts {
+				ensure!(Accounts::<T>::iter().count() == accounts as usize, "an account of version 0 was not translated");
+				ensure!(
+					Accounts::<T>::iter_values().all(|balance| balance.to_cipher().is_some()),
+					"a translated balance cannot be decoded"
+				);
+			}
+			Ok(())
+		}
+	}
+}
@@ -1,5 +1,5 @@
 use crate as pallet_faceless;
-use frame_support::traits::{ConstU16, ConstU64};
+use frame_support::traits::{ConstU16, ConstU32, ConstU64};
 use frame_support::{parameter_types};
 use frame_system as system;
 use sp_core::H256;
@@ -70,6 +70,12 @@ impl pallet_balances::Config for Test {
 impl pallet_faceless::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
+	type EpochLength = ConstU64<10>;
+	type MaxRecipients = ConstU32<4>;
+	type MaxRingSize = ConstU32<4>;
+	type MaxBatchSize = ConstU32<4>;
+	type MaxNoncesPruned = ConstU32<2>;
+	type WeightInfo = ();
 }
 
 // Build genesis storage according to the mock runtime.
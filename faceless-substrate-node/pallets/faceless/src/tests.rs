@@ -1,13 +1,964 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
-
-
-#[test]
-fn test_register() {
-	new_test_ext().execute_with(|| {
-		// Dispatch a signed extrinsic.
-		assert_ok!(FacelessModule::register(RuntimeOrigin::signed(1), Vec::from("ONKpKGedbVyPXAznId3okhveVOfbZH/NcHuA9M1NfSUsbkYJoV6y+aTGpUmNyZXxzjkzFMe0y1p/LJaZyW/YAbqSdVTfWbbKmLrkjTPHfsWTqOSzyJaPmw4pJcYFTAcQmNI+EhSkuuSk/gEIo8SCyoPoBQ7nIDIyxNKNlQSW4hxKzI0a6sKTIy0br+qQGDnYEaFYwr5PbhkQYM9t3tWtJ71HcKczxmQQ9z725XaeCNJ3vfXLqrmrIYSNAejUWu8uKXUqcAQb3vnY5i+PFf981OmYx2ksbjzoat6xHIulyhSNLsxaGWWxLiv64PoSgjprRBqHgE9wVv5W6nhhyPhTF07DTiZ1P4oQysm2tbDk8XotkpPiSwtUIqqz+qt245sklgg+sRAcc1oqf3d6GcsQDpra7yn6oda/vZv7qmmVwC6MJB226CMxxn1+XaPkwmtBai+xESUg+GeINP3KA23SCufhIywNaCi6eH4jWMiIE5D4jx1klJIwevPnsj9gOeQQ")));
-		// // Read pallet storage and assert an expected result.
-		// assert_eq!(FacelessModule::accounts(), Some(42));
-	});
+use crate::{mock::*, Error, PublicKeyId};
+use frame_support::{assert_noop, assert_ok, assert_storage_noop};
+
+
+mod register {
+	use super::*;
+	use crate::Accounts;
+	use aibe::bf_ibe::{BFIbe, G1, Group};
+	use aibe::traits::IdentityBasedEncryption;
+	use aibe::utils::hash_to_g2;
+	use aibe::zk::register::{RegisterStatement, RegisterWitness, RegisterProver};
+	use borsh::ser::BorshSerialize;
+	use codec::Encode;
+	use rand_chacha::ChaCha20Rng;
+	use rand_chacha::rand_core::SeedableRng;
+
+	fn encode<T: BorshSerialize>(x: &T) -> Vec<u8> {
+		base64::encode(x.try_to_vec().unwrap()).into_bytes()
+	}
+
+	/// Returns a pk_id together with a statement and a proof for registering it by `account`.
+	fn setup(account: u64) -> (PublicKeyId, Vec<u8>, Vec<u8>) {
+		let rng = ChaCha20Rng::seed_from_u64(42);
+		let mut ibe = BFIbe::new(rng.clone());
+
+		let (msk, mpk) = ibe.generate_key();
+		let pk_id = BFIbe::<ChaCha20Rng>::pk_id(&mpk, "zico");
+
+		let statement = RegisterStatement {
+			y: mpk,
+			pk_id,
+		};
+		let witness = RegisterWitness {
+			h_id: hash_to_g2("zico".as_bytes()),
+			sk_id: ibe.extract("zico", &msk),
+		};
+		let proof = RegisterProver::new(rng).generate_proof(statement.clone(), witness, &account.encode());
+
+		(PublicKeyId::from_gt(&pk_id), encode(&statement), encode(&proof))
+	}
+
+	#[test]
+	fn test_register() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, statement, proof) = setup(1);
+
+			assert_ok!(FacelessModule::register(RuntimeOrigin::signed(1), pk_id, statement, proof));
+			assert!(Accounts::<Test>::get(pk_id).unwrap().to_cipher().unwrap() == (G1::one(), pk_id.to_gt().unwrap()));
+		});
+	}
+
+	#[test]
+	fn test_register_replayed_by_other_account() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, statement, proof) = setup(1);
+
+			assert_noop!(
+				FacelessModule::register(RuntimeOrigin::signed(2), pk_id, statement, proof),
+				Error::<Test>::RegisterVerificationFailure
+			);
+		});
+	}
+
+	#[test]
+	fn test_register_twice() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, statement, proof) = setup(1);
+
+			assert_ok!(FacelessModule::register(RuntimeOrigin::signed(1), pk_id, statement.clone(), proof.clone()));
+			assert_noop!(
+				FacelessModule::register(RuntimeOrigin::signed(1), pk_id, statement, proof),
+				Error::<Test>::AccountAlreadyRegistered
+			);
+		});
+	}
+
+	#[test]
+	fn test_register_invalid_pk_id() {
+		new_test_ext().execute_with(|| {
+			let (_, statement, proof) = setup(1);
+
+			assert_noop!(
+				FacelessModule::register(RuntimeOrigin::signed(1), PublicKeyId([0xff; 192]), statement, proof),
+				Error::<Test>::InvalidPublicKey
+			);
+		});
+	}
+}
+
+mod transfer {
+	use super::*;
+	use crate::{Accounts, EncryptedBalance, FacelessAccount, LastRollOver, Nonce, Nonces, NoncesToPrune, Pending, VerificationError};
+	use aibe::bf_ibe::{BFIbe, CipherText, G1, G2, Group, pairing};
+	use aibe::traits::IdentityBasedEncryption;
+	use aibe::utils::{u64_to_scalar, pedersen_commitment};
+	use aibe::zk::nonce;
+	use aibe::zk::transfer::{TransferStatement, TransferWitness, TransferProver};
+	use borsh::ser::BorshSerialize;
+	use frame_support::traits::{Currency, Hooks};
+	use frame_support::BoundedVec;
+	use rand_chacha::ChaCha20Rng;
+	use rand_chacha::rand_core::SeedableRng;
+
+	fn encode<T: BorshSerialize>(x: &T) -> Vec<u8> {
+		base64::encode(x.try_to_vec().unwrap()).into_bytes()
+	}
+
+	fn encrypted(cipher: &CipherText) -> EncryptedBalance {
+		EncryptedBalance::from_cipher(cipher)
+	}
+
+	/// Sets up two registered accounts, where the first one holds an encrypted balance of 60,
+	/// and returns the pk_ids together with a statement and proof for transferring 40 from the
+	/// first account to the second one in `epoch`.
+	fn setup_in_epoch(epoch: u64) -> (PublicKeyId, PublicKeyId, TransferStatement, Vec<u8>) {
+		let mut rng = ChaCha20Rng::seed_from_u64(42);
+		let mut ibe = BFIbe::new(rng.clone());
+
+		let (msk1, mpk1) = ibe.generate_key();
+		let (_, mpk2) = ibe.generate_key();
+		let sk1 = ibe.extract("zico1", &msk1);
+
+		let pk_id1 = PublicKeyId::from_gt(&BFIbe::<ChaCha20Rng>::pk_id(&mpk1, "zico1"));
+		let pk_id2 = PublicKeyId::from_gt(&BFIbe::<ChaCha20Rng>::pk_id(&mpk2, "zico2"));
+
+		let b = u64_to_scalar(60);
+		let b_star = u64_to_scalar(40);
+		let b_prime = b - b_star;
+
+		let c_balance = ibe.encrypt(&b, "zico1", &mpk1);
+		Accounts::<Test>::insert(pk_id1, encrypted(&c_balance));
+		Accounts::<Test>::insert(pk_id2, encrypted(&(G1::one(), BFIbe::<ChaCha20Rng>::pk_id(&mpk2, "zico2"))));
+
+		let ((c_transfer, c_transfer_bar), (h_id, h_id_bar), r) =
+			ibe.encrypt_correlated_internal(&b_star, ("zico1", "zico2"), (&mpk1, &mpk2));
+
+		let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng);
+		let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+		let statement = TransferStatement {
+			y: mpk1,
+			y_bar: mpk2,
+			pk_id_bar: pk_id2.to_gt().unwrap(),
+			c1: c_transfer.0,
+			c2: c_transfer.1,
+			c2_bar: c_transfer_bar.1,
+			c1_tilde: c_balance.0 - c_transfer.0,
+			c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
+			c_b_star,
+			c_b_prime,
+			epoch,
+			u: nonce(&sk1, epoch),
+		};
+		let witness = TransferWitness {
+			r,
+			s: msk1,
+			r_star,
+			r_prime,
+			b_star,
+			b_prime,
+			h_id,
+			h_id_bar,
+			sk_id: sk1,
+		};
+
+		let proof = TransferProver::new(rng).generate_full_proof(statement.clone(), witness).unwrap();
+
+		(pk_id1, pk_id2, statement, encode(&proof))
+	}
+
+	fn setup() -> (PublicKeyId, PublicKeyId, TransferStatement, Vec<u8>) {
+		setup_in_epoch(0)
+	}
+
+	fn balance(pk_id: &PublicKeyId) -> CipherText {
+		Accounts::<Test>::get(pk_id).unwrap().to_cipher().unwrap()
+	}
+
+	fn pending(pk_id: &PublicKeyId) -> Option<CipherText> {
+		Pending::<Test>::get(pk_id).map(|pending| pending.to_cipher().unwrap())
+	}
+
+	#[test]
+	fn test_transfer() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup();
+			let before1 = balance(&pk_id1);
+			let before2 = balance(&pk_id2);
+
+			assert_ok!(FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof));
+
+			assert!(balance(&pk_id1) == (statement.c1_tilde, statement.c2_tilde));
+			assert!(balance(&pk_id1) == (before1.0 - statement.c1, before1.1 * statement.c2.inverse().unwrap()));
+			// The receiver only gets the transferred amount as pending balance.
+			assert!(balance(&pk_id2) == before2);
+			assert!(pending(&pk_id2) == Some((statement.c1, statement.c2_bar)));
+		});
+	}
+
+	#[test]
+	fn test_transfer_ignores_submitted_remaining_balance() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, mut statement, proof) = setup();
+			let expected = (statement.c1_tilde, statement.c2_tilde);
+
+			// A forged remaining balance in the statement is replaced by the one derived from storage.
+			statement.c2_tilde = statement.c2_tilde * statement.c2;
+			assert_ok!(FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof));
+			assert!(balance(&pk_id1) == expected);
+		});
+	}
+
+	#[test]
+	fn test_transfer_rejects_proof_for_other_balance() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup();
+			let before1 = balance(&pk_id1);
+
+			// The stored balance changes after the proof was generated.
+			Accounts::<Test>::insert(pk_id1, encrypted(&(before1.0, before1.1 * statement.c2)));
+			assert_noop!(
+				FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof),
+				Error::<Test>::TransferVerificationFailure
+			);
+		});
+	}
+
+	#[test]
+	fn test_transfer_rejects_other_receiver() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup();
+			let other = PublicKeyId::from_gt(&(pk_id2.to_gt().unwrap() * pk_id2.to_gt().unwrap()));
+			Accounts::<Test>::insert(other, Accounts::<Test>::get(pk_id2).unwrap());
+
+			// The proof pays the identity of pk_id2, so it cannot credit another account.
+			assert_noop!(
+				FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, other, encode(&statement), proof.clone()),
+				Error::<Test>::TransferVerificationFailure
+			);
+			let batch = BoundedVec::try_from(vec![(pk_id1, other, encode(&statement), proof)]).unwrap();
+			assert_noop!(
+				FacelessModule::transfer_batch(RuntimeOrigin::signed(1), batch),
+				Error::<Test>::TransferVerificationFailure
+			);
+		});
+	}
+
+	#[test]
+	fn test_transfer_after_incoming_deposit() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup();
+			FacelessAccount::<Test>::put(99);
+			Balances::make_free_balance_be(&99, 100);
+			Balances::make_free_balance_be(&3, 100);
+
+			// A deposit to the sender that lands before the transfer does not invalidate its proof.
+			assert_ok!(FacelessModule::deposit(RuntimeOrigin::signed(3), pk_id1, 10));
+			assert_ok!(FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof));
+
+			assert!(balance(&pk_id1) == (statement.c1_tilde, statement.c2_tilde));
+			assert!(pending(&pk_id1) == Some((G1::zero(), pairing(G1::one(), G2::one()).pow(u64_to_scalar(10)))));
+		});
+	}
+
+	#[test]
+	fn test_transfer_rolls_over_pending_in_next_epoch() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup_in_epoch(1);
+			let before1 = balance(&pk_id1);
+			System::set_block_number(10);
+
+			// Part of the balance the proof was made against is still pending.
+			Accounts::<Test>::insert(pk_id1, encrypted(&(before1.0 - statement.c1, before1.1 * statement.c2.inverse().unwrap())));
+			Pending::<Test>::insert(pk_id1, encrypted(&(statement.c1, statement.c2)));
+
+			// It is not spendable if the account was already rolled over in this epoch.
+			LastRollOver::<Test>::insert(pk_id1, 1);
+			assert_noop!(
+				FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof.clone()),
+				Error::<Test>::TransferVerificationFailure
+			);
+
+			// It becomes spendable with the first action of the account in the epoch.
+			LastRollOver::<Test>::insert(pk_id1, 0);
+			assert_ok!(FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof));
+			assert!(balance(&pk_id1) == (statement.c1_tilde, statement.c2_tilde));
+			assert!(pending(&pk_id1).is_none());
+		});
+	}
+
+	#[test]
+	fn test_transfer_replay() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup();
+
+			assert_ok!(FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof.clone()));
+			assert!(Nonces::<Test>::contains_key(0, Nonce::from_gt(&statement.u)));
+
+			// Restore the balance, so that only the used nonce rejects the proof.
+			let c_balance = (statement.c1_tilde + statement.c1, statement.c2_tilde * statement.c2);
+			Accounts::<Test>::insert(pk_id1, encrypted(&c_balance));
+			assert_noop!(
+				FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof),
+				Error::<Test>::NonceAlreadyUsed
+			);
+		});
+	}
+
+	#[test]
+	fn test_transfer_rejects_proof_of_other_epoch() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup_in_epoch(1);
+
+			assert_noop!(
+				FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof),
+				Error::<Test>::TransferVerificationFailure
+			);
+		});
+	}
+
+	#[test]
+	fn test_nonces_pruned_in_next_epoch() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup();
+			assert_ok!(FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof));
+
+			FacelessModule::on_initialize(9);
+			assert!(Nonces::<Test>::contains_key(0, Nonce::from_gt(&statement.u)));
+			FacelessModule::on_initialize(10);
+			assert!(!Nonces::<Test>::contains_key(0, Nonce::from_gt(&statement.u)));
+		});
+	}
+
+	#[test]
+	fn test_nonces_pruned_over_several_blocks() {
+		let mut ext = new_test_ext();
+		// Five nonces in epoch 0 and one in epoch 1, of which at most two are pruned per block.
+		ext.execute_with(|| {
+			for i in 0..5 {
+				Nonces::<Test>::insert(0, Nonce([i; 192]), ());
+			}
+			Nonces::<Test>::insert(1, Nonce([5; 192]), ());
+		});
+		// Commit the nonces, as blocks do, so that they are pruned from the backend.
+		ext.commit_all().unwrap();
+
+		let remaining = |ext: &mut sp_io::TestExternalities| {
+			ext.execute_with(|| (Nonces::<Test>::iter_prefix(0).count(), Nonces::<Test>::iter_prefix(1).count()))
+		};
+		let mut pruned = Vec::new();
+		for block in 20..25 {
+			ext.execute_with(|| FacelessModule::on_initialize(block));
+			ext.commit_all().unwrap();
+			pruned.push(remaining(&mut ext));
+		}
+		// The nonces of an epoch are pruned before those of the next one.
+		assert_eq!(pruned, vec![(3, 1), (1, 1), (0, 1), (0, 0), (0, 0)]);
+		ext.execute_with(|| assert_eq!(NoncesToPrune::<Test>::get(), 2));
+	}
+
+	#[test]
+	fn test_transfer_to_self() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, _, statement, proof) = setup();
+			assert_noop!(
+				FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id1, encode(&statement), proof),
+				Error::<Test>::SelfTransfer
+			);
+		});
+	}
+
+	#[test]
+	fn test_account_state() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup();
+			assert!(FacelessModule::is_registered(&pk_id2));
+			assert_ok!(FacelessModule::transfer(RuntimeOrigin::signed(1), pk_id1, pk_id2, encode(&statement), proof));
+			let before2 = balance(&pk_id2);
+
+			// Incoming funds stay pending for the rest of the epoch.
+			let (b, p) = FacelessModule::account_state(&pk_id2).unwrap();
+			assert!(b.to_cipher().unwrap() == before2);
+			assert!(p.unwrap().to_cipher().unwrap() == (statement.c1, statement.c2_bar));
+
+			// In the next epoch they are part of the balance, without rolling over the account.
+			System::set_block_number(10);
+			let (b, p) = FacelessModule::account_state(&pk_id2).unwrap();
+			assert!(b.to_cipher().unwrap() == (before2.0 + statement.c1, before2.1 * statement.c2_bar));
+			assert!(p.is_none());
+			assert!(balance(&pk_id2) == before2);
+			assert_eq!(FacelessModule::epoch_index(), 1);
+		});
+	}
+
+	#[test]
+	fn test_check_transfer() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, mut statement, proof) = setup();
+			let unregistered = PublicKeyId::from_gt(&(pk_id2.to_gt().unwrap() * pk_id2.to_gt().unwrap()));
+
+			assert_eq!(FacelessModule::check_transfer(&pk_id1, &pk_id2, &encode(&statement), &proof), Ok(()));
+			assert_eq!(FacelessModule::check_transfer(&pk_id1, &pk_id2, b"not a statement", &proof), Err(VerificationError::InvalidStatement));
+			assert_eq!(FacelessModule::check_transfer(&pk_id1, &pk_id2, &encode(&statement), b"not a proof"), Err(VerificationError::InvalidProof));
+			assert_eq!(FacelessModule::check_transfer(&pk_id1, &unregistered, &encode(&statement), &proof), Err(VerificationError::AccountNotRegistered));
+			assert_eq!(FacelessModule::check_transfer(&pk_id1, &pk_id1, &encode(&statement), &proof), Err(VerificationError::Rejected));
+
+			statement.c2_bar = statement.c2_bar * statement.c2;
+			assert_eq!(FacelessModule::check_transfer(&pk_id1, &pk_id2, &encode(&statement), &proof), Err(VerificationError::Rejected));
+		});
+	}
+
+	#[test]
+	fn test_check_transfer_against_chain_state() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup();
+			let before1 = balance(&pk_id1);
+
+			// The stored balance changes after the proof was generated.
+			Accounts::<Test>::insert(pk_id1, encrypted(&(before1.0, before1.1 * statement.c2)));
+			assert_eq!(FacelessModule::check_transfer(&pk_id1, &pk_id2, &encode(&statement), &proof), Err(VerificationError::Rejected));
+
+			Accounts::<Test>::insert(pk_id1, encrypted(&before1));
+			Nonces::<Test>::insert(0, Nonce::from_gt(&statement.u), ());
+			assert_eq!(FacelessModule::check_transfer(&pk_id1, &pk_id2, &encode(&statement), &proof), Err(VerificationError::NonceAlreadyUsed));
+		});
+	}
+
+	#[test]
+	fn test_check_transfer_does_not_roll_over() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup_in_epoch(1);
+			let before1 = balance(&pk_id1);
+			System::set_block_number(10);
+
+			// The proof spends funds that are still pending, which is only accepted in a new epoch.
+			Accounts::<Test>::insert(pk_id1, encrypted(&(before1.0 - statement.c1, before1.1 * statement.c2.inverse().unwrap())));
+			Pending::<Test>::insert(pk_id1, encrypted(&(statement.c1, statement.c2)));
+
+			assert_storage_noop!(
+				assert_eq!(FacelessModule::check_transfer(&pk_id1, &pk_id2, &encode(&statement), &proof), Ok(()))
+			);
+		});
+	}
+
+	#[test]
+	fn test_transfer_batch() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup();
+			let before2 = balance(&pk_id2);
+
+			let batch = BoundedVec::try_from(vec![(pk_id1, pk_id2, encode(&statement), proof)]).unwrap();
+			assert_ok!(FacelessModule::transfer_batch(RuntimeOrigin::signed(1), batch));
+
+			assert!(balance(&pk_id1) == (statement.c1_tilde, statement.c2_tilde));
+			assert!(balance(&pk_id2) == before2);
+			assert!(pending(&pk_id2) == Some((statement.c1, statement.c2_bar)));
+		});
+	}
+
+	#[test]
+	fn test_transfer_batch_replay() {
+		new_test_ext().execute_with(|| {
+			let (pk_id1, pk_id2, statement, proof) = setup();
+
+			// The same transfer cannot be applied twice within one batch.
+			let transfer = (pk_id1, pk_id2, encode(&statement), proof);
+			let batch = BoundedVec::try_from(vec![transfer.clone(), transfer]).unwrap();
+			assert_noop!(
+				FacelessModule::transfer_batch(RuntimeOrigin::signed(1), batch),
+				Error::<Test>::NonceAlreadyUsed
+			);
+		});
+	}
+}
+
+mod withdraw {
+	use super::*;
+	use crate::{Accounts, EncryptedBalance, FacelessAccount, Nonce, Nonces, VerificationError};
+	use aibe::bf_ibe::{BFIbe, CipherText, G1, G2, Fr, Group, pairing};
+	use aibe::traits::IdentityBasedEncryption;
+	use aibe::utils::{u64_to_scalar, hash_to_g2};
+	use aibe::zk::nonce;
+	use aibe::zk::burn::{BurnStatement, BurnWitness, BurnProver};
+	use borsh::ser::BorshSerialize;
+	use bulletproofs::PedersenGens;
+	use codec::Encode;
+	use frame_support::traits::Currency;
+	use rand_chacha::ChaCha20Rng;
+	use rand_chacha::rand_core::SeedableRng;
+
+	fn encode<T: BorshSerialize>(x: &T) -> Vec<u8> {
+		base64::encode(x.try_to_vec().unwrap()).into_bytes()
+	}
+
+	fn encrypted(cipher: &CipherText) -> EncryptedBalance {
+		EncryptedBalance::from_cipher(cipher)
+	}
+
+	/// Sets up a registered account holding an encrypted balance of 60, funds the pallet account,
+	/// and returns the pk_id together with a statement and proof for withdrawing 25 to account 2.
+	fn setup() -> (PublicKeyId, BurnStatement, Vec<u8>) {
+		let mut rng = ChaCha20Rng::seed_from_u64(42);
+		let mut ibe = BFIbe::new(rng.clone());
+
+		let (msk, mpk) = ibe.generate_key();
+		let sk = ibe.extract("zico", &msk);
+		let pk_id = PublicKeyId::from_gt(&BFIbe::<ChaCha20Rng>::pk_id(&mpk, "zico"));
+
+		let balance = ibe.encrypt(&u64_to_scalar(60), "zico", &mpk);
+		Accounts::<Test>::insert(pk_id, encrypted(&balance));
+
+		FacelessAccount::<Test>::put(99);
+		Balances::make_free_balance_be(&99, 100);
+
+		let remaining = (balance.0, balance.1 * pairing(G1::one(), G2::one()).pow(-u64_to_scalar(25)));
+		let r_b = Fr::random(&mut rng);
+		let statement = BurnStatement {
+			y: mpk,
+			c1_id: remaining.0,
+			c2_id: remaining.1,
+			c_b: PedersenGens::default().commit(u64_to_scalar(35), r_b),
+			epoch: 0,
+			u: nonce(&sk, 0),
+		};
+		let witness = BurnWitness {
+			b: u64_to_scalar(35),
+			s: msk,
+			r_b,
+			h_id: hash_to_g2("zico".as_bytes()),
+			sk_id: sk,
+		};
+
+		let proof = BurnProver::new(rng).generate_full_proof(statement.clone(), witness, &2u64.encode()).unwrap();
+
+		(pk_id, statement, encode(&proof))
+	}
+
+	fn balance(pk_id: &PublicKeyId) -> CipherText {
+		Accounts::<Test>::get(pk_id).unwrap().to_cipher().unwrap()
+	}
+
+	#[test]
+	fn test_withdraw() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, statement, proof) = setup();
+
+			assert_ok!(FacelessModule::withdraw(RuntimeOrigin::signed(1), pk_id, 2, 25, encode(&statement), proof));
+
+			assert!(balance(&pk_id) == (statement.c1_id, statement.c2_id));
+			assert_eq!(Balances::free_balance(&2), 25);
+			assert_eq!(Balances::free_balance(&99), 75);
+		});
+	}
+
+	#[test]
+	fn test_withdraw_rejects_other_amount() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, statement, proof) = setup();
+
+			assert_noop!(
+				FacelessModule::withdraw(RuntimeOrigin::signed(1), pk_id, 2, 50, encode(&statement), proof),
+				Error::<Test>::BurnVerificationFailure
+			);
+		});
+	}
+
+	#[test]
+	fn test_withdraw_rejects_other_destination() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, statement, proof) = setup();
+
+			// A copy of the transaction cannot redirect the payout of the proof to another account.
+			assert_noop!(
+				FacelessModule::withdraw(RuntimeOrigin::signed(3), pk_id, 3, 25, encode(&statement), proof),
+				Error::<Test>::BurnVerificationFailure
+			);
+		});
+	}
+
+	#[test]
+	fn test_withdraw_rejects_malformed_statement() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, _, proof) = setup();
+
+			assert_noop!(
+				FacelessModule::withdraw(RuntimeOrigin::signed(1), pk_id, 2, 25, Vec::from("not a statement"), proof),
+				Error::<Test>::InvalidStatement
+			);
+		});
+	}
+
+	#[test]
+	fn test_check_burn() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, mut statement, proof) = setup();
+			let unregistered = PublicKeyId::from_gt(&(pk_id.to_gt().unwrap() * pk_id.to_gt().unwrap()));
+
+			assert_eq!(FacelessModule::check_burn(&pk_id, &2, 25, &encode(&statement), &proof), Ok(()));
+			assert_eq!(FacelessModule::check_burn(&pk_id, &2, 25, b"not a statement", &proof), Err(VerificationError::InvalidStatement));
+			assert_eq!(FacelessModule::check_burn(&pk_id, &2, 25, &encode(&statement), b"not a proof"), Err(VerificationError::InvalidProof));
+			assert_eq!(FacelessModule::check_burn(&unregistered, &2, 25, &encode(&statement), &proof), Err(VerificationError::AccountNotRegistered));
+			assert_eq!(FacelessModule::check_burn(&pk_id, &3, 25, &encode(&statement), &proof), Err(VerificationError::Rejected));
+			assert_eq!(FacelessModule::check_burn(&pk_id, &2, 50, &encode(&statement), &proof), Err(VerificationError::Rejected));
+
+			// A forged remaining balance in the statement is replaced by the one derived from storage.
+			statement.c2_id = statement.c2_id * statement.c2_id;
+			assert_eq!(FacelessModule::check_burn(&pk_id, &2, 25, &encode(&statement), &proof), Ok(()));
+		});
+	}
+
+	#[test]
+	fn test_check_burn_against_chain_state() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, statement, proof) = setup();
+			let before = balance(&pk_id);
+
+			// The stored balance changes after the proof was generated.
+			Accounts::<Test>::insert(pk_id, encrypted(&(before.0, before.1 * before.1)));
+			assert_eq!(FacelessModule::check_burn(&pk_id, &2, 25, &encode(&statement), &proof), Err(VerificationError::Rejected));
+
+			// A used nonce is reported without using it again.
+			Accounts::<Test>::insert(pk_id, encrypted(&before));
+			Nonces::<Test>::insert(0, Nonce::from_gt(&statement.u), ());
+			assert_storage_noop!(
+				assert_eq!(FacelessModule::check_burn(&pk_id, &2, 25, &encode(&statement), &proof), Err(VerificationError::NonceAlreadyUsed))
+			);
+		});
+	}
+}
+
+mod multi_transfer {
+	use super::*;
+	use crate::{Accounts, EncryptedBalance, Pending};
+	use aibe::bf_ibe::{BFIbe, CipherText, G1, Group};
+	use aibe::traits::IdentityBasedEncryption;
+	use aibe::utils::{u64_to_scalar, pedersen_commitment};
+	use aibe::zk::nonce;
+	use aibe::zk::multi_transfer::{MultiTransferStatement, MultiTransferWitness, MultiTransferProver};
+	use borsh::ser::BorshSerialize;
+	use frame_support::BoundedVec;
+	use rand_chacha::ChaCha20Rng;
+	use rand_chacha::rand_core::SeedableRng;
+
+	fn encode<T: BorshSerialize>(x: &T) -> Vec<u8> {
+		base64::encode(x.try_to_vec().unwrap()).into_bytes()
+	}
+
+	/// Sets up a registered account holding an encrypted balance of 60 and two registered
+	/// recipients, and returns the pk_ids together with a statement and proof for transferring
+	/// 10 and 20 to the recipients.
+	fn setup() -> (PublicKeyId, Vec<PublicKeyId>, MultiTransferStatement, Vec<u8>) {
+		let mut rng = ChaCha20Rng::seed_from_u64(42);
+		let mut ibe = BFIbe::new(rng.clone());
+
+		let (msk, mpk) = ibe.generate_key();
+		let sk = ibe.extract("zico", &msk);
+		let pk_id = PublicKeyId::from_gt(&BFIbe::<ChaCha20Rng>::pk_id(&mpk, "zico"));
+		let c_balance = ibe.encrypt(&u64_to_scalar(60), "zico", &mpk);
+		Accounts::<Test>::insert(pk_id, EncryptedBalance::from_cipher(&c_balance));
+
+		let (_, mpk1) = ibe.generate_key();
+		let (_, mpk2) = ibe.generate_key();
+		let mut recipients = Vec::new();
+		for (id, mpk) in [("zico1", &mpk1), ("zico2", &mpk2)] {
+			let pk = BFIbe::<ChaCha20Rng>::pk_id(mpk, id);
+			recipients.push(PublicKeyId::from_gt(&pk));
+			Accounts::<Test>::insert(PublicKeyId::from_gt(&pk), EncryptedBalance::from_cipher(&(G1::one(), pk)));
+		}
+
+		let b_stars = vec![u64_to_scalar(10), u64_to_scalar(20)];
+		let b_prime = u64_to_scalar(30);
+		let ((c_transfer, c_outputs), (h_id, h_id_bars), r) =
+			ibe.encrypt_multi_internal(&b_stars, ("zico", &mpk), &[("zico1", &mpk1), ("zico2", &mpk2)]);
+
+		let (r_stars, c_b_stars) = b_stars.iter().map(|b_star| pedersen_commitment(*b_star, &mut rng)).unzip();
+		let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+		let statement = MultiTransferStatement {
+			y: mpk,
+			y_bars: vec![mpk1, mpk2],
+			pk_id_bars: recipients.iter().map(|recipient| recipient.to_gt().unwrap()).collect(),
+			c1: c_transfer.0,
+			c2: c_transfer.1,
+			c2_bars: c_outputs.iter().map(|c| c.1).collect(),
+			c1_tilde: c_balance.0 - c_transfer.0,
+			c2_tilde: c_balance.1 * c_transfer.1.inverse().unwrap(),
+			c_b_stars,
+			c_b_prime,
+			epoch: 0,
+			u: nonce(&sk, 0),
+		};
+		let witness = MultiTransferWitness {
+			r,
+			s: msk,
+			r_stars,
+			r_prime,
+			b_stars,
+			b_prime,
+			h_id,
+			h_id_bars,
+			sk_id: sk,
+		};
+
+		let proof = MultiTransferProver::new(rng).generate_full_proof(statement.clone(), witness).unwrap();
+
+		(pk_id, recipients, statement, encode(&proof))
+	}
+
+	fn balance(pk_id: &PublicKeyId) -> CipherText {
+		Accounts::<Test>::get(pk_id).unwrap().to_cipher().unwrap()
+	}
+
+	#[test]
+	fn test_multi_transfer() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, recipients, statement, proof) = setup();
+
+			let bounded = BoundedVec::try_from(recipients.clone()).unwrap();
+			assert_ok!(FacelessModule::multi_transfer(RuntimeOrigin::signed(1), pk_id, bounded, encode(&statement), proof));
+
+			assert!(balance(&pk_id) == (statement.c1_tilde, statement.c2_tilde));
+			for (recipient, c2_bar) in recipients.iter().zip(statement.c2_bars.iter()) {
+				assert!(Pending::<Test>::get(recipient).unwrap().to_cipher().unwrap() == (statement.c1, *c2_bar));
+			}
+		});
+	}
+
+	#[test]
+	fn test_multi_transfer_to_other_recipients() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, recipients, statement, proof) = setup();
+
+			// The statement has two outputs, so it cannot pay a single recipient.
+			let bounded = BoundedVec::try_from(vec![recipients[0]]).unwrap();
+			assert_noop!(
+				FacelessModule::multi_transfer(RuntimeOrigin::signed(1), pk_id, bounded, encode(&statement), proof.clone()),
+				Error::<Test>::InvalidStatement
+			);
+
+			let bounded = BoundedVec::try_from(vec![recipients[0], recipients[0]]).unwrap();
+			assert_noop!(
+				FacelessModule::multi_transfer(RuntimeOrigin::signed(1), pk_id, bounded, encode(&statement), proof.clone()),
+				Error::<Test>::DuplicateRecipient
+			);
+
+			let bounded = BoundedVec::try_from(vec![recipients[0], pk_id]).unwrap();
+			assert_noop!(
+				FacelessModule::multi_transfer(RuntimeOrigin::signed(1), pk_id, bounded, encode(&statement), proof),
+				Error::<Test>::SelfTransfer
+			);
+		});
+	}
+
+	#[test]
+	fn test_multi_transfer_rejects_other_recipient_keys() {
+		new_test_ext().execute_with(|| {
+			let (pk_id, recipients, statement, proof) = setup();
+			let other = PublicKeyId::from_gt(&(recipients[1].to_gt().unwrap() * recipients[1].to_gt().unwrap()));
+			Accounts::<Test>::insert(other, Accounts::<Test>::get(recipients[1]).unwrap());
+
+			// Every output is encrypted under the identity of its recipient, so the proof can
+			// neither credit another registered account nor swap the outputs of the recipients.
+			for bounded in [vec![recipients[0], other], vec![recipients[1], recipients[0]]] {
+				let bounded = BoundedVec::try_from(bounded).unwrap();
+				assert_noop!(
+					FacelessModule::multi_transfer(RuntimeOrigin::signed(1), pk_id, bounded, encode(&statement), proof.clone()),
+					Error::<Test>::TransferVerificationFailure
+				);
+			}
+		});
+	}
+}
+
+mod ring_transfer {
+	use super::*;
+	use crate::{Accounts, EncryptedBalance, Pending};
+	use aibe::bf_ibe::{BFIbe, CipherText};
+	use aibe::traits::IdentityBasedEncryption;
+	use aibe::utils::{u64_to_scalar, pedersen_commitment};
+	use aibe::zk::nonce;
+	use aibe::zk::ring_transfer::{RingTransferStatement, RingTransferWitness, RingTransferProver};
+	use borsh::ser::BorshSerialize;
+	use frame_support::BoundedVec;
+	use rand_chacha::ChaCha20Rng;
+	use rand_chacha::rand_core::SeedableRng;
+
+	fn encode<T: BorshSerialize>(x: &T) -> Vec<u8> {
+		base64::encode(x.try_to_vec().unwrap()).into_bytes()
+	}
+
+	/// Sets up a ring of three registered accounts holding an encrypted balance of 60 each, and
+	/// returns the ring together with a statement and proof for anonymously transferring 40 from
+	/// the first to the last member.
+	fn setup() -> (Vec<PublicKeyId>, RingTransferStatement, Vec<u8>) {
+		let mut rng = ChaCha20Rng::seed_from_u64(42);
+		let mut ibe = BFIbe::new(rng.clone());
+
+		let mut pk_ids = Vec::new();
+		let mut sks = Vec::new();
+		let mut balances = Vec::new();
+		for id in ["zico0", "zico1", "zico2"] {
+			let (msk, mpk) = ibe.generate_key();
+			let pk_id = BFIbe::<ChaCha20Rng>::pk_id(&mpk, id);
+			let c_balance = ibe.encrypt(&u64_to_scalar(60), id, &mpk);
+			Accounts::<Test>::insert(PublicKeyId::from_gt(&pk_id), EncryptedBalance::from_cipher(&c_balance));
+			pk_ids.push(pk_id);
+			sks.push(ibe.extract(id, &msk));
+			balances.push(c_balance);
+		}
+		let ring = pk_ids.iter().map(PublicKeyId::from_gt).collect();
+
+		let b_star = u64_to_scalar(40);
+		let b_prime = u64_to_scalar(20);
+		let ((c1, c2s), r) = ibe.encrypt_ring_internal(&b_star, &pk_ids, 0, 2);
+
+		let (r_star, c_b_star) = pedersen_commitment(b_star, &mut rng);
+		let (r_prime, c_b_prime) = pedersen_commitment(b_prime, &mut rng);
+
+		let statement = RingTransferStatement {
+			pk_ids,
+			balances,
+			c1,
+			c2s,
+			c_b_star,
+			c_b_prime,
+			epoch: 0,
+			u: nonce(&sks[0], 0),
+		};
+		let witness = RingTransferWitness {
+			sender: 0,
+			receiver: 2,
+			r,
+			r_star,
+			r_prime,
+			b_star,
+			b_prime,
+			sk_id: sks[0],
+		};
+
+		let proof = RingTransferProver::new(rng).generate_full_proof(statement.clone(), witness).unwrap();
+
+		(ring, statement, encode(&proof))
+	}
+
+	fn pending(pk_id: &PublicKeyId) -> CipherText {
+		Pending::<Test>::get(pk_id).unwrap().to_cipher().unwrap()
+	}
+
+	#[test]
+	fn test_ring_transfer() {
+		new_test_ext().execute_with(|| {
+			let (ring, statement, proof) = setup();
+			let before = ring.iter().map(|pk_id| Accounts::<Test>::get(pk_id).unwrap()).collect::<Vec<_>>();
+
+			let bounded = BoundedVec::try_from(ring.clone()).unwrap();
+			assert_ok!(FacelessModule::ring_transfer(RuntimeOrigin::signed(1), bounded.clone(), encode(&statement), proof.clone()));
+
+			// Every member is credited in its pending balance, the spendable balances are unchanged.
+			for (i, pk_id) in ring.iter().enumerate() {
+				assert!(pending(pk_id) == (statement.c1, statement.c2s[i]));
+				assert_eq!(Accounts::<Test>::get(pk_id).unwrap(), before[i]);
+			}
+
+			assert_noop!(
+				FacelessModule::ring_transfer(RuntimeOrigin::signed(1), bounded, encode(&statement), proof),
+				Error::<Test>::NonceAlreadyUsed
+			);
+		});
+	}
+
+	#[test]
+	fn test_ring_transfer_with_other_ring() {
+		new_test_ext().execute_with(|| {
+			let (ring, statement, proof) = setup();
+
+			let bounded = BoundedVec::try_from(vec![ring[0], ring[1], ring[0]]).unwrap();
+			assert_noop!(
+				FacelessModule::ring_transfer(RuntimeOrigin::signed(1), bounded, encode(&statement), proof.clone()),
+				Error::<Test>::DuplicateRingMember
+			);
+
+			let bounded = BoundedVec::try_from(vec![ring[0], ring[2]]).unwrap();
+			assert_noop!(
+				FacelessModule::ring_transfer(RuntimeOrigin::signed(1), bounded, encode(&statement), proof.clone()),
+				Error::<Test>::InvalidStatement
+			);
+
+			// The balances of the ring are taken from storage in the given order.
+			let bounded = BoundedVec::try_from(vec![ring[1], ring[0], ring[2]]).unwrap();
+			assert_noop!(
+				FacelessModule::ring_transfer(RuntimeOrigin::signed(1), bounded, encode(&statement), proof),
+				Error::<Test>::TransferVerificationFailure
+			);
+		});
+	}
+}
+
+mod migrations {
+	use super::*;
+	use crate::migrations::v1::MigrateToV1;
+	use crate::{Accounts, EncryptedBalance, LastRollOver, Pending};
+	use aibe::bf_ibe::{BFIbe, CipherText};
+	use aibe::traits::IdentityBasedEncryption;
+	use aibe::utils::u64_to_scalar;
+	use borsh::ser::BorshSerialize;
+	use codec::Encode;
+	use frame_support::storage::migration::{get_storage_value, put_storage_value};
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+	use frame_support::{Blake2_128Concat, StorageHasher};
+	use rand_chacha::ChaCha20Rng;
+	use rand_chacha::rand_core::SeedableRng;
+
+	/// Store an account as version 0 did: the base64 encoded, uncompressed Borsh public key mapped
+	/// to the base64 encoded, uncompressed Borsh balance.
+	fn put_v0_account(pk_id: &aibe::bf_ibe::Gt, balance: &CipherText) {
+		let key = base64::encode(pk_id.try_to_vec().unwrap()).into_bytes();
+		let value = base64::encode(balance.try_to_vec().unwrap()).into_bytes();
+		put_storage_value(b"FacelessModule", b"Accounts", &Blake2_128Concat::hash(&key.encode()), value);
+	}
+
+	#[test]
+	fn test_migrate_to_v1() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(25);
+			StorageVersion::new(0).put::<FacelessModule>();
+
+			// Two accounts and a proof as version 0 stored them.
+			let mut ibe = BFIbe::new(ChaCha20Rng::seed_from_u64(42));
+			let accounts = ["zico1", "zico2"].map(|id| {
+				let (msk, mpk) = ibe.generate_key();
+				let pk_id = BFIbe::<ChaCha20Rng>::pk_id(&mpk, id);
+				let balance = ibe.encrypt(&u64_to_scalar(60), id, &mpk);
+				put_v0_account(&pk_id, &balance);
+				(id, msk, pk_id, balance)
+			});
+			let proof = Blake2_128Concat::hash(&Vec::from("proof").encode());
+			put_storage_value(b"FacelessModule", b"Proofs", &proof, (1u64, 0u64));
+
+			MigrateToV1::<Test>::on_runtime_upgrade();
+			assert_eq!(FacelessModule::on_chain_storage_version(), 1);
+			assert_eq!(get_storage_value::<(u64, u64)>(b"FacelessModule", b"Proofs", &proof), None);
+
+			// The balances are kept, under the typed public keys, and still decrypt.
+			assert_eq!(Accounts::<Test>::iter().count(), 2);
+			for (id, msk, pk_id, balance) in accounts {
+				let pk_id = PublicKeyId::from_gt(&pk_id);
+				let stored = Accounts::<Test>::get(pk_id).unwrap();
+				assert!(stored == EncryptedBalance::from_cipher(&balance));
+				let sk = ibe.extract(id, &msk);
+				assert_eq!(ibe.decrypt_signed(&stored.to_cipher().unwrap(), id, &sk, 100), Ok(60));
+				assert_eq!(Pending::<Test>::get(pk_id), None);
+				assert_eq!(LastRollOver::<Test>::get(pk_id), 2);
+			}
+
+			// Accounts of version 1 are kept by a second run.
+			let pk_id = PublicKeyId([1; 192]);
+			let balance = EncryptedBalance { c1: [2; 33], c2: [3; 192] };
+			Accounts::<Test>::insert(pk_id, balance);
+			MigrateToV1::<Test>::on_runtime_upgrade();
+			assert_eq!(Accounts::<Test>::get(pk_id), Some(balance));
+			assert_eq!(Accounts::<Test>::iter().count(), 3);
+		});
+	}
 }
@@ -0,0 +1,68 @@
+//! Typed values kept in the pallet's storage.
+//!
+//! Group elements are stored with their compressed encodings from `bn`, so every value has a
+//! fixed size and implements `MaxEncodedLen`.
+
+use aibe::bf_ibe::{CipherText, G1, Gt};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::RuntimeDebug;
+use scale_info::TypeInfo;
+
+/// The public key `e(Y, H(id))` of an identity, which also identifies its account.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug)]
+pub struct PublicKeyId(pub [u8; 192]);
+
+impl PublicKeyId {
+	pub fn from_gt(pk_id: &Gt) -> Self {
+		Self(pk_id.to_compressed())
+	}
+
+	/// Decompress the public key, or `None` if it is not an element of Gt.
+	pub fn to_gt(&self) -> Option<Gt> {
+		Gt::from_compressed(&self.0).ok()
+	}
+}
+
+/// The balance of an account, encrypted under its identity.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug)]
+pub struct EncryptedBalance {
+	pub c1: [u8; 33],
+	pub c2: [u8; 192],
+}
+
+impl EncryptedBalance {
+	pub fn from_cipher(cipher: &CipherText) -> Self {
+		Self { c1: cipher.0.to_compressed(), c2: cipher.1.to_compressed() }
+	}
+
+	/// Decompress the ciphertext, or `None` if either part is not a valid group element.
+	pub fn to_cipher(&self) -> Option<CipherText> {
+		Some((G1::from_compressed(&self.c1).ok()?, Gt::from_compressed(&self.c2).ok()?))
+	}
+}
+
+/// The nonce `u` of a burn or transfer proof, see `aibe::zk::nonce`.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug)]
+pub struct Nonce(pub [u8; 192]);
+
+impl Nonce {
+	pub fn from_gt(u: &Gt) -> Self {
+		Self(u.to_compressed())
+	}
+}
+
+/// Why a proof was rejected by a dry-run verification, see `Pallet::check_transfer`.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo, RuntimeDebug)]
+pub enum VerificationError {
+	/// The statement is not base64 encoded Borsh.
+	InvalidStatement,
+	/// The proof is not base64 encoded Borsh.
+	InvalidProof,
+	/// An account of the statement is not registered.
+	AccountNotRegistered,
+	/// The nonce of the proof has already been used in this epoch.
+	NonceAlreadyUsed,
+	/// The proof does not verify against the statement rebuilt from the chain state, or the
+	/// action it proves is not allowed, e.g. a transfer to the sender itself.
+	Rejected,
+}
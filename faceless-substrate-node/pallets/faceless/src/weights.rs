@@ -0,0 +1,266 @@
+//! Weights for pallet_faceless
+//!
+//! PROVISIONAL: these weights are not the output of `benchmark pallet`. They have to be replaced by
+//! the output of `scripts/benchmark.sh` on reference hardware, which runs the benchmarks in
+//! `benchmarking.rs`, before the pallet is deployed. Until then:
+//!
+//! - the execution times are the native verification times printed by
+//!   `cargo run --release --bin verification_times` in `aibe` on a development machine, multiplied
+//!   by 1.5 and rounded. The runtime executes Wasm, in which the decoding of statements and proofs
+//!   and the work that does not go through the host functions are slower, so they underestimate the
+//!   cost of the calls;
+//! - `balance` is the native time of decompressing, adding and compressing the spendable and the
+//!   pending balance of an account, printed by the same binary, multiplied by 1.5;
+//! - the sizes of the batch calls are fitted by least squares to the times printed for every size;
+//! - the storage accesses are counted by hand from the calls and from `on_initialize`, which
+//!   prunes nonces.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_faceless.
+pub trait WeightInfo {
+	fn register() -> Weight;
+	fn deposit() -> Weight;
+	fn withdraw() -> Weight;
+	fn transfer() -> Weight;
+	fn transfer_batch(n: u32, ) -> Weight;
+	fn multi_transfer(n: u32, ) -> Weight;
+	fn ring_transfer(n: u32, ) -> Weight;
+	fn verify_burn() -> Weight;
+	fn verify_transfer() -> Weight;
+	fn balance() -> Weight;
+	fn on_initialize(n: u32, ) -> Weight;
+}
+
+/// Weights for pallet_faceless, derived as described above.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: Faceless Accounts (r:1 w:1)
+	// Storage: Faceless LastRollOver (r:0 w:1)
+	fn register() -> Weight {
+		Weight::from_ref_time(7_050_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: Faceless FacelessAccount (r:1 w:0)
+	// Storage: Faceless Accounts (r:1 w:1)
+	// Storage: Faceless LastRollOver (r:1 w:1)
+	// Storage: Faceless Pending (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn deposit() -> Weight {
+		Weight::from_ref_time(3_300_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	// Storage: Faceless FacelessAccount (r:1 w:0)
+	// Storage: Faceless Accounts (r:1 w:1)
+	// Storage: Faceless LastRollOver (r:1 w:1)
+	// Storage: Faceless Pending (r:1 w:1)
+	// Storage: Faceless Nonces (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn withdraw() -> Weight {
+		Weight::from_ref_time(30_150_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(7 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+	}
+	// Storage: Faceless Accounts (r:2 w:2)
+	// Storage: Faceless LastRollOver (r:2 w:2)
+	// Storage: Faceless Pending (r:2 w:2)
+	// Storage: Faceless Nonces (r:1 w:1)
+	fn transfer() -> Weight {
+		Weight::from_ref_time(66_750_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(7 as u64))
+			.saturating_add(T::DbWeight::get().writes(7 as u64))
+	}
+	// Storage: Faceless Nonces (r:1 w:1)
+	// Storage: Faceless Accounts (r:2 w:2)
+	// Storage: Faceless LastRollOver (r:2 w:2)
+	// Storage: Faceless Pending (r:2 w:2)
+	fn transfer_batch(n: u32, ) -> Weight {
+		Weight::from_ref_time(13_920_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(52_640_000_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads((7 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes((7 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: Faceless Accounts (r:1 w:1)
+	// Storage: Faceless LastRollOver (r:1 w:1)
+	// Storage: Faceless Pending (r:1 w:1)
+	// Storage: Faceless Nonces (r:1 w:1)
+	fn multi_transfer(n: u32, ) -> Weight {
+		Weight::from_ref_time(37_200_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(27_050_000_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+			.saturating_add(T::DbWeight::get().writes((3 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: Faceless Accounts (r:1 w:1)
+	// Storage: Faceless LastRollOver (r:1 w:1)
+	// Storage: Faceless Pending (r:1 w:1)
+	// Storage: Faceless Nonces (r:1 w:1)
+	/// The proof has a branch for every ordered pair of members, `n * (n - 1)`, each of which
+	/// raises a pairing to a power for every member, so it is fitted per branch and per branch
+	/// and member.
+	fn ring_transfer(n: u32, ) -> Weight {
+		let branches = (n as u64).saturating_mul((n as u64).saturating_sub(1));
+		Weight::from_ref_time(23_640_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(23_580_000_000 as u64).saturating_mul(branches))
+			.saturating_add(Weight::from_ref_time(4_670_000_000 as u64).saturating_mul(branches.saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((3 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: Faceless Accounts (r:1 w:0)
+	// Storage: Faceless LastRollOver (r:1 w:0)
+	// Storage: Faceless Pending (r:1 w:0)
+	// Storage: Faceless Nonces (r:1 w:0)
+	fn verify_burn() -> Weight {
+		Weight::from_ref_time(30_150_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+	}
+	// Storage: Faceless Accounts (r:2 w:0)
+	// Storage: Faceless LastRollOver (r:1 w:0)
+	// Storage: Faceless Pending (r:1 w:0)
+	// Storage: Faceless Nonces (r:1 w:0)
+	fn verify_transfer() -> Weight {
+		Weight::from_ref_time(66_750_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+	}
+	// Storage: Faceless Accounts (r:1 w:0)
+	// Storage: Faceless Pending (r:1 w:0)
+	// Storage: Faceless LastRollOver (r:1 w:0)
+	fn balance() -> Weight {
+		Weight::from_ref_time(4_200_000_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+	}
+	// Storage: Faceless NoncesToPrune (r:1 w:1)
+	// Storage: Faceless Nonces (r:1 w:1)
+	/// The nonces are removed by a single host call, which iterates the keys of the epoch in the
+	/// trie: every nonce removed costs a read and a write. Its execution time besides these is only
+	/// known from the `on_initialize` benchmark, which has not been run yet.
+	fn on_initialize(n: u32, ) -> Weight {
+		Weight::from_ref_time(0 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(n as u64)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: Faceless Accounts (r:1 w:1)
+	// Storage: Faceless LastRollOver (r:0 w:1)
+	fn register() -> Weight {
+		Weight::from_ref_time(7_050_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	// Storage: Faceless FacelessAccount (r:1 w:0)
+	// Storage: Faceless Accounts (r:1 w:1)
+	// Storage: Faceless LastRollOver (r:1 w:1)
+	// Storage: Faceless Pending (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn deposit() -> Weight {
+		Weight::from_ref_time(3_300_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+	// Storage: Faceless FacelessAccount (r:1 w:0)
+	// Storage: Faceless Accounts (r:1 w:1)
+	// Storage: Faceless LastRollOver (r:1 w:1)
+	// Storage: Faceless Pending (r:1 w:1)
+	// Storage: Faceless Nonces (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn withdraw() -> Weight {
+		Weight::from_ref_time(30_150_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(7 as u64))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+	}
+	// Storage: Faceless Accounts (r:2 w:2)
+	// Storage: Faceless LastRollOver (r:2 w:2)
+	// Storage: Faceless Pending (r:2 w:2)
+	// Storage: Faceless Nonces (r:1 w:1)
+	fn transfer() -> Weight {
+		Weight::from_ref_time(66_750_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(7 as u64))
+			.saturating_add(RocksDbWeight::get().writes(7 as u64))
+	}
+	// Storage: Faceless Nonces (r:1 w:1)
+	// Storage: Faceless Accounts (r:2 w:2)
+	// Storage: Faceless LastRollOver (r:2 w:2)
+	// Storage: Faceless Pending (r:2 w:2)
+	fn transfer_batch(n: u32, ) -> Weight {
+		Weight::from_ref_time(13_920_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(52_640_000_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads((7 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes((7 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: Faceless Accounts (r:1 w:1)
+	// Storage: Faceless LastRollOver (r:1 w:1)
+	// Storage: Faceless Pending (r:1 w:1)
+	// Storage: Faceless Nonces (r:1 w:1)
+	fn multi_transfer(n: u32, ) -> Weight {
+		Weight::from_ref_time(37_200_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(27_050_000_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes((3 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: Faceless Accounts (r:1 w:1)
+	// Storage: Faceless LastRollOver (r:1 w:1)
+	// Storage: Faceless Pending (r:1 w:1)
+	// Storage: Faceless Nonces (r:1 w:1)
+	fn ring_transfer(n: u32, ) -> Weight {
+		let branches = (n as u64).saturating_mul((n as u64).saturating_sub(1));
+		Weight::from_ref_time(23_640_000_000 as u64)
+			.saturating_add(Weight::from_ref_time(23_580_000_000 as u64).saturating_mul(branches))
+			.saturating_add(Weight::from_ref_time(4_670_000_000 as u64).saturating_mul(branches.saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((3 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: Faceless Accounts (r:1 w:0)
+	// Storage: Faceless LastRollOver (r:1 w:0)
+	// Storage: Faceless Pending (r:1 w:0)
+	// Storage: Faceless Nonces (r:1 w:0)
+	fn verify_burn() -> Weight {
+		Weight::from_ref_time(30_150_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+	}
+	// Storage: Faceless Accounts (r:2 w:0)
+	// Storage: Faceless LastRollOver (r:1 w:0)
+	// Storage: Faceless Pending (r:1 w:0)
+	// Storage: Faceless Nonces (r:1 w:0)
+	fn verify_transfer() -> Weight {
+		Weight::from_ref_time(66_750_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+	}
+	// Storage: Faceless Accounts (r:1 w:0)
+	// Storage: Faceless Pending (r:1 w:0)
+	// Storage: Faceless LastRollOver (r:1 w:0)
+	fn balance() -> Weight {
+		Weight::from_ref_time(4_200_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+	}
+	// Storage: Faceless NoncesToPrune (r:1 w:1)
+	// Storage: Faceless Nonces (r:1 w:1)
+	/// The nonces are removed by a single host call, which iterates the keys of the epoch in the
+	/// trie: every nonce removed costs a read and a write. Its execution time besides these is only
+	/// known from the `on_initialize` benchmark, which has not been run yet.
+	fn on_initialize(n: u32, ) -> Weight {
+		Weight::from_ref_time(0 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(n as u64)))
+	}
+}
@@ -41,6 +41,12 @@ pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
 pub use pallet_timestamp::Call as TimestampCall;
 use pallet_transaction_payment::CurrencyAdapter;
+use pallet_assets_chain_extension::substrate::AssetsExtension;
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RegisteredChainExtension, RetVal, SysConfig,
+};
+use pallet_faceless_chain_extension::FacelessExtension;
+use sp_core::crypto::UncheckedFrom;
 // use pallet_contracts::DefaultContractAccessWeight;
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
@@ -101,10 +107,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -367,9 +373,25 @@ impl pallet_assets::Config for Runtime {
 // }
 
 
+parameter_types! {
+	pub const FacelessEpochLength: BlockNumber = MINUTES;
+	pub const FacelessMaxRecipients: u32 = 16;
+	/// The verification of a ring transfer is cubic in the size of the ring, so rings of more than
+	/// five members do not fit in a block.
+	pub const FacelessMaxRingSize: u32 = 5;
+	pub const FacelessMaxBatchSize: u32 = 16;
+	pub const FacelessMaxNoncesPruned: u32 = 1_000;
+}
+
 impl pallet_faceless::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
+	type EpochLength = FacelessEpochLength;
+	type MaxRecipients = FacelessMaxRecipients;
+	type MaxRingSize = FacelessMaxRingSize;
+	type MaxBatchSize = FacelessMaxBatchSize;
+	type MaxNoncesPruned = FacelessMaxNoncesPruned;
+	type WeightInfo = pallet_faceless::weights::SubstrateWeight<Runtime>;
 }
 
 /*** Add a block similar to the following ***/
@@ -386,6 +408,25 @@ parameter_types! {
 	pub Schedule: pallet_contracts::Schedule<Runtime> = Default::default();
 }
 
+/// The chain extensions available to contracts: the faceless extension under its id, and the
+/// assets extension for every other id, so that existing contracts keep calling it unchanged.
+#[derive(Default)]
+pub struct ChainExtensions;
+
+impl ChainExtension<Runtime> for ChainExtensions {
+	fn call<E>(&mut self, env: Environment<E, InitState>) -> Result<RetVal, sp_runtime::DispatchError>
+	where
+		E: Ext<T = Runtime>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		if env.ext_id() == <FacelessExtension as RegisteredChainExtension<Runtime>>::ID {
+			FacelessExtension.call(env)
+		} else {
+			AssetsExtension::default().call(env)
+		}
+	}
+}
+
 impl pallet_contracts::Config for Runtime {
 	type Time = Timestamp;
 	type Randomness = RandomnessCollectiveFlip;
@@ -404,7 +445,7 @@ impl pallet_contracts::Config for Runtime {
 	type CallStack = [pallet_contracts::Frame<Self>; 31];
 	type WeightPrice = pallet_transaction_payment::Pallet<Self>;
 	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
-	type ChainExtension = pallet_assets_chain_extension::substrate::AssetsExtension;
+	type ChainExtension = ChainExtensions;
 	type DeletionQueueDepth = DeletionQueueDepth;
 	type DeletionWeightLimit = DeletionWeightLimit;
 	type Schedule = Schedule;
@@ -478,9 +519,14 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
-	pallet_contracts::Migration<Runtime>,
+	Migrations,
 >;
 
+/// The migrations to run on a runtime upgrade. The faceless pallet verifies proofs through the host
+/// functions of `pallet_faceless_host`, so every node has to register them before the upgrade is
+/// enacted, see the README of the node.
+type Migrations = (pallet_contracts::Migration<Runtime>, pallet_faceless::migrations::v1::MigrateToV1<Runtime>);
+
 #[cfg(feature = "runtime-benchmarks")]
 #[macro_use]
 extern crate frame_benchmarking;
@@ -492,6 +538,7 @@ mod benches {
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_balances, Balances]
 		[pallet_timestamp, Timestamp]
+		[pallet_faceless, Faceless]
 		// [pallet_template, TemplateModule]
 	);
 }
@@ -627,6 +674,43 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_faceless_runtime_api::FacelessApi<Block, AccountId> for Runtime {
+		fn balance(pk_id: pallet_faceless::PublicKeyId) -> Option<pallet_faceless::EncryptedBalance> {
+			Faceless::account_state(&pk_id).map(|(balance, _)| balance)
+		}
+
+		fn pending(pk_id: pallet_faceless::PublicKeyId) -> Option<pallet_faceless::EncryptedBalance> {
+			Faceless::account_state(&pk_id).and_then(|(_, pending)| pending)
+		}
+
+		fn is_registered(pk_id: pallet_faceless::PublicKeyId) -> bool {
+			Faceless::is_registered(&pk_id)
+		}
+
+		fn epoch() -> u64 {
+			Faceless::epoch_index()
+		}
+
+		fn verify_burn(
+			pk_id: pallet_faceless::PublicKeyId,
+			destination: AccountId,
+			amount: u32,
+			statement: Vec<u8>,
+			proof: Vec<u8>,
+		) -> Result<(), pallet_faceless::VerificationError> {
+			Faceless::check_burn(&pk_id, &destination, amount, &statement, &proof)
+		}
+
+		fn verify_transfer(
+			pk_id1: pallet_faceless::PublicKeyId,
+			pk_id2: pallet_faceless::PublicKeyId,
+			statement: Vec<u8>,
+			proof: Vec<u8>,
+		) -> Result<(), pallet_faceless::VerificationError> {
+			Faceless::check_transfer(&pk_id1, &pk_id2, &statement, &proof)
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -756,3 +840,26 @@ impl_runtime_apis! {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pallet_faceless::weights::WeightInfo;
+
+	type FacelessWeight = pallet_faceless::weights::SubstrateWeight<Runtime>;
+
+	#[test]
+	fn faceless_calls_of_maximal_size_fit_in_a_block() {
+		let max_extrinsic = RuntimeBlockWeights::get()
+			.get(DispatchClass::Normal)
+			.max_extrinsic
+			.unwrap()
+			.ref_time();
+
+		assert!(FacelessWeight::ring_transfer(FacelessMaxRingSize::get()).ref_time() <= max_extrinsic);
+		assert!(FacelessWeight::transfer_batch(FacelessMaxBatchSize::get()).ref_time() <= max_extrinsic);
+		assert!(FacelessWeight::multi_transfer(FacelessMaxRecipients::get()).ref_time() <= max_extrinsic);
+		// The ring is as large as it can be.
+		assert!(FacelessWeight::ring_transfer(FacelessMaxRingSize::get() + 1).ref_time() > max_extrinsic);
+	}
+}
@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+# This script is meant to be run on Unix/Linux based systems, on the reference hardware of the chain
+set -e
+
+echo "*** Benchmarking pallet_faceless ***"
+
+cd $(dirname ${BASH_SOURCE[0]})/..
+
+cargo build --release --features runtime-benchmarks
+
+./target/release/faceless-node benchmark pallet \
+	--chain=dev \
+	--execution=wasm \
+	--wasm-execution=compiled \
+	--pallet=pallet_faceless \
+	--extrinsic='*' \
+	--steps=50 \
+	--repeat=20 \
+	--output=pallets/faceless/src/weights.rs